@@ -0,0 +1,157 @@
+////////////////////////////////////////////////////////////////
+// Criterion benchmarks
+//
+// Single element put / get, the bulk slice paths and the
+// spsc / channel handoffs (in one thread and across two), so a
+// refactor of the index math shows up as a number instead of
+// a guess.
+//
+//   cargo bench
+//   cargo bench --features std     (adds the channel)
+//
+///////////////////////////////////////////////////////////////
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput, black_box};
+
+use staticfifo::{StaticFifoU8, StaticFifoU32, ByteFifo};
+use staticfifo::spsc::Queue;
+
+// elements moved per iteration.
+const BATCH : usize = 1024;
+
+fn single_element(c : &mut Criterion) {
+    let mut group = c.benchmark_group("single");
+    group.throughput(Throughput::Elements(BATCH as u64));
+
+    // small fifo so the pointers wrap all the time.
+    group.bench_function("u8 put/get", |b| {
+        let mut fifo : StaticFifoU8<64> = StaticFifoU8::new();
+        b.iter(|| {
+            for i in 0..BATCH {
+                let _ = fifo.put(black_box(i as u8));
+                black_box(fifo.get().ok());
+            }
+        })
+    });
+
+    group.bench_function("u32 put/get", |b| {
+        let mut fifo : StaticFifoU32<64> = StaticFifoU32::new();
+        b.iter(|| {
+            for i in 0..BATCH {
+                let _ = fifo.put(black_box(i as u32));
+                black_box(fifo.get().ok());
+            }
+        })
+    });
+
+    // fill then drain, len() stays high.
+    group.bench_function("u32 fill/drain", |b| {
+        let mut fifo : StaticFifoU32<{ BATCH + 1 }> = StaticFifoU32::new();
+        b.iter(|| {
+            for i in 0..BATCH {
+                let _ = fifo.put(black_box(i as u32));
+            }
+            while let Ok(v) = fifo.get() {
+                black_box(v);
+            }
+        })
+    });
+    group.finish();
+}
+
+fn bulk(c : &mut Criterion) {
+    let mut group = c.benchmark_group("bulk");
+    group.throughput(Throughput::Bytes(BATCH as u64));
+
+    let data = [0xA5u8; BATCH];
+    let mut out = [0u8; BATCH];
+
+    // the storage is not a multiple of the batch, so the copies split at the wrap.
+    group.bench_function("u8 put_slice/get_slice", |b| {
+        let mut fifo : StaticFifoU8<{ BATCH + 100 }> = StaticFifoU8::new();
+        b.iter(|| {
+            fifo.put_slice(black_box(&data));
+            black_box(fifo.get_slice(&mut out));
+        })
+    });
+
+    group.bench_function("u8 transfer_to", |b| {
+        let mut src : StaticFifoU8<{ BATCH + 100 }> = StaticFifoU8::new();
+        let mut dst : StaticFifoU8<{ BATCH + 100 }> = StaticFifoU8::new();
+        b.iter(|| {
+            src.put_slice(&data);
+            black_box(src.transfer_to(&mut dst, BATCH));
+            dst.skip(BATCH);
+        })
+    });
+    group.finish();
+}
+
+fn spsc(c : &mut Criterion) {
+    let mut group = c.benchmark_group("spsc");
+    group.throughput(Throughput::Elements(BATCH as u64));
+
+    group.bench_function("split enqueue/dequeue", |b| {
+        let mut q : Queue<u32, 64> = Queue::new();
+        let (mut p, mut c) = q.split();
+        b.iter(|| {
+            for i in 0..BATCH {
+                let _ = p.enqueue(black_box(i as u32));
+                black_box(c.dequeue());
+            }
+        })
+    });
+
+    // producer thread feeding the split queue, started once per batch of
+    // iterations so the spawn stays out of the numbers.
+    group.bench_function("split cross thread", |b| {
+        b.iter_custom(|iters| {
+            let mut q : Queue<u32, 64> = Queue::new();
+            let (mut p, mut c) = q.split();
+            let total = iters as usize * BATCH;
+            std::thread::scope(|s| {
+                let start = std::time::Instant::now();
+                s.spawn(move || {
+                    for i in 0..total {
+                        while p.enqueue(i as u32).is_err() {
+                            std::thread::yield_now();
+                        }
+                    }
+                });
+                let mut n = 0;
+                while n < total {
+                    match c.dequeue() {
+                        Some(v) => { black_box(v); n += 1; },
+                        None => std::thread::yield_now(),
+                    }
+                }
+                start.elapsed()
+            })
+        })
+    });
+
+    // the same through the blocking channel.
+    #[cfg(feature = "std")]
+    group.bench_function("channel cross thread", |b| {
+        b.iter_custom(|iters| {
+            let (tx, rx) = staticfifo::channel::channel(StaticFifoU32::<64>::new());
+            let total = iters as usize * BATCH;
+            let start = std::time::Instant::now();
+            let producer = std::thread::spawn(move || {
+                for i in 0..total {
+                    tx.send(i as u32).unwrap();
+                }
+            });
+            for _ in 0..total {
+                black_box(rx.recv().unwrap());
+            }
+            let rv = start.elapsed();
+            producer.join().unwrap();
+            rv
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, single_element, bulk, spsc);
+criterion_main!(benches);
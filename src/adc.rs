@@ -0,0 +1,116 @@
+////////////////////////////////////////////////////////////////
+// ADC sample stream
+//
+// Wrapper for a fifo fed from an ADC conversion complete
+// interrupt. push_sample() never blocks and never fails: when
+// the processing task has fallen behind, the policy decides
+// whether the new sample is dropped or the oldest one makes
+// way for it, and either way the overrun is counted. The task
+// takes samples in blocks with read_block().
+//
+// Shared between the ISR and the task the usual way, e.g. in
+// a BasepriFifo / LockedFifo, or with each side holding it
+// only inside a critical section.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::traits::{FifoProducer, FifoConsumer};
+
+// what push_sample() does with a full fifo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverrunPolicy {
+    // keep what is queued, lose the new sample
+    Drop,
+    // lose the oldest queued sample, keep the new one
+    Overwrite,
+}
+
+pub struct AdcStream<F> {
+    fifo: F,
+    policy: OverrunPolicy,
+    overruns: u32,
+}
+
+impl<F> AdcStream<F> {
+
+    pub fn new(fifo : F, policy : OverrunPolicy) -> Self {
+        AdcStream { fifo, policy, overruns: 0 }
+    }
+
+    // isr side. returns false if a sample was lost (the new one or the oldest).
+    pub fn push_sample<T>(&mut self, sample : T) -> bool where F : FifoProducer<T> + FifoConsumer<T> {
+        let full = self.fifo.is_full();
+        if full {
+            self.overruns = self.overruns.wrapping_add(1);
+            if self.policy == OverrunPolicy::Drop || self.fifo.get().is_err() {
+                return false;
+            }
+        }
+        self.fifo.put(sample).is_ok() && !full
+    }
+
+    // task side, fill out with the oldest samples. returns how many.
+    pub fn read_block<T>(&mut self, out : &mut [T]) -> usize where F : FifoConsumer<T> {
+        out.iter_mut().map_while(|slot| self.fifo.get().ok().map(|s| *slot = s)).count()
+    }
+
+    pub fn len<T>(&self) -> usize where F : FifoConsumer<T> {
+        self.fifo.len()
+    }
+
+    pub fn is_empty<T>(&self) -> bool where F : FifoConsumer<T> {
+        self.fifo.is_empty()
+    }
+
+    // samples lost since new() / the last reset.
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+
+    pub fn reset_overruns(&mut self) {
+        self.overruns = 0;
+    }
+
+    pub fn policy(&self) -> OverrunPolicy {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy : OverrunPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+    use crate::StaticFifoU32;
+
+    #[test]
+    fn overrun_policies() {
+        println!("##################### ADC STREAM TEST ######################################");
+
+        // 4 slots, 6 conversions before the task runs
+        let mut adc = AdcStream::new(StaticFifoU32::<5>::new(), OverrunPolicy::Drop);
+        let kept = (0..6).filter(|&s| adc.push_sample(s)).count();
+        assert!(kept == 4 && adc.overruns() == 2, "drop policy wrong");
+        let mut block = [0u32; 8];
+        assert!(adc.read_block(&mut block) == 4 && block[..4] == [0, 1, 2, 3], "oldest not kept");
+
+        adc.set_policy(OverrunPolicy::Overwrite);
+        adc.reset_overruns();
+        for s in 10..16 {
+            adc.push_sample(s);
+        }
+        assert!(adc.overruns() == 2, "overwrite overruns wrong");
+        let mut block = [0u32; 3];
+        assert!(adc.read_block(&mut block) == 3 && block == [12, 13, 14], "newest not kept");
+        assert!(adc.len() == 1, "block read took too much");
+    }
+}
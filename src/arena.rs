@@ -0,0 +1,173 @@
+////////////////////////////////////////////////////////////////
+// Arena backed message queue
+//
+// Variable length messages whose payloads sit in a byte arena
+// while the queue itself only carries a compact handle per
+// message: offset and length packed into one u32, kept in a
+// StaticFifoU32. Unlike FrameFifo a payload is never split
+// over the end of the storage; a message that does not fit in
+// the space left before the end goes to the start of the
+// arena instead (the tail is left unused until then), so
+// every message can be read or written in place as one slice
+// and nothing is padded to a fixed slot size.
+//
+// A is the arena size in bytes, at most 65535. N is the raw
+// handle storage, so N-1 messages at most.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU32, StaticFifoError};
+
+pub struct ArenaQueue<const A : usize, const N : usize> {
+    arena: [u8; A],
+    handles: StaticFifoU32<N>,
+    // where the next payload goes, one past the end of the newest
+    head: usize,
+}
+
+impl<const A : usize, const N : usize> Default for ArenaQueue<A, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const A : usize, const N : usize> ArenaQueue<A, N> {
+
+    const ARENA_OK : () = assert!(A <= 0xFFFF, "ArenaQueue arena is limited to 65535 bytes, offsets are 16 bit");
+
+    pub const fn new() -> Self {
+        let () = Self::ARENA_OK;
+        ArenaQueue { arena: [0; A], handles: StaticFifoU32::new(), head: 0 }
+    }
+
+    // messages queued.
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    // copy data in as a new message. Full if the arena or the handle fifo has no room,
+    // TooLarge if it could never fit.
+    pub fn push(&mut self, data : &[u8]) -> Result<(), StaticFifoError> {
+        self.push_with(data.len(), |buf| buf.copy_from_slice(data))
+    }
+
+    // reserve len bytes and let f write the message in place.
+    pub fn push_with<F : FnOnce(&mut [u8])>(&mut self, len : usize, f : F) -> Result<(), StaticFifoError> {
+        if len > A {
+            return Err(StaticFifoError::TooLarge);
+        }
+        if self.handles.is_full() {
+            return Err(StaticFifoError::Full);
+        }
+        let offset = self.place(len).ok_or(StaticFifoError::Full)?;
+        f(&mut self.arena[offset..(offset + len)]);
+        self.handles.put(((offset as u32) << 16) | len as u32)?;
+        self.head = offset + len;
+        Ok(())
+    }
+
+    // the oldest message, in place.
+    pub fn peek(&self) -> Option<&[u8]> {
+        let (offset, len) = self.front()?;
+        self.arena.get(offset..(offset + len))
+    }
+
+    // copy the oldest message into the front of out and remove it, returns its length.
+    // TooLarge and the message stays queued if out is too short.
+    pub fn pop(&mut self, out : &mut [u8]) -> Result<usize, StaticFifoError> {
+        let data = self.peek().ok_or(StaticFifoError::Empty)?;
+        let dst = out.get_mut(..data.len()).ok_or(StaticFifoError::TooLarge)?;
+        dst.copy_from_slice(data);
+        let len = data.len();
+        self.discard()?;
+        Ok(len)
+    }
+
+    // lend the oldest message to f and remove it, returns what f returned.
+    pub fn pop_with<R, F : FnOnce(&[u8]) -> R>(&mut self, f : F) -> Result<R, StaticFifoError> {
+        let rv = f(self.peek().ok_or(StaticFifoError::Empty)?);
+        self.discard()?;
+        Ok(rv)
+    }
+
+    // drop the oldest message, returns its length.
+    pub fn discard(&mut self) -> Result<usize, StaticFifoError> {
+        let (_, len) = self.front().ok_or(StaticFifoError::Empty)?;
+        self.handles.get()?;
+        if self.handles.is_empty() {
+            // nothing left, the whole arena is free again
+            self.head = 0;
+        }
+        Ok(len)
+    }
+
+    // (offset, len) of the oldest message.
+    fn front(&self) -> Option<(usize, usize)> {
+        self.handles.peek_at(0).ok().map(|h| ((h >> 16) as usize, (h & 0xFFFF) as usize))
+    }
+
+    // where a len byte payload can go, if anywhere.
+    fn place(&self, len : usize) -> Option<usize> {
+        let Some((tail, _)) = self.front() else {
+            return Some(0);
+        };
+        // the newest payload sits before the oldest once the messages wrapped
+        let newest = self.handles.peek_at(self.handles.len().wrapping_sub(1)).map_or(tail, |h| (h >> 16) as usize);
+        if newest >= tail {
+            // used [tail, head), free up to the end and again before tail
+            if self.head + len <= A {
+                Some(self.head)
+            } else if len <= tail {
+                Some(0)
+            } else {
+                None
+            }
+        } else {
+            // wrapped, free only [head, tail)
+            (self.head + len <= tail).then_some(self.head)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn variable_messages_in_arena() {
+        println!("##################### ARENA QUEUE TEST ######################################");
+
+        let mut q : ArenaQueue<16, 8> = ArenaQueue::new();
+        q.push(b"hello").unwrap();
+        q.push(b"big world").unwrap();
+        assert!(q.push(b"xyz") == Err(StaticFifoError::Full), "arena overfilled");
+        assert!(q.push(&[0; 17]) == Err(StaticFifoError::TooLarge), "oversize message accepted");
+
+        // freeing the front makes room at the start, the 2 byte tail stays unused
+        let mut out = [0u8; 4];
+        assert!(q.pop(&mut out) == Err(StaticFifoError::TooLarge) && q.len() == 2, "short buffer popped");
+        assert!(q.pop_with(|m| m == b"hello") == Ok(true), "first message wrong");
+        q.push_with(4, |buf| buf.copy_from_slice(b"wrap")).unwrap();
+        assert!(q.push(b"z") == Ok(()) && q.push(b"no") == Err(StaticFifoError::Full), "wrapped space wrong");
+
+        // every payload still one slice
+        assert!(q.peek() == Some(&b"big world"[..]), "peek wrong");
+        q.discard().unwrap();
+        assert!(q.pop(&mut out) == Ok(4) && out == *b"wrap", "wrapped message wrong");
+        assert!(q.pop_with(|m| m.to_vec()) == Ok(b"z".to_vec()) && q.is_empty(), "last message wrong");
+        q.push(&[7; 16]).unwrap();
+        assert!(q.peek().map(|m| m.len()) == Some(16), "empty arena not reset");
+
+        // empty messages take no arena space
+        q.discard().unwrap();
+        q.push(b"").unwrap();
+        assert!(q.push(&[1; 16]).is_ok() && q.pop_with(|m| m.is_empty()) == Ok(true), "empty message blocked the arena");
+    }
+}
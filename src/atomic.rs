@@ -0,0 +1,23 @@
+////////////////////////////////////////////////////////////////
+// Atomics
+//
+// The one place the lock-free parts (spsc, Mailbox,
+// BufferQueue) take their atomics from. core::sync::atomic by
+// default; with the portable-atomic feature the portable_atomic
+// crate instead, so thumbv6m (no CAS) and AVR (no native
+// atomics at all, no AtomicU32) build too. The index handoffs
+// only ever load and store, which portable_atomic does natively
+// on ARMv6-M and with interrupts masked on single core AVR.
+//
+///////////////////////////////////////////////////////////////
+
+#[cfg(not(feature = "portable-atomic"))]
+pub(crate) use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering, fence};
+// only the test stand ins (spin locks) need it
+#[cfg(all(test, not(feature = "portable-atomic")))]
+pub(crate) use core::sync::atomic::AtomicBool;
+
+#[cfg(feature = "portable-atomic")]
+pub(crate) use portable_atomic::{AtomicU32, AtomicUsize, Ordering, fence};
+#[cfg(all(test, feature = "portable-atomic"))]
+pub(crate) use portable_atomic::AtomicBool;
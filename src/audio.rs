@@ -0,0 +1,164 @@
+////////////////////////////////////////////////////////////////
+// Double buffered audio DMA
+//
+// For I2S / SAI / DAC streams running a DMA in circular mode
+// over one buffer of two halves. The DMA's half transfer and
+// transfer complete interrupts call on_half_complete() and
+// on_complete(); each handles the half the DMA just left:
+//
+//   AudioTx refills it from a fifo. Samples the fifo does not
+//   have are played as silence (T::default()) and the half
+//   counts as an underrun.
+//
+//   AudioRx moves the received samples into a fifo. Samples
+//   that do not fit are dropped and the half counts as an
+//   overrun.
+//
+// The DMA gets the buffer from dma_buffer(); the two halves
+// are one contiguous block, first half first.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::traits::{FifoProducer, FifoConsumer};
+
+// playback side, H samples per half.
+pub struct AudioTx<T, const H : usize> {
+    buf: [[T; H]; 2],
+    underruns: u32,
+}
+
+impl<T : Copy + Default, const H : usize> Default for AudioTx<T, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T : Copy + Default, const H : usize> AudioTx<T, H> {
+
+    // starts out silent.
+    pub fn new() -> Self {
+        AudioTx { buf: [[T::default(); H]; 2], underruns: 0 }
+    }
+
+    // the whole circular buffer, for the DMA source address / length.
+    pub fn dma_buffer(&mut self) -> &mut [[T; H]; 2] {
+        &mut self.buf
+    }
+
+    // the DMA is done with the first half, refill it.
+    pub fn on_half_complete<F : FifoConsumer<T>>(&mut self, fifo : &mut F) {
+        self.refill(0, fifo);
+    }
+
+    // the DMA is done with the second half and went back to the first, refill the second.
+    pub fn on_complete<F : FifoConsumer<T>>(&mut self, fifo : &mut F) {
+        self.refill(1, fifo);
+    }
+
+    // halves that had to be padded with silence.
+    pub fn underruns(&self) -> u32 {
+        self.underruns
+    }
+
+    pub fn reset_underruns(&mut self) {
+        self.underruns = 0;
+    }
+
+    fn refill<F : FifoConsumer<T>>(&mut self, half : usize, fifo : &mut F) {
+        let mut short = false;
+        for slot in self.buf[half].iter_mut() {
+            *slot = fifo.get().unwrap_or_else(|_| {
+                short = true;
+                T::default()
+            });
+        }
+        if short {
+            self.underruns = self.underruns.wrapping_add(1);
+        }
+    }
+}
+
+// capture side, H samples per half.
+pub struct AudioRx<T, const H : usize> {
+    buf: [[T; H]; 2],
+    overruns: u32,
+}
+
+impl<T : Copy + Default, const H : usize> Default for AudioRx<T, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T : Copy + Default, const H : usize> AudioRx<T, H> {
+
+    pub fn new() -> Self {
+        AudioRx { buf: [[T::default(); H]; 2], overruns: 0 }
+    }
+
+    // the whole circular buffer, for the DMA destination address / length.
+    pub fn dma_buffer(&mut self) -> &mut [[T; H]; 2] {
+        &mut self.buf
+    }
+
+    // the DMA filled the first half, move it out.
+    pub fn on_half_complete<F : FifoProducer<T>>(&mut self, fifo : &mut F) {
+        self.drain(0, fifo);
+    }
+
+    // the DMA filled the second half, move it out.
+    pub fn on_complete<F : FifoProducer<T>>(&mut self, fifo : &mut F) {
+        self.drain(1, fifo);
+    }
+
+    // halves that did not fit in the fifo completely.
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+
+    pub fn reset_overruns(&mut self) {
+        self.overruns = 0;
+    }
+
+    fn drain<F : FifoProducer<T>>(&mut self, half : usize, fifo : &mut F) {
+        let put = self.buf[half].iter().take_while(|&&s| fifo.put(s).is_ok()).count();
+        if put < H {
+            self.overruns = self.overruns.wrapping_add(1);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+    use crate::StaticFifoU32;
+
+    #[test]
+    fn dma_halves() {
+        println!("##################### AUDIO DMA TEST ######################################");
+
+        // playback, 4 samples per half
+        let mut src : StaticFifoU32<16> = StaticFifoU32::new();
+        for s in 1..=6 {
+            src.put(s).unwrap();
+        }
+        let mut tx : AudioTx<u32, 4> = AudioTx::new();
+        tx.on_half_complete(&mut src);
+        tx.on_complete(&mut src);
+        assert!(tx.dma_buffer() == &[[1, 2, 3, 4], [5, 6, 0, 0]], "refill wrong");
+        assert!(tx.underruns() == 1, "underrun not counted");
+
+        // capture into a fifo with room for 6
+        let mut sink : StaticFifoU32<7> = StaticFifoU32::new();
+        let mut rx : AudioRx<u32, 4> = AudioRx::new();
+        *rx.dma_buffer() = [[10, 11, 12, 13], [14, 15, 16, 17]];
+        rx.on_half_complete(&mut sink);
+        assert!(rx.overruns() == 0 && sink.len() == 4, "first half not moved");
+        rx.on_complete(&mut sink);
+        assert!(rx.overruns() == 1 && sink.len() == 6, "overrun not counted");
+        assert!(sink.iter().copied().eq(10..16), "captured samples wrong");
+    }
+}
@@ -0,0 +1,81 @@
+////////////////////////////////////////////////////////////////
+// Cortex-M BASEPRI protected fifo
+//
+// Shares a fifo between main code and interrupt handlers by
+// raising BASEPRI to a ceiling priority around each access,
+// instead of disabling all interrupts. Handlers above the
+// ceiling keep running (and must not touch this fifo), handlers
+// at or below it are held off for the few cycles a put / get
+// takes.
+//
+// The ceiling is the raw BASEPRI value, i.e. the priority of
+// the most urgent handler using the fifo as written to the NVIC
+// (upper NVIC_PRIO_BITS bits). It must not be 0, that value
+// switches BASEPRI masking off.
+//
+// Needs BASEPRI, so ARMv7-M / ARMv8-M mainline (M3 and up), not
+// M0 / M0+ / M23.
+//
+///////////////////////////////////////////////////////////////
+
+use core::cell::{Cell, UnsafeCell};
+
+use cortex_m::register::{basepri, basepri_max};
+
+use crate::StaticFifoError;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+pub struct BasepriFifo<F> {
+    fifo: UnsafeCell<F>,
+    ceiling: u8,
+    // set while a lock() closure runs, catches a nested lock() on the same fifo.
+    busy: Cell<bool>,
+}
+
+// every access goes through lock(), which runs with all users of the fifo masked
+// (as long as the ceiling covers them), so only one context is ever inside.
+unsafe impl<F : Send> Sync for BasepriFifo<F> {}
+
+impl<F> BasepriFifo<F> {
+
+    // const so it can sit in a static shared with the handlers.
+    pub const fn new(fifo : F, ceiling : u8) -> Self {
+        assert!(ceiling != 0, "BASEPRI ceiling 0 masks nothing");
+        BasepriFifo { fifo: UnsafeCell::new(fifo), ceiling, busy: Cell::new(false) }
+    }
+
+    pub fn ceiling(&self) -> u8 {
+        self.ceiling
+    }
+
+    // run f on the fifo with BASEPRI raised to the ceiling. a context that already
+    // runs above the ceiling keeps its level (BASEPRI_MAX only ever raises).
+    // panics if f locks the same fifo again.
+    pub fn lock<R>(&self, f : impl FnOnce(&mut F) -> R) -> R {
+        let old = basepri::read();
+        basepri_max::write(self.ceiling);
+        assert!(!self.busy.replace(true), "nested lock on a BasepriFifo");
+        // masked, and busy rules out a second &mut from this context.
+        let rv = f(unsafe { &mut *self.fifo.get() });
+        self.busy.set(false);
+        unsafe { basepri::write(old) };
+        rv
+    }
+
+    pub fn put<T>(&self, data : T) -> Result<(), StaticFifoError> where F : FifoProducer<T> {
+        self.lock(|fifo| fifo.put(data))
+    }
+
+    pub fn get<T>(&self) -> Result<T, StaticFifoError> where F : FifoConsumer<T> {
+        self.lock(|fifo| fifo.get())
+    }
+
+    // the fifo itself, no locking needed with exclusive access.
+    pub fn get_mut(&mut self) -> &mut F {
+        self.fifo.get_mut()
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo.into_inner()
+    }
+}
@@ -0,0 +1,150 @@
+////////////////////////////////////////////////////////////////
+// Bit fifo
+//
+// Fifo of single bits for bit oriented protocols and soft
+// decision demodulator output, one bit of storage each. Bits go
+// in and come out in groups of up to 32 through push_bits() /
+// pop_bits(), most significant bit of the group first, so a
+// field pushed as (value, 5) comes back from pop_bits(5) as
+// the same value, and bits pushed one at a time come back
+// msb first when popped as a group.
+//
+// A group goes in or comes out whole or not at all. N is the
+// storage in bytes; one bit is the full marker, so it holds
+// N * 8 - 1 bits. Built on PackedFifo<bool, N>.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::packed::PackedFifo;
+
+// largest group push_bits() / pop_bits() take.
+pub const MAX_BITS : u32 = 32;
+
+pub struct StaticBitFifo<const N : usize> {
+    bits: PackedFifo<bool, N>,
+}
+
+impl<const N : usize> Default for StaticBitFifo<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N : usize> StaticBitFifo<N> {
+
+    pub const fn new() -> Self {
+        StaticBitFifo { bits: PackedFifo::new() }
+    }
+
+    pub fn init(&mut self) {
+        self.bits.init();
+    }
+
+    // bits the fifo can hold.
+    pub const fn capacity(&self) -> usize {
+        self.bits.capacity()
+    }
+
+    // bits queued.
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.bits.is_full()
+    }
+
+    // bits that can still be pushed.
+    pub fn free_space(&self) -> usize {
+        self.bits.free_space()
+    }
+
+    // queue the low count bits of value, msb first. TooLarge for count > 32, Full if they do not all fit.
+    pub fn push_bits(&mut self, value : u32, count : u32) -> Result<(), StaticFifoError> {
+        if count > MAX_BITS {
+            return Err(StaticFifoError::TooLarge);
+        }
+        if count as usize > self.free_space() {
+            return Err(StaticFifoError::Full);
+        }
+        for i in (0..count).rev() {
+            let _ = self.bits.put((value >> i) & 1 != 0);
+        }
+        Ok(())
+    }
+
+    // remove count bits, the first one ends up most significant. TooLarge for count > 32, Empty if fewer are queued.
+    pub fn pop_bits(&mut self, count : u32) -> Result<u32, StaticFifoError> {
+        let rv = self.peek_bits(count)?;
+        for _ in 0..count {
+            let _ = self.bits.get();
+        }
+        Ok(rv)
+    }
+
+    // like pop_bits() but nothing is removed.
+    pub fn peek_bits(&self, count : u32) -> Result<u32, StaticFifoError> {
+        if count > MAX_BITS {
+            return Err(StaticFifoError::TooLarge);
+        }
+        if count as usize > self.len() {
+            return Err(StaticFifoError::Empty);
+        }
+        let mut rv = 0u32;
+        for i in 0..count as usize {
+            rv = (rv << 1) | u32::from(self.bits.peek_at(i) == Ok(true));
+        }
+        Ok(rv)
+    }
+
+    pub fn push_bit(&mut self, bit : bool) -> Result<(), StaticFifoError> {
+        self.bits.put(bit)
+    }
+
+    pub fn pop_bit(&mut self) -> Result<bool, StaticFifoError> {
+        self.bits.get()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn bit_groups() {
+        println!("##################### BIT FIFO TEST ######################################");
+
+        // 31 bits of room
+        let mut bits : StaticBitFifo<4> = StaticBitFifo::new();
+        assert!(bits.capacity() == 31, "capacity wrong");
+
+        bits.push_bits(0b101, 3).unwrap();
+        bits.push_bits(0xABCD, 16).unwrap();
+        bits.push_bit(true).unwrap();
+        assert!(bits.len() == 20, "len wrong");
+        assert!(bits.push_bits(0, 12) == Err(StaticFifoError::Full) && bits.len() == 20, "partial push");
+        assert!(bits.push_bits(0, 33) == Err(StaticFifoError::TooLarge), "oversize group taken");
+
+        assert!(bits.pop_bits(3) == Ok(0b101), "3 bit field wrong");
+        // regrouped: the 16 bits plus the single one
+        assert!(bits.peek_bits(17) == Ok(0x1579B), "regrouped bits wrong");
+        assert!(bits.pop_bits(8) == Ok(0xAB) && bits.pop_bits(8) == Ok(0xCD), "bytes wrong");
+        assert!(bits.pop_bits(2) == Err(StaticFifoError::Empty) && bits.len() == 1, "short pop took bits");
+        assert!(bits.pop_bit() == Ok(true) && bits.is_empty(), "last bit wrong");
+
+        // a full width group across the wrap
+        bits.push_bits(0, 20).unwrap();
+        bits.pop_bits(20).unwrap();
+        bits.push_bits(0xDEAD_BEEF, 32).unwrap_err();
+        bits.push_bits(0x5EAD_BEEF, 31).unwrap();
+        assert!(bits.is_full() && bits.pop_bits(31) == Ok(0x5EAD_BEEF), "wrapped group wrong");
+    }
+}
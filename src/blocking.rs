@@ -0,0 +1,101 @@
+////////////////////////////////////////////////////////////////
+// Blocking put / get with timeout
+//
+// Retry loops for bare-metal apps where the other side of the
+// fifo is an interrupt or another core. put_timeout() /
+// get_timeout() keep trying until they go through or the
+// deadline (in Clock ticks) passes, spinning in between. The
+// _with variants call a user hook in between instead (wfi,
+// feed a watchdog, yield to a scheduler ..).
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::timed::Clock;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+pub trait BlockingProducer<T : Copy> : FifoProducer<T> {
+
+    // retry put until it succeeds or timeout ticks have passed, then the last error
+    // (normally Full) is returned. a timeout of 0 still tries once.
+    fn put_timeout_with<C : Clock, H : FnMut()>(&mut self, data : T, clock : &C, timeout : u32, mut idle : H) -> Result<(), StaticFifoError> {
+        let start = clock.now();
+        loop {
+            let err = match self.put(data) {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+            if clock.now().wrapping_sub(start) >= timeout {
+                return Err(err);
+            }
+            idle();
+        }
+    }
+
+    fn put_timeout<C : Clock>(&mut self, data : T, clock : &C, timeout : u32) -> Result<(), StaticFifoError> {
+        self.put_timeout_with(data, clock, timeout, core::hint::spin_loop)
+    }
+}
+
+pub trait BlockingConsumer<T> : FifoConsumer<T> {
+
+    // retry get until it succeeds or timeout ticks have passed, then the last error
+    // (normally Empty) is returned. a timeout of 0 still tries once.
+    fn get_timeout_with<C : Clock, H : FnMut()>(&mut self, clock : &C, timeout : u32, mut idle : H) -> Result<T, StaticFifoError> {
+        let start = clock.now();
+        loop {
+            let err = match self.get() {
+                Ok(v) => return Ok(v),
+                Err(e) => e,
+            };
+            if clock.now().wrapping_sub(start) >= timeout {
+                return Err(err);
+            }
+            idle();
+        }
+    }
+
+    fn get_timeout<C : Clock>(&mut self, clock : &C, timeout : u32) -> Result<T, StaticFifoError> {
+        self.get_timeout_with(clock, timeout, core::hint::spin_loop)
+    }
+}
+
+impl<T : Copy, P : FifoProducer<T> + ?Sized> BlockingProducer<T> for P {}
+
+impl<T, C : FifoConsumer<T> + ?Sized> BlockingConsumer<T> for C {}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use core::cell::Cell;
+    use super::*;
+    use crate::StaticFifoU8;
+
+    #[test]
+    fn retry_until_deadline() {
+        println!("##################### BLOCKING TEST ######################################");
+
+        let ticks = Cell::new(0u32);
+        let clock = || ticks.get();
+        let mut fifo : StaticFifoU8<3> = StaticFifoU8::new();
+
+        fifo.put_timeout(1, &clock, 10).unwrap();
+        fifo.put_timeout(2, &clock, 10).unwrap();
+
+        // full, the hook is the only thing moving time along
+        let mut idles = 0;
+        let rv = fifo.put_timeout_with(3, &clock, 10, || { idles += 1; ticks.set(ticks.get() + 1); });
+        assert!(rv == Err(StaticFifoError::Full) && idles == 10, "timeout wrong");
+
+        assert!(fifo.get_timeout(&clock, 0) == Ok(1), "get with data queued failed");
+        assert!(fifo.get_timeout(&clock, 0) == Ok(2), "get with data queued failed");
+
+        // the clock wrapping during the wait is fine
+        ticks.set(u32::MAX - 2);
+        let mut idles = 0;
+        let rv = fifo.get_timeout_with(&clock, 5, || { idles += 1; ticks.set(ticks.get().wrapping_add(1)); });
+        assert!(rv == Err(StaticFifoError::Empty) && idles == 5, "wrapping timeout wrong");
+    }
+}
@@ -0,0 +1,127 @@
+////////////////////////////////////////////////////////////////
+// BlockPool
+//
+// Pool of fixed size byte blocks (e.g. 64 byte packet buffers)
+// for networking code that can not allocate. Free block
+// indices sit in a StaticFifoU32. alloc() hands out a Block
+// that derefs to the bytes and puts itself back on the free
+// list when dropped, so a block can not leak or be freed twice.
+//
+// alloc() only needs &self so several blocks can be out at
+// once. The pool is not Sync, it belongs to one context.
+//
+///////////////////////////////////////////////////////////////
+
+use core::cell::{RefCell, UnsafeCell};
+use core::ops::{Deref, DerefMut};
+
+use crate::{StaticFifoU32, StaticFifoError};
+
+// note N is raw storage, same as the fifos one block is lost to the
+// full marker of the free list, so N-1 blocks of B bytes can be handed out.
+pub struct BlockPool<const B : usize, const N : usize> {
+    blocks: UnsafeCell<[[u8; B]; N]>,
+    free: RefCell<StaticFifoU32<N>>,
+}
+
+impl<const B : usize, const N : usize> Default for BlockPool<B, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const B : usize, const N : usize> BlockPool<B, N> {
+
+    pub fn new() -> Self {
+        let mut free = StaticFifoU32::new();
+        for i in 0..(N.saturating_sub(1)) {
+            let _ = free.put(i as u32);
+        }
+        BlockPool { blocks: UnsafeCell::new([[0; B]; N]), free: RefCell::new(free) }
+    }
+
+    // number of blocks that can still be handed out.
+    pub fn available(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    // take a free block, its contents are whatever the last user left. Full if none are free.
+    pub fn alloc(&self) -> Result<Block<'_, B, N>, StaticFifoError> {
+        let i = self.free.borrow_mut().get().map_err(|_| StaticFifoError::Full)?;
+        Ok(Block { pool: self, index: i as usize })
+    }
+
+    // take a free block with every byte set to 0.
+    pub fn alloc_zeroed(&self) -> Result<Block<'_, B, N>, StaticFifoError> {
+        let mut b = self.alloc()?;
+        b.fill(0);
+        Ok(b)
+    }
+}
+
+// one block out of a BlockPool, goes back to the pool on drop.
+pub struct Block<'a, const B : usize, const N : usize> {
+    pool: &'a BlockPool<B, N>,
+    index: usize,
+}
+
+impl<'a, const B : usize, const N : usize> Block<'a, B, N> {
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<'a, const B : usize, const N : usize> Deref for Block<'a, B, N> {
+    type Target = [u8; B];
+
+    fn deref(&self) -> &[u8; B] {
+        // only the Block holding an index can reach that block, see alloc().
+        unsafe { &(*self.pool.blocks.get())[self.index] }
+    }
+}
+
+impl<'a, const B : usize, const N : usize> DerefMut for Block<'a, B, N> {
+    fn deref_mut(&mut self) -> &mut [u8; B] {
+        // only the Block holding an index can reach that block, see alloc().
+        unsafe { &mut (*self.pool.blocks.get())[self.index] }
+    }
+}
+
+impl<'a, const B : usize, const N : usize> Drop for Block<'a, B, N> {
+    fn drop(&mut self) {
+        // the free list has room for every block, this put can not fail.
+        let _ = self.pool.free.borrow_mut().put(self.index as u32);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn block_pool_raii() {
+        println!("##################### BLOCK POOL TEST ######################################");
+
+        let pool : BlockPool<64, 3> = BlockPool::new();
+
+        let mut a = pool.alloc_zeroed().unwrap();
+        let mut b = pool.alloc().unwrap();
+        assert!(pool.alloc().is_err() && pool.available() == 0, "alloc from empty pool");
+        a[0] = 0xAA;
+        b[..3].copy_from_slice(b"abc");
+        assert!(a[0] == 0xAA && a[1] == 0 && &b[..3] == b"abc", "blocks overlap");
+
+        drop(a);
+        assert!(pool.available() == 1, "dropped block not returned");
+        {
+            let c = pool.alloc().unwrap();
+            assert!(c.index() != b.index(), "block handed out twice");
+        }
+        drop(b);
+        assert!(pool.available() == 2, "pool not full after dropping everything");
+    }
+}
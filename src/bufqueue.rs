@@ -0,0 +1,248 @@
+////////////////////////////////////////////////////////////////
+// Buffer descriptor queue
+//
+// Passes whole packet buffers (&'static mut [u8] plus the
+// length in use) between an ISR and a task without copying a
+// payload byte: only the descriptor moves through the queue,
+// and with it the ownership of the buffer. The classic setup
+// is two of them, one carrying filled buffers from the rx ISR
+// to the task, the other handing emptied ones back.
+//
+// Single producer / single consumer with the same index
+// handoff as Mailbox (each index written by one side only,
+// plain atomic loads / stores, fine on ARMv6-M). The queue is
+// Sync and the handles are Send, so both can sit in statics or
+// move into an interrupt context; a descriptor is only ever
+// reachable from one side at a time, so no buffer gets two
+// live &mut.
+//
+///////////////////////////////////////////////////////////////
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use crate::atomic::{AtomicUsize, Ordering};
+
+use crate::StaticFifoError;
+
+// a static buffer and how much of it holds data.
+#[derive(Debug)]
+pub struct Packet {
+    pub buf: &'static mut [u8],
+    pub len: usize,
+}
+
+impl Packet {
+
+    // an empty packet over buf.
+    pub fn new(buf : &'static mut [u8]) -> Self {
+        Packet { buf, len: 0 }
+    }
+
+    // the part in use.
+    pub fn data(&self) -> &[u8] {
+        &self.buf[..core::cmp::min(self.len, self.buf.len())]
+    }
+
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        let n = core::cmp::min(self.len, self.buf.len());
+        &mut self.buf[..n]
+    }
+
+    // the whole buffer back, for the next packet.
+    pub fn into_buf(self) -> &'static mut [u8] {
+        self.buf
+    }
+}
+
+// like the other fifos, holds N-1 packets.
+pub struct BufferQueue<const N : usize> {
+    // written by the consumer only
+    read_ptr: AtomicUsize,
+    // written by the producer only
+    write_ptr: AtomicUsize,
+    slots: [UnsafeCell<MaybeUninit<Packet>>; N],
+}
+
+// a slot belongs to the producer until write_ptr moves past it, then to the
+// consumer until read_ptr does, never to both.
+unsafe impl<const N : usize> Sync for BufferQueue<N> {}
+
+impl<const N : usize> Default for BufferQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the safety rules of the unsafe fns are in the comments above them.
+#[allow(clippy::missing_safety_doc)]
+impl<const N : usize> BufferQueue<N> {
+
+    const CAPACITY_OK : () = assert!(N >= 2, "BufferQueue needs N >= 2, one slot is lost to the full marker");
+
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY_OK;
+        BufferQueue {
+            read_ptr: AtomicUsize::new(0),
+            write_ptr: AtomicUsize::new(0),
+            slots: [const { UnsafeCell::new(MaybeUninit::uninit()) }; N],
+        }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N - 1
+    }
+
+    // snapshot, may already be stale when it returns.
+    pub fn len(&self) -> usize {
+        let w = self.write_ptr.load(Ordering::Acquire);
+        let r = self.read_ptr.load(Ordering::Acquire);
+        if r > w { N - r + w } else { w - r }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // the put side. safety: at most one producer may exist at a time.
+    pub unsafe fn producer(&self) -> BufProducer<'_, N> {
+        BufProducer { q: self }
+    }
+
+    // the get side. safety: at most one consumer may exist at a time.
+    pub unsafe fn consumer(&self) -> BufConsumer<'_, N> {
+        BufConsumer { q: self }
+    }
+
+    // both sides, for when the queue is exclusively borrowed.
+    pub fn split(&mut self) -> (BufProducer<'_, N>, BufConsumer<'_, N>) {
+        let q : &Self = self;
+        (BufProducer { q }, BufConsumer { q })
+    }
+
+    #[inline]
+    fn next(p : usize) -> usize {
+        if p + 1 >= N { 0 } else { p + 1 }
+    }
+}
+
+pub struct BufProducer<'a, const N : usize> {
+    q: &'a BufferQueue<N>,
+}
+
+pub struct BufConsumer<'a, const N : usize> {
+    q: &'a BufferQueue<N>,
+}
+
+impl<'a, const N : usize> BufProducer<'a, N> {
+
+    // queue the packet, handing it back with Full if there is no room.
+    pub fn put(&mut self, packet : Packet) -> Result<(), (Packet, StaticFifoError)> {
+        let w = self.q.write_ptr.load(Ordering::Relaxed);
+        let next = BufferQueue::<N>::next(w);
+        if next == self.q.read_ptr.load(Ordering::Acquire) {
+            return Err((packet, StaticFifoError::Full));
+        }
+        let Some(slot) = self.q.slots.get(w) else {
+            return Err((packet, StaticFifoError::Corrupt));
+        };
+        // the consumer moved read_ptr past this slot, it is ours.
+        unsafe { (*slot.get()).write(packet) };
+        self.q.write_ptr.store(next, Ordering::Release);
+        Ok(())
+    }
+
+    pub fn free_space(&self) -> usize {
+        (N - 1).saturating_sub(self.q.len())
+    }
+}
+
+impl<'a, const N : usize> BufConsumer<'a, N> {
+
+    pub fn get(&mut self) -> Result<Packet, StaticFifoError> {
+        let r = self.q.read_ptr.load(Ordering::Relaxed);
+        if r == self.q.write_ptr.load(Ordering::Acquire) {
+            return Err(StaticFifoError::Empty);
+        }
+        let slot = self.q.slots.get(r).ok_or(StaticFifoError::Corrupt)?;
+        // written by the producer before it moved write_ptr past it; moved out
+        // here and read_ptr moves on, so it is not read twice.
+        let rv = unsafe { (*slot.get()).assume_init_read() };
+        self.q.read_ptr.store(BufferQueue::<N>::next(r), Ordering::Release);
+        Ok(rv)
+    }
+
+    pub fn len(&self) -> usize {
+        self.q.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.q.is_empty()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use std::thread;
+    use std::boxed::Box;
+    use super::*;
+
+    static FILLED : BufferQueue<4> = BufferQueue::new();
+    static EMPTY : BufferQueue<4> = BufferQueue::new();
+
+    #[test]
+    fn buffers_round_trip() {
+        println!("##################### BUFFER QUEUE TEST ######################################");
+
+        // three packet buffers, all free at the start
+        let mut free = unsafe { EMPTY.producer() };
+        for _ in 0..3 {
+            free.put(Packet::new(Box::leak(Box::new([0u8; 16])))).unwrap();
+        }
+        let extra = Packet::new(Box::leak(Box::new([0u8; 16])));
+        assert!(matches!(free.put(extra), Err((p, StaticFifoError::Full)) if p.buf.len() == 16), "full queue kept the packet");
+
+        // "rx isr": takes a free buffer, fills it, hands it over
+        let isr = thread::spawn(|| {
+            let (mut free, mut filled) = unsafe { (EMPTY.consumer(), FILLED.producer()) };
+            for i in 0..50u8 {
+                let mut p = loop {
+                    if let Ok(p) = free.get() {
+                        break p;
+                    }
+                    thread::yield_now();
+                };
+                p.buf[..3].copy_from_slice(&[i, i, i]);
+                p.len = 3;
+                let mut p = Some(p);
+                while let Some(q) = p.take() {
+                    if let Err((q, _)) = filled.put(q) {
+                        p = Some(q);
+                        thread::yield_now();
+                    }
+                }
+            }
+        });
+
+        // task: reads each packet in place, gives the buffer back
+        let mut filled = unsafe { FILLED.consumer() };
+        for i in 0..50u8 {
+            let p = loop {
+                if let Ok(p) = filled.get() {
+                    break p;
+                }
+                thread::yield_now();
+            };
+            assert!(p.data() == [i, i, i], "packet data wrong");
+            let mut p = Packet::new(p.into_buf());
+            while let Err((q, _)) = free.put(p) {
+                p = q;
+                thread::yield_now();
+            }
+        }
+        isr.join().unwrap();
+        assert!(FILLED.is_empty() && EMPTY.len() == 3, "buffers lost");
+    }
+}
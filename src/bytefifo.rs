@@ -0,0 +1,165 @@
+////////////////////////////////////////////////////////////////
+// ByteFifo
+//
+// Object safe trait over the byte fifos, so a driver can hold
+// a &mut dyn ByteFifo and work with a fifo of any capacity
+// (or flavor) without being generic over N itself.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoError};
+
+pub trait ByteFifo {
+
+    fn put_u8(&mut self, data : u8) -> Result<(), StaticFifoError>;
+
+    fn get_u8(&mut self) -> Result<u8, StaticFifoError>;
+
+    // number of bytes queued.
+    fn len(&self) -> usize;
+
+    // bytes the fifo can hold at most.
+    fn capacity(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn is_full(&self) -> bool {
+        self.len() >= self.capacity()
+    }
+
+    // number of bytes that can still be put.
+    fn free_space(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    // put as much of data as fits, returns how many bytes were put.
+    fn put_slice(&mut self, data : &[u8]) -> usize {
+        data.iter().take_while(|b| self.put_u8(**b).is_ok()).count()
+    }
+
+    // fill as much of out as there is data for, returns how many bytes were taken.
+    // stops early at a byte get_u8() refuses (e.g. Corrupt).
+    fn get_slice(&mut self, out : &mut [u8]) -> usize {
+        let n = core::cmp::min(out.len(), self.len());
+        for (i, b) in out[..n].iter_mut().enumerate() {
+            match self.get_u8() {
+                Ok(v) => *b = v,
+                Err(_) => return i,
+            }
+        }
+        n
+    }
+}
+
+impl<const N : usize> ByteFifo for StaticFifoU8<N> {
+
+    fn put_u8(&mut self, data : u8) -> Result<(), StaticFifoError> {
+        self.put(data)
+    }
+
+    fn get_u8(&mut self) -> Result<u8, StaticFifoError> {
+        self.get()
+    }
+
+    fn len(&self) -> usize {
+        StaticFifoU8::len(self)
+    }
+
+    // one slot of the raw storage is the full marker, and a soft capacity limits it further.
+    fn capacity(&self) -> usize {
+        self.soft_capacity()
+    }
+
+    fn is_full(&self) -> bool {
+        StaticFifoU8::is_full(self)
+    }
+
+    fn free_space(&self) -> usize {
+        StaticFifoU8::free_space(self)
+    }
+
+    // copies straight into the storage, at most two copies.
+    fn put_slice(&mut self, data : &[u8]) -> usize {
+        let n = core::cmp::min(data.len(), StaticFifoU8::free_space(self));
+        self.copy_in(&data[..n]);
+        n
+    }
+
+    // copies straight out of the storage, at most two copies.
+    fn get_slice(&mut self, out : &mut [u8]) -> usize {
+        let (a, b) = self.as_slices();
+        let first = core::cmp::min(out.len(), a.len());
+        let second = core::cmp::min(out.len() - first, b.len());
+        out[..first].copy_from_slice(&a[..first]);
+        out[first..(first + second)].copy_from_slice(&b[..second]);
+        self.skip(first + second)
+    }
+}
+
+#[cfg(feature = "ecc")]
+impl<const N : usize> ByteFifo for crate::EccFifoU8<N> {
+
+    fn put_u8(&mut self, data : u8) -> Result<(), StaticFifoError> {
+        self.put(data)
+    }
+
+    fn get_u8(&mut self) -> Result<u8, StaticFifoError> {
+        self.get()
+    }
+
+    fn len(&self) -> usize {
+        crate::EccFifoU8::len(self)
+    }
+
+    // one slot of the raw storage is the full marker.
+    fn capacity(&self) -> usize {
+        self.max_len() - 1
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    // driver that takes any byte fifo
+    fn send_hello(tx : &mut dyn ByteFifo) -> usize {
+        tx.put_slice(b"hello")
+    }
+
+    #[test]
+    fn dyn_byte_fifo() {
+        println!("##################### BYTE FIFO TRAIT TEST ######################################");
+
+        let mut small : StaticFifoU8<4> = StaticFifoU8::new();
+        let mut big : StaticFifoU8<32> = StaticFifoU8::new();
+
+        assert!(send_hello(&mut small) == 3 && small.is_full(), "short fifo not filled");
+        assert!(send_hello(&mut big) == 5, "big fifo short");
+
+        let fifos : [&mut dyn ByteFifo; 2] = [&mut small, &mut big];
+        assert!(fifos[0].capacity() == 3 && fifos[1].capacity() == 31, "capacity wrong");
+        assert!(fifos[1].free_space() == 26, "free space wrong");
+
+        let mut out = [0u8; 8];
+        assert!(fifos[1].get_slice(&mut out) == 5 && &out[..5] == b"hello", "get_slice wrong");
+        assert!(fifos[0].get_slice(&mut out[..2]) == 2 && &out[..2] == b"he", "partial get_slice wrong");
+        assert!(fifos[0].len() == 1 && fifos[1].is_empty(), "lengths wrong after get_slice");
+    }
+
+    #[test]
+    fn dyn_byte_fifo_soft_capacity() {
+        println!("##################### BYTE FIFO SOFT CAPACITY TEST ######################################");
+
+        let mut fifo : StaticFifoU8<64> = StaticFifoU8::new();
+        fifo.set_soft_capacity(8);
+        let tx : &mut dyn ByteFifo = &mut fifo;
+        assert!(tx.capacity() == 8 && tx.free_space() == 8, "soft capacity ignored");
+        assert!(tx.put_slice(b"0123456789") == 8 && tx.is_full(), "put past the soft capacity");
+        assert!(tx.free_space() == 0 && tx.put_u8(0).is_err(), "full fifo reports room");
+    }
+}
@@ -0,0 +1,204 @@
+////////////////////////////////////////////////////////////////
+// CAN frame fifo
+//
+// Receive queue for CAN frames where a dispatcher pulls out
+// only the frames for one consumer with pop_matching(), the
+// same mask / filter test a CAN controller's acceptance
+// filters do: a frame matches when its IDE bit is the one the
+// filter asks for and (id & mask) == (filter & mask), so a
+// standard and an extended id with the same low bits never
+// mix. Frames for other consumers stay queued in their
+// order, and each consumer sees its own frames in arrival
+// order.
+//
+// Taking a frame from the middle moves the ones in front of
+// it, cost grows with how deep the match sits.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticDeque, StaticFifoError};
+use crate::traits::{FifoProducer, FifoConsumer};
+
+// classic CAN frame, up to 8 data bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CanFrame {
+    // 11 bit standard or 29 bit extended identifier
+    pub id: u32,
+    pub extended: bool,
+    // remote transmission request, no data
+    pub rtr: bool,
+    // data length, 0..=8
+    pub dlc: u8,
+    pub data: [u8; 8],
+}
+
+// largest standard / extended identifier.
+pub const MAX_STANDARD_ID : u32 = 0x7FF;
+pub const MAX_EXTENDED_ID : u32 = 0x1FFF_FFFF;
+
+impl CanFrame {
+
+    // data frame with a standard id. None for an id past 11 bits or more than 8 bytes.
+    pub fn new(id : u32, data : &[u8]) -> Option<Self> {
+        if id > MAX_STANDARD_ID {
+            return None;
+        }
+        Self::with_id(id, false, data)
+    }
+
+    // data frame with an extended id. None for an id past 29 bits or more than 8 bytes.
+    pub fn new_extended(id : u32, data : &[u8]) -> Option<Self> {
+        if id > MAX_EXTENDED_ID {
+            return None;
+        }
+        Self::with_id(id, true, data)
+    }
+
+    fn with_id(id : u32, extended : bool, data : &[u8]) -> Option<Self> {
+        let mut buf = [0u8; 8];
+        buf.get_mut(..data.len())?.copy_from_slice(data);
+        Some(CanFrame { id, extended, rtr: false, dlc: data.len() as u8, data: buf })
+    }
+
+    // the data bytes in use.
+    pub fn data(&self) -> &[u8] {
+        &self.data[..core::cmp::min(usize::from(self.dlc), 8)]
+    }
+
+    // extended picks which kind of id the filter is for.
+    pub fn matches(&self, id_mask : u32, id_filter : u32, extended : bool) -> bool {
+        self.extended == extended && (self.id & id_mask) == (id_filter & id_mask)
+    }
+}
+
+// note length specified is raw storage container.
+// fifo full pointers take up 1 element. so you will need N+1
+pub struct CanFifo<const N : usize> {
+    frames: StaticDeque<CanFrame, N>,
+}
+
+impl<const N : usize> Default for CanFifo<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N : usize> CanFifo<N> {
+
+    pub fn new() -> Self {
+        CanFifo { frames: StaticDeque::new() }
+    }
+
+    pub fn put(&mut self, frame : CanFrame) -> Result<(), StaticFifoError> {
+        self.frames.push_back(frame)
+    }
+
+    // oldest frame, whoever it is for.
+    pub fn get(&mut self) -> Result<CanFrame, StaticFifoError> {
+        self.frames.pop_front()
+    }
+
+    // oldest frame passing the mask / filter, the others stay queued. Empty if none does.
+    pub fn pop_matching(&mut self, id_mask : u32, id_filter : u32, extended : bool) -> Result<CanFrame, StaticFifoError> {
+        let i = self.position_matching(id_mask, id_filter, extended).ok_or(StaticFifoError::Empty)?;
+        self.frames.remove(i)
+    }
+
+    // number of queued frames passing the mask / filter.
+    pub fn count_matching(&self, id_mask : u32, id_filter : u32, extended : bool) -> usize {
+        (0..self.len()).filter(|&i| self.frames.peek_at(i).is_ok_and(|f| f.matches(id_mask, id_filter, extended))).count()
+    }
+
+    fn position_matching(&self, id_mask : u32, id_filter : u32, extended : bool) -> Option<usize> {
+        (0..self.len()).find(|&i| self.frames.peek_at(i).is_ok_and(|f| f.matches(id_mask, id_filter, extended)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn free_space(&self) -> usize {
+        self.frames.free_space()
+    }
+}
+
+impl<const N : usize> FifoProducer<CanFrame> for CanFifo<N> {
+
+    fn put(&mut self, data : CanFrame) -> Result<(), StaticFifoError> {
+        CanFifo::put(self, data)
+    }
+
+    fn free_space(&self) -> usize {
+        CanFifo::free_space(self)
+    }
+}
+
+impl<const N : usize> FifoConsumer<CanFrame> for CanFifo<N> {
+
+    fn get(&mut self) -> Result<CanFrame, StaticFifoError> {
+        CanFifo::get(self)
+    }
+
+    fn len(&self) -> usize {
+        CanFifo::len(self)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn dispatch_by_filter() {
+        println!("##################### CAN FIFO TEST ######################################");
+
+        let mut rx : CanFifo<8> = CanFifo::new();
+        // 0x1xx motor controller, 0x2xx battery
+        for (id, b) in [(0x101, 1u8), (0x200, 2), (0x102, 3), (0x201, 4), (0x300, 5)] {
+            rx.put(CanFrame::new(id, &[b]).unwrap()).unwrap();
+        }
+        assert!(CanFrame::new(0x100, &[0; 9]).is_none(), "9 data bytes accepted");
+
+        // the battery task takes only its frames, in order
+        assert!(rx.count_matching(0x700, 0x200, false) == 2, "count wrong");
+        assert!(rx.pop_matching(0x700, 0x200, false).map(|f| f.data()[0]) == Ok(2), "first battery frame wrong");
+        assert!(rx.pop_matching(0x700, 0x200, false).map(|f| f.id) == Ok(0x201), "second battery frame wrong");
+        assert!(rx.pop_matching(0x700, 0x200, false) == Err(StaticFifoError::Empty), "battery frames left");
+
+        // the rest kept its order
+        assert!(rx.len() == 3 && rx.pop_matching(0x7FF, 0x300, false).is_ok(), "exact id filter wrong");
+        assert!(rx.get().map(|f| f.id) == Ok(0x101) && rx.get().map(|f| f.id) == Ok(0x102), "motor frames out of order");
+
+        let ext = CanFrame::new_extended(0x18FF_0001, &[]).unwrap();
+        assert!(ext.extended && ext.data().is_empty(), "extended frame wrong");
+    }
+
+    #[test]
+    fn standard_and_extended() {
+        println!("##################### CAN FIFO IDE TEST ######################################");
+
+        // ids past their range are refused
+        assert!(CanFrame::new(0x800, &[]).is_none() && CanFrame::new(0x7FF, &[]).is_some(), "standard id range wrong");
+        assert!(CanFrame::new_extended(0x2000_0000, &[]).is_none(), "extended id range wrong");
+        assert!(CanFrame::new_extended(0x1FFF_FFFF, &[]).is_some(), "largest extended id refused");
+
+        // same low bits, one standard and one extended
+        let mut rx : CanFifo<8> = CanFifo::new();
+        rx.put(CanFrame::new_extended(0x123, &[1]).unwrap()).unwrap();
+        rx.put(CanFrame::new(0x123, &[2]).unwrap()).unwrap();
+        rx.put(CanFrame::new_extended(0x1800_0123, &[3]).unwrap()).unwrap();
+
+        assert!(rx.count_matching(0x7FF, 0x123, false) == 1 && rx.count_matching(0x7FF, 0x123, true) == 2, "ide bit ignored");
+        assert!(rx.pop_matching(0x7FF, 0x123, false).map(|f| f.data()[0]) == Ok(2), "standard filter took an extended frame");
+        assert!(rx.pop_matching(0x7FF, 0x123, false) == Err(StaticFifoError::Empty), "standard frames left");
+        assert!(rx.pop_matching(MAX_EXTENDED_ID, 0x1800_0123, true).map(|f| f.data()[0]) == Ok(3), "exact extended filter wrong");
+        assert!(rx.get().map(|f| f.data()[0]) == Ok(1) && rx.is_empty(), "extended frame lost");
+    }
+}
@@ -0,0 +1,166 @@
+////////////////////////////////////////////////////////////////
+// CellFifo
+//
+// Fifo with Cell based storage and indices, for driver code
+// that only ever has &self where it has to pop (callbacks,
+// trait methods taking &self ..). split() hands out exactly
+// one producer and one consumer handle, both working through
+// &self; neither can be cloned, so the single producer /
+// single consumer rule is kept by the types. Cells make it
+// !Sync, it is for one execution context, and the handles
+// (which borrow it) are not Send.
+//
+///////////////////////////////////////////////////////////////
+
+use core::cell::Cell;
+
+use crate::StaticFifoError;
+
+// note length specified is raw storage container.
+// fifo full pointers take up 1 element. so you will need N+1
+pub struct CellFifo<T, const N : usize> {
+    buf: [Cell<T>; N],
+    read_ptr: Cell<usize>,
+    write_ptr: Cell<usize>,
+}
+
+impl<T : Copy + Default, const N : usize> Default for CellFifo<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T : Copy + Default, const N : usize> CellFifo<T, N> {
+
+    const CAPACITY_OK : () = assert!(N >= 2, "CellFifo needs N >= 2, one slot is lost to the full marker");
+
+    pub fn new() -> Self {
+        let () = Self::CAPACITY_OK;
+        CellFifo { buf: core::array::from_fn(|_| Cell::new(T::default())), read_ptr: Cell::new(0), write_ptr: Cell::new(0) }
+    }
+
+    // the one producer and the one consumer. the &mut borrow keeps split() from
+    // handing out a second pair while these live.
+    pub fn split(&mut self) -> (CellProducer<'_, T, N>, CellConsumer<'_, T, N>) {
+        let fifo : &Self = self;
+        (CellProducer { fifo }, CellConsumer { fifo })
+    }
+
+    pub fn len(&self) -> usize {
+        let (r, w) = (self.read_ptr.get(), self.write_ptr.get());
+        if r > w { N - r + w } else { w - r }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read_ptr.get() == self.write_ptr.get()
+    }
+
+    pub fn is_full(&self) -> bool {
+        Self::next(self.write_ptr.get()) == self.read_ptr.get()
+    }
+
+    // number of elements that can still be put before the fifo is full.
+    pub fn free_space(&self) -> usize {
+        (N - 1) - self.len()
+    }
+
+    #[inline]
+    fn next(p : usize) -> usize {
+        if p + 1 >= N { 0 } else { p + 1 }
+    }
+}
+
+pub struct CellProducer<'a, T, const N : usize> {
+    fifo: &'a CellFifo<T, N>,
+}
+
+pub struct CellConsumer<'a, T, const N : usize> {
+    fifo: &'a CellFifo<T, N>,
+}
+
+impl<'a, T : Copy + Default, const N : usize> CellProducer<'a, T, N> {
+
+    pub fn put(&self, data : T) -> Result<(), StaticFifoError> {
+        if self.fifo.is_full() {
+            return Err(StaticFifoError::Full);
+        }
+        let w = self.fifo.write_ptr.get();
+        self.fifo.buf[w].set(data);
+        self.fifo.write_ptr.set(CellFifo::<T, N>::next(w));
+        Ok(())
+    }
+
+    pub fn free_space(&self) -> usize {
+        self.fifo.free_space()
+    }
+}
+
+impl<'a, T : Copy + Default, const N : usize> CellConsumer<'a, T, N> {
+
+    pub fn get(&self) -> Result<T, StaticFifoError> {
+        let rv = self.peek()?;
+        self.fifo.read_ptr.set(CellFifo::<T, N>::next(self.fifo.read_ptr.get()));
+        Ok(rv)
+    }
+
+    // next element without removing it.
+    pub fn peek(&self) -> Result<T, StaticFifoError> {
+        if self.fifo.is_empty() {
+            return Err(StaticFifoError::Empty);
+        }
+        Ok(self.fifo.buf[self.fifo.read_ptr.get()].get())
+    }
+
+    pub fn len(&self) -> usize {
+        self.fifo.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fifo.is_empty()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    // a driver whose receive hook only gets &self
+    trait RxHook {
+        fn on_poll(&self) -> Option<u16>;
+    }
+
+    struct Driver<'a> {
+        rx: CellConsumer<'a, u16, 4>,
+    }
+
+    impl<'a> RxHook for Driver<'a> {
+        fn on_poll(&self) -> Option<u16> {
+            self.rx.get().ok()
+        }
+    }
+
+    #[test]
+    fn pop_through_shared_ref() {
+        println!("##################### CELL FIFO TEST ######################################");
+
+        let mut fifo : CellFifo<u16, 4> = CellFifo::new();
+        let (tx, rx) = fifo.split();
+        let driver = Driver { rx };
+
+        assert!(driver.on_poll().is_none(), "empty fifo gave data");
+        for v in [1, 2, 3] {
+            tx.put(v).unwrap();
+        }
+        assert!(tx.put(4) == Err(StaticFifoError::Full) && tx.free_space() == 0, "full fifo took data");
+        assert!(driver.rx.peek() == Ok(1) && driver.rx.len() == 3, "peek wrong");
+        assert!(driver.on_poll() == Some(1) && driver.on_poll() == Some(2), "order wrong");
+        // wrap around
+        tx.put(4).unwrap();
+        tx.put(5).unwrap();
+        assert!(driver.on_poll() == Some(3) && driver.on_poll() == Some(4) && driver.on_poll() == Some(5), "order after wrap wrong");
+        assert!(driver.rx.is_empty(), "fifo not empty");
+    }
+}
@@ -0,0 +1,287 @@
+////////////////////////////////////////////////////////////////
+// Blocking channel (std)
+//
+// Puts any fifo behind a Mutex + Condvar pair and hands out a
+// Sender / Receiver, so code written around the static fifos
+// can run on threads in host side simulations without being
+// moved over to std::sync::mpsc. The fifo keeps its fixed
+// size, send() blocks while it is full and recv() while it is
+// empty.
+//
+// Both ends count their clones. Once the last Receiver is gone
+// sends fail Disconnected; once the last Sender is gone the
+// Receiver still gets what is queued, then Disconnected instead
+// of blocking forever.
+//
+///////////////////////////////////////////////////////////////
+
+use core::marker::PhantomData;
+use crate::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Instant;
+
+use crate::StaticFifoError;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+struct Shared<F> {
+    fifo: Mutex<F>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    // live ends, the last one to go wakes the other side
+    senders: AtomicUsize,
+    receivers: AtomicUsize,
+}
+
+impl<F> Shared<F> {
+
+    // a thread panicking while holding the lock can not leave the fifo half
+    // updated (put / get are single steps), so a poisoned lock is still usable.
+    fn lock(&self) -> MutexGuard<'_, F> {
+        self.fifo.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // wake everyone waiting on cv. taking the lock first means a waiter either
+    // saw the count drop already or is parked and gets the notify.
+    fn wake_all(&self, cv : &Condvar) {
+        drop(self.lock());
+        cv.notify_all();
+    }
+
+    fn no_senders(&self) -> bool {
+        self.senders.load(Ordering::Acquire) == 0
+    }
+
+    fn no_receivers(&self) -> bool {
+        self.receivers.load(Ordering::Acquire) == 0
+    }
+}
+
+pub struct Sender<T, F> {
+    shared: Arc<Shared<F>>,
+    _t: PhantomData<fn(T)>,
+}
+
+pub struct Receiver<T, F> {
+    shared: Arc<Shared<F>>,
+    _t: PhantomData<fn() -> T>,
+}
+
+// split fifo into the two ends of a blocking channel. both ends can be cloned.
+pub fn channel<T, F : FifoProducer<T> + FifoConsumer<T>>(fifo : F) -> (Sender<T, F>, Receiver<T, F>) {
+    let shared = Arc::new(Shared {
+        fifo: Mutex::new(fifo),
+        not_empty: Condvar::new(),
+        not_full: Condvar::new(),
+        senders: AtomicUsize::new(1),
+        receivers: AtomicUsize::new(1),
+    });
+    (Sender { shared: shared.clone(), _t: PhantomData }, Receiver { shared, _t: PhantomData })
+}
+
+impl<T, F> Clone for Sender<T, F> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+        Sender { shared: self.shared.clone(), _t: PhantomData }
+    }
+}
+
+impl<T, F> Clone for Receiver<T, F> {
+    fn clone(&self) -> Self {
+        self.shared.receivers.fetch_add(1, Ordering::Relaxed);
+        Receiver { shared: self.shared.clone(), _t: PhantomData }
+    }
+}
+
+impl<T, F> Drop for Sender<T, F> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.wake_all(&self.shared.not_empty);
+        }
+    }
+}
+
+impl<T, F> Drop for Receiver<T, F> {
+    fn drop(&mut self) {
+        if self.shared.receivers.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.wake_all(&self.shared.not_full);
+        }
+    }
+}
+
+impl<T, F : FifoProducer<T> + FifoConsumer<T>> Sender<T, F> {
+
+    // put data, waiting for room as long as it takes. Disconnected once no Receiver is left.
+    pub fn send(&self, data : T) -> Result<(), StaticFifoError> {
+        let mut fifo = self.shared.lock();
+        loop {
+            if self.shared.no_receivers() {
+                return Err(StaticFifoError::Disconnected);
+            }
+            if !fifo.is_full() {
+                break;
+            }
+            fifo = self.shared.not_full.wait(fifo).unwrap_or_else(|e| e.into_inner());
+        }
+        fifo.put(data)?;
+        self.shared.not_empty.notify_one();
+        Ok(())
+    }
+
+    // put data, waiting at most timeout for room. Full if there was none, Disconnected as for send().
+    pub fn send_timeout(&self, data : T, timeout : Duration) -> Result<(), StaticFifoError> {
+        let deadline = Instant::now() + timeout;
+        let mut fifo = self.shared.lock();
+        loop {
+            if self.shared.no_receivers() {
+                return Err(StaticFifoError::Disconnected);
+            }
+            if !fifo.is_full() {
+                break;
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Err(StaticFifoError::Full);
+            }
+            fifo = self.shared.not_full.wait_timeout(fifo, left).unwrap_or_else(|e| e.into_inner()).0;
+        }
+        fifo.put(data)?;
+        self.shared.not_empty.notify_one();
+        Ok(())
+    }
+
+    // put data without waiting.
+    pub fn try_send(&self, data : T) -> Result<(), StaticFifoError> {
+        if self.shared.no_receivers() {
+            return Err(StaticFifoError::Disconnected);
+        }
+        self.shared.lock().put(data)?;
+        self.shared.not_empty.notify_one();
+        Ok(())
+    }
+}
+
+impl<T, F : FifoProducer<T> + FifoConsumer<T>> Receiver<T, F> {
+
+    // get the next element, waiting for one as long as it takes.
+    // Disconnected once the fifo is drained and no Sender is left.
+    pub fn recv(&self) -> Result<T, StaticFifoError> {
+        let mut fifo = self.shared.lock();
+        while fifo.is_empty() {
+            if self.shared.no_senders() {
+                return Err(StaticFifoError::Disconnected);
+            }
+            fifo = self.shared.not_empty.wait(fifo).unwrap_or_else(|e| e.into_inner());
+        }
+        let rv = fifo.get()?;
+        self.shared.not_full.notify_one();
+        Ok(rv)
+    }
+
+    // get the next element, waiting at most timeout. Empty if none arrived, Disconnected as for recv().
+    pub fn recv_timeout(&self, timeout : Duration) -> Result<T, StaticFifoError> {
+        let deadline = Instant::now() + timeout;
+        let mut fifo = self.shared.lock();
+        while fifo.is_empty() {
+            if self.shared.no_senders() {
+                return Err(StaticFifoError::Disconnected);
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Err(StaticFifoError::Empty);
+            }
+            fifo = self.shared.not_empty.wait_timeout(fifo, left).unwrap_or_else(|e| e.into_inner()).0;
+        }
+        let rv = fifo.get()?;
+        self.shared.not_full.notify_one();
+        Ok(rv)
+    }
+
+    // get the next element without waiting.
+    pub fn try_recv(&self) -> Result<T, StaticFifoError> {
+        let mut fifo = self.shared.lock();
+        if fifo.is_empty() && self.shared.no_senders() {
+            return Err(StaticFifoError::Disconnected);
+        }
+        let rv = fifo.get()?;
+        drop(fifo);
+        self.shared.not_full.notify_one();
+        Ok(rv)
+    }
+
+    // number of elements queued right now.
+    pub fn len(&self) -> usize {
+        self.shared.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use std::thread;
+    use super::*;
+    use crate::StaticFifoU32;
+
+    #[test]
+    fn threads_through_channel() {
+        println!("##################### CHANNEL TEST ######################################");
+
+        let (tx, rx) = channel(StaticFifoU32::<4>::new());
+
+        assert!(rx.recv_timeout(Duration::from_millis(10)) == Err(StaticFifoError::Empty), "recv on empty channel");
+
+        // far more than fits, so the sender has to block on the slow receiver
+        let producer = thread::spawn(move || {
+            for i in 0..100 {
+                tx.send(i).unwrap();
+            }
+            tx
+        });
+        for i in 0..100 {
+            assert!(rx.recv() == Ok(i), "order wrong");
+        }
+        let tx = producer.join().unwrap();
+
+        for i in 0..3 {
+            tx.try_send(i).unwrap();
+        }
+        assert!(tx.send_timeout(3, Duration::from_millis(10)) == Err(StaticFifoError::Full), "send on full channel");
+        assert!(rx.len() == 3 && rx.try_recv() == Ok(0), "try_recv wrong");
+    }
+    #[test]
+    fn disconnect() {
+        println!("##################### CHANNEL DISCONNECT TEST ######################################");
+
+        // what is queued still arrives after the sender is gone
+        let (tx, rx) = channel(StaticFifoU32::<4>::new());
+        tx.send(1).unwrap();
+        let tx2 = tx.clone();
+        drop(tx);
+        tx2.send(2).unwrap();
+        drop(tx2);
+        assert!(rx.recv() == Ok(1) && rx.try_recv() == Ok(2), "queued data lost");
+        assert!(rx.recv() == Err(StaticFifoError::Disconnected), "recv after senders gone");
+        assert!(rx.try_recv() == Err(StaticFifoError::Disconnected), "try_recv after senders gone");
+
+        // a blocked receiver wakes up when the last sender drops
+        let (tx, rx) = channel(StaticFifoU32::<4>::new());
+        let waiter = thread::spawn(move || rx.recv());
+        thread::sleep(Duration::from_millis(20));
+        drop(tx);
+        assert!(waiter.join().unwrap() == Err(StaticFifoError::Disconnected), "blocked recv not woken");
+
+        // a blocked sender wakes up when the last receiver drops
+        let (tx, rx) = channel(StaticFifoU32::<2>::new());
+        tx.send(1).unwrap();
+        let waiter = thread::spawn(move || tx.send(2));
+        thread::sleep(Duration::from_millis(20));
+        drop(rx);
+        assert!(waiter.join().unwrap() == Err(StaticFifoError::Disconnected), "blocked send not woken");
+    }
+}
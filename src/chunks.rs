@@ -0,0 +1,112 @@
+////////////////////////////////////////////////////////////////
+// Chunked reads
+//
+// Consuming iterators that take whole M element chunks off the
+// read side (built on get_array()) and stop as soon as less
+// than a full chunk is queued. The partial tail stays in the
+// fifo, so block oriented consumers (flash pages, codec
+// frames) only ever see aligned units.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32};
+
+// see StaticFifoU8::chunks().
+pub struct ChunksU8<'a, const N : usize, const M : usize> {
+    fifo: &'a mut StaticFifoU8<N>,
+}
+
+impl<'a, const N : usize, const M : usize> Iterator for ChunksU8<'a, N, M> {
+    type Item = [u8; M];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if M == 0 {
+            return None;
+        }
+        self.fifo.get_array::<M>().ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.fifo.len().checked_div(M).unwrap_or(0);
+        (n, Some(n))
+    }
+}
+
+impl<'a, const N : usize, const M : usize> ExactSizeIterator for ChunksU8<'a, N, M> {}
+
+// see StaticFifoU32::chunks().
+pub struct ChunksU32<'a, const N : usize, const M : usize> {
+    fifo: &'a mut StaticFifoU32<N>,
+}
+
+impl<'a, const N : usize, const M : usize> Iterator for ChunksU32<'a, N, M> {
+    type Item = [u32; M];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if M == 0 {
+            return None;
+        }
+        self.fifo.get_array::<M>().ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.fifo.len().checked_div(M).unwrap_or(0);
+        (n, Some(n))
+    }
+}
+
+impl<'a, const N : usize, const M : usize> ExactSizeIterator for ChunksU32<'a, N, M> {}
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // remove whole M byte chunks as they are iterated, a partial chunk is left queued.
+    pub fn chunks<const M : usize>(&mut self) -> ChunksU8<'_, N, M> {
+        ChunksU8 { fifo: self }
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // remove whole M element chunks as they are iterated, a partial chunk is left queued.
+    pub fn chunks<const M : usize>(&mut self) -> ChunksU32<'_, N, M> {
+        ChunksU32 { fifo: self }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn chunked_reads() {
+        println!("##################### CHUNKS TEST ######################################");
+
+        let mut fifo : StaticFifoU8<12> = StaticFifoU8::new();
+        for b in 0..10 {
+            fifo.put(b).unwrap();
+        }
+
+        let mut pages = [[0u8; 4]; 2];
+        let mut n : usize = 0;
+        assert!(fifo.chunks::<4>().size_hint() == (2, Some(2)), "chunk count wrong");
+        for page in fifo.chunks::<4>() {
+            pages[n] = page;
+            n += 1;
+        }
+        assert!(n == 2 && pages == [[0, 1, 2, 3], [4, 5, 6, 7]], "chunks wrong");
+        assert!(fifo.len() == 2 && fifo.peek_at(0) == Ok(8), "partial chunk not left queued");
+
+        // stopping early leaves the rest alone
+        let mut words : StaticFifoU32<8> = StaticFifoU32::new();
+        for v in 0..7 {
+            words.put(v).unwrap();
+        }
+        assert!(words.chunks::<2>().next() == Some([0, 1]), "word chunk wrong");
+        assert!(words.len() == 5, "early stop consumed too much");
+        assert!(words.chunks::<2>().count() == 2 && words.len() == 1, "word tail wrong");
+        assert!(words.chunks::<0>().next().is_none(), "zero size chunk yielded");
+    }
+}
@@ -0,0 +1,125 @@
+////////////////////////////////////////////////////////////////
+// CoalesceFifo
+//
+// Event fifo that folds a put equal to the most recently
+// queued element into that element instead of queueing it
+// again. Each entry carries a repeat count, so a burst of
+// identical events ("button still held") costs one slot and
+// the consumer still knows how many there were.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+
+// note length specified is raw storage container.
+// fifo full pointers take up 1 element. so you will need N+1
+pub struct CoalesceFifo<T, const N : usize> {
+    buf: [(T, u32); N],
+    read_ptr: usize,
+    write_ptr: usize,
+}
+
+impl<T : Copy + Default + PartialEq, const N : usize> Default for CoalesceFifo<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T : Copy + Default + PartialEq, const N : usize> CoalesceFifo<T, N> {
+
+    pub fn new() -> Self {
+        CoalesceFifo { buf: [(T::default(), 0); N], read_ptr: 0, write_ptr: 0 }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.read_ptr == self.write_ptr
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        let rp1 : usize = (self.write_ptr+1) % N;
+        rp1 == self.read_ptr
+    }
+
+    // number of entries queued (merged repeats count once).
+    pub fn len(&self) -> usize {
+        if self.read_ptr > self.write_ptr {
+            (N - self.read_ptr) + self.write_ptr
+        } else {
+            self.write_ptr - self.read_ptr
+        }
+    }
+
+    // number of entries that can still be put before the fifo is full.
+    pub fn free_space(&self) -> usize {
+        (N - 1) - self.len()
+    }
+
+    // queue data, or bump the repeat count of the newest entry if it is equal.
+    // returns true if data was merged. a merge never needs space, so it works on a full fifo.
+    pub fn put(&mut self, data : T) -> Result<bool, StaticFifoError> {
+        if !self.is_empty() {
+            let last = (self.write_ptr + N - 1) % N;
+            if self.buf[last].0 == data {
+                self.buf[last].1 = self.buf[last].1.saturating_add(1);
+                return Ok(true);
+            }
+        }
+        if self.is_full() {
+            return Err(StaticFifoError::Full);
+        }
+        self.buf[self.write_ptr] = (data, 1);
+        self.write_ptr = (self.write_ptr + 1) % N;
+        Ok(false)
+    }
+
+    // remove the oldest entry, returns it with how many times it was put in a row.
+    #[inline]
+    pub fn get(&mut self) -> Result<(T, u32), StaticFifoError> {
+        let rv = self.peek()?;
+        self.read_ptr = (self.read_ptr + 1) % N;
+        Ok(rv)
+    }
+
+    // the oldest entry and its repeat count, without removing it.
+    #[inline]
+    pub fn peek(&self) -> Result<(T, u32), StaticFifoError> {
+        if self.is_empty() {
+            return Err(StaticFifoError::Empty);
+        }
+        Ok(self.buf[self.read_ptr])
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn coalesce_fifo_merges() {
+        println!("##################### COALESCE FIFO TEST ######################################");
+
+        let mut fifo : CoalesceFifo<u8, 3> = CoalesceFifo::new();
+
+        assert!(fifo.put(7) == Ok(false), "first put merged");
+        assert!(fifo.put(7) == Ok(true) && fifo.put(7) == Ok(true), "repeat not merged");
+        assert!(fifo.put(8) == Ok(false), "different value merged");
+        assert!(fifo.is_full(), "fifo should be full");
+        assert!(fifo.put(8) == Ok(true), "merge refused on a full fifo");
+        assert!(fifo.put(7) == Err(StaticFifoError::Full), "put on full fifo");
+
+        assert!(fifo.get() == Ok((7, 3)), "first entry or count wrong");
+        // only the newest entry merges, older equal entries do not
+        assert!(fifo.put(7) == Ok(false), "merged into a non adjacent entry");
+        assert!(fifo.get() == Ok((8, 2)), "second entry or count wrong");
+        assert!(fifo.get() == Ok((7, 1)), "third entry or count wrong");
+
+        // once the newest entry is consumed a repeat starts a new one
+        assert!(fifo.put(7) == Ok(false), "merged into a consumed entry");
+        assert!(fifo.peek() == Ok((7, 1)), "peek wrong");
+    }
+}
@@ -0,0 +1,216 @@
+////////////////////////////////////////////////////////////////
+// COBS framing
+//
+// Consistent Overhead Byte Stuffing on top of StaticFifoU8.
+// put_frame() encodes a frame into a tx fifo and terminates
+// it with a 0x00 delimiter. get_frame() pulls one complete
+// frame back out of an rx fifo and decodes it.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoError};
+
+// frame delimiter, never appears inside an encoded frame.
+pub const DELIMITER : u8 = 0x00;
+
+// worst case encoded size of a len byte frame, including the delimiter.
+pub const fn max_encoded_len(len : usize) -> usize {
+    len + (len / 254) + 2
+}
+
+// walk the data and hand every encoded byte to emit (no delimiter).
+fn encode<F : FnMut(u8)>(data : &[u8], mut emit : F) {
+    let mut start : usize = 0;
+    loop {
+        let mut run : usize = 0;
+        while run < 254 && start + run < data.len() && data[start + run] != 0 {
+            run += 1;
+        }
+        emit((run + 1) as u8);
+        for b in &data[start..start + run] {
+            emit(*b);
+        }
+        start += run;
+        if run == 254 {
+            // full block has no implied zero, always follow it with another block.
+            continue;
+        }
+        if start < data.len() {
+            // data[start] is the zero this block stands in for.
+            start += 1;
+            continue;
+        }
+        break;
+    }
+}
+
+// encode data as one frame into the fifo.
+// all or nothing, returns Full without touching the fifo if the frame does not fit.
+pub fn put_frame<const N : usize>(fifo : &mut StaticFifoU8<N>, data : &[u8]) -> Result<(), StaticFifoError> {
+    let mut needed : usize = 1;
+    encode(data, |_| needed += 1);
+    if needed > fifo.free_space() {
+        return Err(StaticFifoError::Full);
+    }
+    // space was checked above, puts can not fail.
+    encode(data, |b| { let _ = fifo.put(b); });
+    let _ = fifo.put(DELIMITER);
+    Ok(())
+}
+
+// decode the encoded bytes in front of the delimiter at offset end.
+fn decode<const N : usize>(fifo : &StaticFifoU8<N>, end : usize, out : &mut [u8]) -> Result<usize, StaticFifoError> {
+    let mut i : usize = 0;
+    let mut n : usize = 0;
+    while i < end {
+        let code = fifo.peek_at(i)? as usize;
+        i += 1;
+        if i + code - 1 > end {
+            return Err(StaticFifoError::Malformed);
+        }
+        for _ in 1..code {
+            if n >= out.len() {
+                return Err(StaticFifoError::TooLarge);
+            }
+            out[n] = fifo.peek_at(i)?;
+            n += 1;
+            i += 1;
+        }
+        if code != 0xFF && i < end {
+            if n >= out.len() {
+                return Err(StaticFifoError::TooLarge);
+            }
+            out[n] = 0;
+            n += 1;
+        }
+    }
+    Ok(n)
+}
+
+// pull one complete frame out of the fifo and decode it into out, returns decoded length.
+// Empty if no complete frame is queued yet (partial frames are left alone).
+// a frame that is Malformed or TooLarge for out is dropped so the link stays in sync.
+// a full fifo with no delimiter in it can never complete a frame, it is dropped as Malformed.
+pub fn get_frame<const N : usize>(fifo : &mut StaticFifoU8<N>, out : &mut [u8]) -> Result<usize, StaticFifoError> {
+    // extra delimiters between frames carry nothing, drop them.
+    while fifo.peek_at(0) == Ok(DELIMITER) {
+        fifo.skip(1);
+    }
+    let end = match fifo.find(DELIMITER) {
+        Some(e) => e,
+        None if fifo.is_full() => {
+            fifo.skip(fifo.len());
+            return Err(StaticFifoError::Malformed);
+        },
+        None => return Err(StaticFifoError::Empty),
+    };
+    let rv = decode(fifo, end, out);
+    fifo.skip(end + 1);
+    rv
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    fn new_fifo() -> StaticFifoU8<600> {
+        StaticFifoU8::<600> { buf: [0;600], read_ptr: 0, write_ptr: 0, capacity: 600, soft_cap: usize::MAX }
+    }
+
+    #[test]
+    fn cobs_round_trip() {
+        println!("##################### COBS ROUND TRIP TEST ######################################");
+
+        let mut fifo = new_fifo();
+        let mut out = [0u8; 300];
+
+        // known encoding from the COBS paper / wikipedia examples
+        put_frame(&mut fifo, &[0x11, 0x22, 0x00, 0x33]).unwrap();
+        let expect = [0x03, 0x11, 0x22, 0x02, 0x33, 0x00];
+        assert!(fifo.len() == expect.len(), "encoded length wrong");
+        for (i, b) in expect.iter().enumerate() {
+            assert!(fifo.peek_at(i) == Ok(*b), "encoded byte {} wrong", i);
+        }
+        assert!(get_frame(&mut fifo, &mut out) == Ok(4), "decoded length wrong");
+        assert!(out[..4] == [0x11, 0x22, 0x00, 0x33], "decoded data wrong");
+        assert!(fifo.is_empty(), "fifo should be empty after reading the only frame");
+
+        // runs around the 254 byte block limit, with and without zeros
+        let mut data = [0u8; 300];
+        for len in [0, 1, 253, 254, 255, 300] {
+            for (i, b) in data.iter_mut().enumerate() {
+                *b = (i % 255) as u8 + 1;
+            }
+            for zeros in [false, true] {
+                if zeros {
+                    data[0] = 0;
+                    data[len / 2] = 0;
+                }
+                put_frame(&mut fifo, &data[..len]).unwrap();
+                assert!(fifo.len() <= max_encoded_len(len), "encoded frame longer than max_encoded_len");
+                match get_frame(&mut fifo, &mut out) {
+                    Ok(n) => { assert!(n == len, "len {} decoded to {}", len, n); assert!(out[..n] == data[..len], "len {} data mismatch", len); },
+                    Err(e) => { panic!("len {} failed to decode {:?}", len, e); },
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cobs_rx_side() {
+        println!("##################### COBS RX TEST ######################################");
+
+        let mut fifo = new_fifo();
+        let mut out = [0u8; 4];
+
+        // partial frame stays queued until its delimiter arrives
+        for b in [0x00, 0x03, 0x11, 0x22] {
+            fifo.put(b).unwrap();
+        }
+        assert!(get_frame(&mut fifo, &mut out) == Err(StaticFifoError::Empty), "partial frame returned");
+        assert!(fifo.len() == 3, "partial frame should be left queued");
+        fifo.put(0x00).unwrap();
+        assert!(get_frame(&mut fifo, &mut out) == Ok(2), "completed frame not returned");
+
+        // code byte running past the delimiter is malformed and dropped
+        for b in [0x05, 0x11, 0x00, 0x02, 0x44, 0x00] {
+            fifo.put(b).unwrap();
+        }
+        assert!(get_frame(&mut fifo, &mut out) == Err(StaticFifoError::Malformed), "malformed frame accepted");
+        assert!(get_frame(&mut fifo, &mut out) == Ok(1) && out[0] == 0x44, "frame after malformed one lost");
+
+        // frame bigger than the output buffer is dropped
+        put_frame(&mut fifo, &[1, 2, 3, 4, 5]).unwrap();
+        put_frame(&mut fifo, &[6]).unwrap();
+        assert!(get_frame(&mut fifo, &mut out) == Err(StaticFifoError::TooLarge), "oversized frame accepted");
+        assert!(get_frame(&mut fifo, &mut out) == Ok(1) && out[0] == 6, "frame after oversized one lost");
+        assert!(fifo.is_empty(), "fifo should be empty");
+
+        // frame that does not fit leaves the tx fifo untouched
+        let mut small = StaticFifoU8::<8> { buf: [0;8], read_ptr: 0, write_ptr: 0, capacity: 8, soft_cap: usize::MAX };
+        small.put(0xAA).unwrap();
+        assert!(put_frame(&mut small, &[1, 2, 3, 4, 5]) == Err(StaticFifoError::Full), "oversized put accepted");
+        assert!(small.len() == 1, "failed put modified the fifo");
+        assert!(put_frame(&mut small, &[1, 2, 3, 4]) == Ok(()), "frame that fits exactly was rejected");
+        assert!(small.is_full(), "fifo should be exactly full");
+    }
+
+    #[test]
+    fn cobs_full_without_delimiter() {
+        println!("##################### COBS FULL RX TEST ######################################");
+
+        // line noise fills the rx fifo without a delimiter
+        let mut fifo = StaticFifoU8::<16> { buf: [0;16], read_ptr: 0, write_ptr: 0, capacity: 16, soft_cap: usize::MAX };
+        let mut out = [0u8; 16];
+        while fifo.put(0x55).is_ok() {}
+        assert!(get_frame(&mut fifo, &mut out) == Err(StaticFifoError::Malformed), "wedged fifo not reported");
+        assert!(fifo.is_empty(), "wedged fifo not dropped");
+
+        // decoder picks up again with the next frame
+        put_frame(&mut fifo, &[0x11, 0x00, 0x22]).unwrap();
+        assert!(get_frame(&mut fifo, &mut out) == Ok(3) && out[..3] == [0x11, 0x00, 0x22], "frame after wedged fifo lost");
+    }
+}
@@ -0,0 +1,287 @@
+////////////////////////////////////////////////////////////////
+// Conversions
+//
+// Build (or reload) a fifo straight from an array / slice of data, copy
+// or turn the queued data back out into a slice / array, and
+// (with the heapless feature) move data between the fifos and
+// heapless::Vec without hand written get / put loops.
+// Data that can not fit in the destination is TooLarge and
+// nothing is converted.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError};
+
+impl<const N : usize> TryFrom<&[u8]> for StaticFifoU8<N> {
+    type Error = StaticFifoError;
+
+    fn try_from(data : &[u8]) -> Result<Self, StaticFifoError> {
+        if data.len() >= N {
+            return Err(StaticFifoError::TooLarge);
+        }
+        let mut rv = Self::new();
+        rv.buf[..data.len()].copy_from_slice(data);
+        rv.write_ptr = data.len();
+        Ok(rv)
+    }
+}
+
+impl<const N : usize, const M : usize> TryFrom<[u8; M]> for StaticFifoU8<N> {
+    type Error = StaticFifoError;
+
+    fn try_from(data : [u8; M]) -> Result<Self, StaticFifoError> {
+        Self::try_from(&data[..])
+    }
+}
+
+impl<const N : usize> TryFrom<&[u32]> for StaticFifoU32<N> {
+    type Error = StaticFifoError;
+
+    fn try_from(data : &[u32]) -> Result<Self, StaticFifoError> {
+        if data.len() >= N {
+            return Err(StaticFifoError::TooLarge);
+        }
+        let mut rv = Self::new();
+        rv.buf[..data.len()].copy_from_slice(data);
+        rv.write_ptr = data.len();
+        Ok(rv)
+    }
+}
+
+impl<const N : usize, const M : usize> TryFrom<[u32; M]> for StaticFifoU32<N> {
+    type Error = StaticFifoError;
+
+    fn try_from(data : [u32; M]) -> Result<Self, StaticFifoError> {
+        Self::try_from(&data[..])
+    }
+}
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // copy the queued data, oldest first, into the front of out without removing it.
+    // returns how many bytes were copied (the smaller of len() and out.len()).
+    pub fn copy_to_slice(&self, out : &mut [u8]) -> usize {
+        let (a, b) = self.as_slices();
+        let first = core::cmp::min(out.len(), a.len());
+        let second = core::cmp::min(out.len() - first, b.len());
+        out[..first].copy_from_slice(&a[..first]);
+        out[first..(first + second)].copy_from_slice(&b[..second]);
+        first + second
+    }
+
+    // the queued data as an array, when exactly M bytes are queued.
+    // otherwise the fifo is handed back untouched.
+    pub fn into_array<const M : usize>(mut self) -> Result<[u8; M], Self> {
+        if self.len() != M {
+            return Err(self);
+        }
+        self.get_array::<M>().map_err(|_| self)
+    }
+
+    // drop whatever is queued and queue data instead. TooLarge and the fifo left
+    // as it was if data does not fit (the soft capacity counts).
+    pub fn replace_contents(&mut self, data : &[u8]) -> Result<(), StaticFifoError> {
+        if data.len() > self.soft_capacity() {
+            return Err(StaticFifoError::TooLarge);
+        }
+        self.clear();
+        self.buf[..data.len()].copy_from_slice(data);
+        self.write_ptr = data.len();
+        Ok(())
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // copy the queued data, oldest first, into the front of out without removing it.
+    // returns how many elements were copied (the smaller of len() and out.len()).
+    pub fn copy_to_slice(&self, out : &mut [u32]) -> usize {
+        let (a, b) = self.as_slices();
+        let first = core::cmp::min(out.len(), a.len());
+        let second = core::cmp::min(out.len() - first, b.len());
+        out[..first].copy_from_slice(&a[..first]);
+        out[first..(first + second)].copy_from_slice(&b[..second]);
+        first + second
+    }
+
+    // the queued data as an array, when exactly M elements are queued.
+    // otherwise the fifo is handed back untouched.
+    pub fn into_array<const M : usize>(mut self) -> Result<[u32; M], Self> {
+        if self.len() != M {
+            return Err(self);
+        }
+        self.get_array::<M>().map_err(|_| self)
+    }
+
+    // drop whatever is queued and queue data instead. TooLarge and the fifo left
+    // as it was if data does not fit (the soft capacity counts).
+    pub fn replace_contents(&mut self, data : &[u32]) -> Result<(), StaticFifoError> {
+        if data.len() > self.soft_capacity() {
+            return Err(StaticFifoError::TooLarge);
+        }
+        self.clear();
+        self.buf[..data.len()].copy_from_slice(data);
+        self.write_ptr = data.len();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N : usize, const M : usize> TryFrom<&heapless::Vec<u8, M>> for StaticFifoU8<N> {
+    type Error = StaticFifoError;
+
+    fn try_from(v : &heapless::Vec<u8, M>) -> Result<Self, StaticFifoError> {
+        Self::try_from(v.as_slice())
+    }
+}
+
+// copy of the queued data, the fifo is left alone.
+#[cfg(feature = "heapless")]
+impl<const N : usize, const M : usize> TryFrom<&StaticFifoU8<N>> for heapless::Vec<u8, M> {
+    type Error = StaticFifoError;
+
+    fn try_from(fifo : &StaticFifoU8<N>) -> Result<Self, StaticFifoError> {
+        let (a, b) = fifo.as_slices();
+        let mut rv = heapless::Vec::new();
+        rv.extend_from_slice(a).map_err(|_| StaticFifoError::TooLarge)?;
+        rv.extend_from_slice(b).map_err(|_| StaticFifoError::TooLarge)?;
+        Ok(rv)
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N : usize, const M : usize> TryFrom<&heapless::Vec<u32, M>> for StaticFifoU32<N> {
+    type Error = StaticFifoError;
+
+    fn try_from(v : &heapless::Vec<u32, M>) -> Result<Self, StaticFifoError> {
+        Self::try_from(v.as_slice())
+    }
+}
+
+// copy of the queued data, the fifo is left alone.
+#[cfg(feature = "heapless")]
+impl<const N : usize, const M : usize> TryFrom<&StaticFifoU32<N>> for heapless::Vec<u32, M> {
+    type Error = StaticFifoError;
+
+    fn try_from(fifo : &StaticFifoU32<N>) -> Result<Self, StaticFifoError> {
+        let (a, b) = fifo.as_slices();
+        let mut rv = heapless::Vec::new();
+        rv.extend_from_slice(a).map_err(|_| StaticFifoError::TooLarge)?;
+        rv.extend_from_slice(b).map_err(|_| StaticFifoError::TooLarge)?;
+        Ok(rv)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn array_conversions() {
+        println!("##################### CONVERSION TEST ######################################");
+
+        let mut fifo = StaticFifoU8::<8>::try_from(*b"abc").unwrap();
+        assert!(fifo.len() == 3 && fifo.get() == Ok(b'a'), "array conversion wrong");
+        assert!(StaticFifoU8::<4>::try_from([0u8; 4]).is_err(), "oversized array accepted");
+
+        let words = StaticFifoU32::<4>::try_from(&[1u32, 2, 3][..]).unwrap();
+        assert!(words.is_full() && words.peek_at(2) == Ok(3), "slice conversion wrong");
+    }
+
+    #[test]
+    fn into_array_snapshot() {
+        println!("##################### INTO ARRAY TEST ######################################");
+
+        let mut fifo : StaticFifoU8<8> = StaticFifoU8::new();
+        for b in [0x1A, 0xCF, 0xFC] {
+            fifo.put(b).unwrap();
+        }
+        // wrong length gives the fifo back as it was
+        let mut fifo = match fifo.into_array::<4>() {
+            Ok(_) => panic!("short record converted"),
+            Err(f) => f,
+        };
+        assert!(fifo.len() == 3, "fifo changed by failed conversion");
+        fifo.put(0x1D).unwrap();
+        assert!(fifo.into_array::<4>().ok() == Some([0x1A, 0xCF, 0xFC, 0x1D]), "record wrong");
+
+        let words = StaticFifoU32::<4>::try_from([1u32, 2]).unwrap();
+        assert!(words.into_array::<2>().ok() == Some([1, 2]), "word record wrong");
+    }
+
+    #[test]
+    fn replace_contents_reload() {
+        println!("##################### REPLACE CONTENTS TEST ######################################");
+
+        // reload a preamble, whatever was left over goes
+        let mut tx : StaticFifoU8<8> = StaticFifoU8::new();
+        tx.put(0xEE).unwrap();
+        tx.get().unwrap();
+        tx.put(0xEE).unwrap();
+        tx.replace_contents(&[0x55, 0x55, 0xD5]).unwrap();
+        assert!(tx.len() == 3 && tx.get() == Ok(0x55) && tx.peek_at(1) == Ok(0xD5), "reload wrong");
+        assert!(tx.replace_contents(&[0; 8]) == Err(StaticFifoError::TooLarge) && tx.len() == 2, "oversize reload touched the fifo");
+
+        let mut words : StaticFifoU32<4> = StaticFifoU32::new();
+        words.replace_contents(&[1, 2, 3]).unwrap();
+        assert!(words.is_full() && words.get() == Ok(1), "word reload wrong");
+        words.replace_contents(&[]).unwrap();
+        assert!(words.is_empty(), "empty reload left data");
+
+        // a soft capacity limits the reload like any other put
+        words.set_soft_capacity(2);
+        assert!(words.replace_contents(&[1, 2, 3]) == Err(StaticFifoError::TooLarge), "reload past soft capacity");
+        assert!(words.replace_contents(&[4, 5]) == Ok(()) && words.is_full(), "reload up to soft capacity refused");
+    }
+
+    #[test]
+    fn copy_to_slice_snapshot() {
+        println!("##################### COPY TO SLICE TEST ######################################");
+
+        let mut fifo : StaticFifoU8<6> = StaticFifoU8::new();
+        for _ in 0..4 {
+            fifo.put(0).unwrap();
+        }
+        fifo.skip(4);
+        for b in 1..=5 {
+            fifo.put(b).unwrap();
+        }
+        let mut dump = [0u8; 8];
+        assert!(fifo.copy_to_slice(&mut dump) == 5 && dump[..5] == [1, 2, 3, 4, 5], "wrapped snapshot wrong");
+        assert!(fifo.copy_to_slice(&mut dump[..3]) == 3 && dump[..3] == [1, 2, 3], "short snapshot wrong");
+        assert!(fifo.len() == 5, "snapshot modified the fifo");
+
+        let words = StaticFifoU32::<4>::try_from([7u32, 8]).unwrap();
+        let mut out = [0u32; 2];
+        assert!(words.copy_to_slice(&mut out) == 2 && out == [7, 8], "word snapshot wrong");
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn heapless_conversions() {
+        println!("##################### HEAPLESS CONVERSION TEST ######################################");
+
+        // wrapped fifo comes out in order
+        let mut fifo : StaticFifoU8<6> = StaticFifoU8::new();
+        for _ in 0..4 {
+            fifo.put(0).unwrap();
+        }
+        fifo.skip(4);
+        for b in 1..=4 {
+            fifo.put(b).unwrap();
+        }
+        let v : heapless::Vec<u8, 8> = (&fifo).try_into().unwrap();
+        assert!(v == [1, 2, 3, 4] && fifo.len() == 4, "vec copy wrong");
+        assert!(heapless::Vec::<u8, 3>::try_from(&fifo).is_err(), "short vec accepted");
+
+        let back = StaticFifoU8::<6>::try_from(&v).unwrap();
+        assert!(back.as_slices().0 == [1, 2, 3, 4], "fifo from vec wrong");
+
+        let w : heapless::Vec<u32, 2> = heapless::Vec::from_slice(&[5, 6]).unwrap();
+        let words = StaticFifoU32::<4>::try_from(&w).unwrap();
+        assert!(heapless::Vec::<u32, 2>::try_from(&words) == Ok(w), "word round trip wrong");
+    }
+}
@@ -0,0 +1,130 @@
+////////////////////////////////////////////////////////////////
+// CRC helpers
+//
+// Small bitwise (table free) CRC routines shared by the
+// framing helpers. Slow but tiny, which is the right trade
+// for the parts this crate targets.
+//
+// crc_of_front() runs one over the next n queued elements
+// without taking them out, so a block's checksum can be
+// checked before committing to drain it. u32 elements go in
+// lsb first, like the crcs stored by FrameFifo.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError};
+use crate::framefifo::RecordCrc;
+
+// CRC-16/X-25, the HDLC / PPP FCS-16.
+// reflected poly 0x1021, init 0xFFFF, final xor 0xFFFF.
+pub const CRC16_INIT : u16 = 0xFFFF;
+
+// value crc16_update() leaves behind after running over data followed by its own crc.
+pub const CRC16_GOOD : u16 = 0xF0B8;
+
+#[inline]
+pub fn crc16_update(crc : u16, data : u8) -> u16 {
+    let mut crc = crc ^ (data as u16);
+    for _ in 0..8 {
+        crc = if (crc & 1) != 0 { (crc >> 1) ^ 0x8408 } else { crc >> 1 };
+    }
+    crc
+}
+
+#[inline]
+pub fn crc16_finish(crc : u16) -> u16 {
+    crc ^ 0xFFFF
+}
+
+pub fn crc16(data : &[u8]) -> u16 {
+    crc16_finish(data.iter().fold(CRC16_INIT, |crc, b| crc16_update(crc, *b)))
+}
+
+// CRC-32 (IEEE 802.3, zlib, ethernet).
+// reflected poly 0x04C11DB7, init 0xFFFFFFFF, final xor 0xFFFFFFFF.
+pub const CRC32_INIT : u32 = 0xFFFF_FFFF;
+
+#[inline]
+pub fn crc32_update(crc : u32, data : u8) -> u32 {
+    let mut crc = crc ^ (data as u32);
+    for _ in 0..8 {
+        crc = if (crc & 1) != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+    }
+    crc
+}
+
+#[inline]
+pub fn crc32_finish(crc : u32) -> u32 {
+    crc ^ 0xFFFF_FFFF
+}
+
+pub fn crc32(data : &[u8]) -> u32 {
+    crc32_finish(data.iter().fold(CRC32_INIT, |crc, b| crc32_update(crc, *b)))
+}
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // crc over the next n bytes, nothing is removed. Empty if fewer than n are queued.
+    pub fn crc_of_front(&self, n : usize, crc : RecordCrc) -> Result<u32, StaticFifoError> {
+        if n > self.len() {
+            return Err(StaticFifoError::Empty);
+        }
+        let (a, b) = self.as_slices();
+        Ok(crc.compute(a.iter().chain(b).take(n).copied()))
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // crc over the next n words (lsb first), nothing is removed. Empty if fewer than n are queued.
+    pub fn crc_of_front(&self, n : usize, crc : RecordCrc) -> Result<u32, StaticFifoError> {
+        if n > self.len() {
+            return Err(StaticFifoError::Empty);
+        }
+        let (a, b) = self.as_slices();
+        Ok(crc.compute(a.iter().chain(b).take(n).flat_map(|w| w.to_le_bytes())))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn crc_check_values() {
+        // standard check value over "123456789"
+        assert!(crc16(b"123456789") == 0x906E, "crc16 check value wrong");
+        assert!(crc32(b"123456789") == 0xCBF4_3926, "crc32 check value wrong");
+
+        // running over the data plus its crc (lsb first) leaves the magic residue
+        let crc = crc16(b"123456789");
+        let residue = [(crc & 0xFF) as u8, (crc >> 8) as u8].iter()
+            .fold(b"123456789".iter().fold(CRC16_INIT, |c, b| crc16_update(c, *b)), |c, b| crc16_update(c, *b));
+        assert!(residue == CRC16_GOOD, "crc16 residue wrong");
+    }
+
+    #[test]
+    fn crc_of_front_wrapped() {
+        // wrap the data around the end of the storage
+        let mut fifo : StaticFifoU8<8> = StaticFifoU8::new();
+        for b in b"xxxxx" {
+            fifo.put(*b).unwrap();
+        }
+        fifo.skip(5);
+        for b in b"123456789" {
+            let _ = fifo.put(*b);
+        }
+        assert!(!fifo.as_slices().1.is_empty(), "data did not wrap");
+        assert!(fifo.crc_of_front(7, RecordCrc::Crc16) == Ok(crc16(b"1234567") as u32), "crc16 over wrap wrong");
+        assert!(fifo.crc_of_front(7, RecordCrc::Crc32) == Ok(crc32(b"1234567")), "crc32 over wrap wrong");
+        assert!(fifo.crc_of_front(8, RecordCrc::Crc16) == Err(StaticFifoError::Empty), "short fifo not refused");
+        assert!(fifo.len() == 7, "crc consumed data");
+
+        let mut words : StaticFifoU32<4> = StaticFifoU32::new();
+        words.put(0x3433_3231).unwrap();
+        words.put(0x3837_3635).unwrap();
+        assert!(words.crc_of_front(2, RecordCrc::Crc32) == Ok(crc32(b"12345678")), "word crc not lsb first");
+    }
+}
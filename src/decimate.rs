@@ -0,0 +1,73 @@
+////////////////////////////////////////////////////////////////
+// Decimating read
+//
+// get_decimated(factor) takes a whole group of factor samples
+// off the read side and returns the first one, the rest are
+// dropped in place. Downsamples a high rate stream without
+// copying every sample through application code.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError};
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // first sample of the next group of factor samples, the group is removed.
+    // Empty (nothing removed) until a whole group is queued, so groups stay aligned.
+    // a factor of 0 is treated as 1.
+    pub fn get_decimated(&mut self, factor : usize) -> Result<u8, StaticFifoError> {
+        let factor = core::cmp::max(factor, 1);
+        if self.len() < factor {
+            return Err(StaticFifoError::Empty);
+        }
+        let rv = self.get()?;
+        self.skip(factor - 1);
+        Ok(rv)
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // first sample of the next group of factor samples, the group is removed.
+    // Empty (nothing removed) until a whole group is queued, so groups stay aligned.
+    // a factor of 0 is treated as 1.
+    pub fn get_decimated(&mut self, factor : usize) -> Result<u32, StaticFifoError> {
+        let factor = core::cmp::max(factor, 1);
+        if self.len() < factor {
+            return Err(StaticFifoError::Empty);
+        }
+        let rv = self.get()?;
+        self.skip(factor - 1);
+        Ok(rv)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn decimated_read() {
+        println!("##################### DECIMATE TEST ######################################");
+
+        let mut adc : StaticFifoU32<16> = StaticFifoU32::new();
+        for v in 0..10 {
+            adc.put(v * 100).unwrap();
+        }
+        assert!(adc.get_decimated(4) == Ok(0), "first group wrong");
+        assert!(adc.get_decimated(4) == Ok(400), "second group wrong");
+        assert!(adc.get_decimated(4) == Err(StaticFifoError::Empty), "partial group returned");
+        assert!(adc.len() == 2, "partial group consumed");
+        assert!(adc.get_decimated(0) == Ok(800), "factor 0 not treated as 1");
+
+        let mut bytes : StaticFifoU8<8> = StaticFifoU8::new();
+        for b in 1..=6 {
+            bytes.put(b).unwrap();
+        }
+        assert!(bytes.get_decimated(3) == Ok(1) && bytes.get_decimated(3) == Ok(4), "byte decimation wrong");
+        assert!(bytes.is_empty(), "byte fifo should be empty");
+    }
+}
@@ -0,0 +1,90 @@
+////////////////////////////////////////////////////////////////
+// Deduplicating put
+//
+// put variants that first look for a queued element with the
+// same key (worked out by a caller supplied key function), so
+// a scheduler never queues the same piece of work twice. The
+// duplicate is either rejected or refreshed in place with the
+// new value, keeping its spot in the queue.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError};
+
+// what to do when a put finds an element with the same key already queued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dedup {
+    // leave the queued element alone and drop the new one
+    Reject,
+    // overwrite the queued element with the new one, it keeps its place
+    Refresh,
+}
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // put data unless an element with the same key is already queued.
+    // returns true if data was queued, false if it matched a queued element (see Dedup).
+    pub fn put_unique_by<K : PartialEq, F : Fn(u8) -> K>(&mut self, data : u8, key : F, mode : Dedup) -> Result<bool, StaticFifoError> {
+        let k = key(data);
+        match (0..self.len()).map(|i| (self.read_ptr + i) % self.capacity).find(|&i| key(self.buf[i]) == k) {
+            Some(i) => {
+                if mode == Dedup::Refresh {
+                    self.buf[i] = data;
+                }
+                Ok(false)
+            },
+            None => self.put(data).map(|_| true),
+        }
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // put data unless an element with the same key is already queued.
+    // returns true if data was queued, false if it matched a queued element (see Dedup).
+    pub fn put_unique_by<K : PartialEq, F : Fn(u32) -> K>(&mut self, data : u32, key : F, mode : Dedup) -> Result<bool, StaticFifoError> {
+        let k = key(data);
+        match (0..self.len()).map(|i| (self.read_ptr + i) % self.capacity).find(|&i| key(self.buf[i]) == k) {
+            Some(i) => {
+                if mode == Dedup::Refresh {
+                    self.buf[i] = data;
+                }
+                Ok(false)
+            },
+            None => self.put(data).map(|_| true),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn put_unique() {
+        println!("##################### DEDUP PUT TEST ######################################");
+
+        // commands as opcode << 16 | argument, one pending command per opcode
+        let opcode = |c : u32| c >> 16;
+        let mut fifo : StaticFifoU32<4> = StaticFifoU32::new();
+
+        assert!(fifo.put_unique_by(0x0001_0005, opcode, Dedup::Reject) == Ok(true), "first command not queued");
+        assert!(fifo.put_unique_by(0x0002_0000, opcode, Dedup::Reject) == Ok(true), "second command not queued");
+        assert!(fifo.put_unique_by(0x0001_0009, opcode, Dedup::Reject) == Ok(false), "duplicate queued");
+        assert!(fifo.peek_at(0) == Ok(0x0001_0005), "rejected duplicate changed the queue");
+        assert!(fifo.put_unique_by(0x0001_0009, opcode, Dedup::Refresh) == Ok(false), "duplicate queued on refresh");
+        assert!(fifo.len() == 2 && fifo.peek_at(0) == Ok(0x0001_0009), "refresh did not update in place");
+
+        fifo.put(0x0003_0000).unwrap();
+        assert!(fifo.put_unique_by(0x0004_0000, opcode, Dedup::Reject) == Err(StaticFifoError::Full), "put on full fifo");
+        assert!(fifo.put_unique_by(0x0003_0001, opcode, Dedup::Refresh) == Ok(false), "refresh refused on a full fifo");
+
+        let mut bytes : StaticFifoU8<8> = StaticFifoU8::new();
+        assert!(bytes.put_unique_by(b'a', |b| b.to_ascii_lowercase(), Dedup::Reject) == Ok(true), "byte not queued");
+        assert!(bytes.put_unique_by(b'A', |b| b.to_ascii_lowercase(), Dedup::Reject) == Ok(false), "byte duplicate queued");
+        assert!(bytes.len() == 1, "byte fifo length wrong");
+    }
+}
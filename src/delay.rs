@@ -0,0 +1,88 @@
+////////////////////////////////////////////////////////////////
+// Delay line
+//
+// Lets a fifo run at fixed occupancy as a DSP delay line:
+// shift_in() drops the oldest sample once the fifo is full,
+// and tap(k) reads the k-th most recent sample, which is what
+// FIR filters and moving averages need.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError};
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // put data, making room by dropping the oldest sample if full.
+    // returns the sample that fell off the end, if any.
+    #[inline]
+    pub fn shift_in(&mut self, data : u8) -> Option<u8> {
+        let rv = if self.is_full() { self.get().ok() } else { None };
+        let _ = self.put(data);
+        rv
+    }
+
+    // k-th most recent sample, tap(0) is the newest one.
+    #[inline]
+    pub fn tap(&self, k : usize) -> Result<u8, StaticFifoError> {
+        if k >= self.len() {
+            return Err(StaticFifoError::Empty);
+        }
+        self.peek_at(self.len() - 1 - k)
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // put data, making room by dropping the oldest sample if full.
+    // returns the sample that fell off the end, if any.
+    #[inline]
+    pub fn shift_in(&mut self, data : u32) -> Option<u32> {
+        let rv = if self.is_full() { self.get().ok() } else { None };
+        let _ = self.put(data);
+        rv
+    }
+
+    // k-th most recent sample, tap(0) is the newest one.
+    #[inline]
+    pub fn tap(&self, k : usize) -> Result<u32, StaticFifoError> {
+        if k >= self.len() {
+            return Err(StaticFifoError::Empty);
+        }
+        self.peek_at(self.len() - 1 - k)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn delay_line_taps() {
+        println!("##################### DELAY LINE TEST ######################################");
+
+        // 4 tap moving sum, the dropped sample keeps the running sum O(1)
+        let mut line : StaticFifoU32<5> = StaticFifoU32::new();
+        let mut sum : u32 = 0;
+        let mut sums = [0u32; 6];
+        for (i, x) in [1, 2, 3, 4, 5, 6].iter().enumerate() {
+            sum += x;
+            sum -= line.shift_in(*x).unwrap_or(0);
+            sums[i] = sum;
+        }
+        assert!(sums == [1, 3, 6, 10, 14, 18], "moving sum wrong");
+        assert!(line.len() == 4, "occupancy not fixed");
+
+        // taps newest first
+        assert!(line.tap(0) == Ok(6) && line.tap(3) == Ok(3), "taps wrong");
+        assert!(line.tap(4) == Err(StaticFifoError::Empty), "tap past the line");
+        let fir : u32 = [1, 2, 2, 1].iter().enumerate().map(|(k, c)| c * line.tap(k).unwrap()).sum();
+        assert!(fir == 6 + 10 + 8 + 3, "fir output wrong");
+
+        let mut bytes : StaticFifoU8<3> = StaticFifoU8::new();
+        assert!(bytes.shift_in(1).is_none() && bytes.shift_in(2).is_none(), "sample dropped early");
+        assert!(bytes.shift_in(3) == Some(1) && bytes.tap(1) == Ok(2), "byte delay line wrong");
+    }
+}
@@ -0,0 +1,181 @@
+////////////////////////////////////////////////////////////////
+// Delta encoded u32 fifo
+//
+// For timestamps and counters that only ever creep upwards:
+// each value is stored as its difference from the one put
+// before it, as a LEB128 varint (7 bits a byte), and added back
+// up on the way out. Steps below 128 take one byte instead of
+// four, so the same storage holds up to four times as many.
+//
+// Differences wrap, so any sequence goes in, a step backwards
+// just takes the full five bytes. N is the byte storage, with
+// one byte lost to the full marker as usual.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoError};
+use crate::traits::{FifoProducer, FifoConsumer};
+
+// bytes a u32 takes at most as a varint.
+pub const MAX_VARINT_LEN : usize = 5;
+
+pub struct DeltaFifoU32<const N : usize> {
+    bytes: StaticFifoU8<N>,
+    // values queued
+    count: usize,
+    // last value put / got, the deltas are against these
+    last_in: u32,
+    last_out: u32,
+}
+
+impl<const N : usize> Default for DeltaFifoU32<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N : usize> DeltaFifoU32<N> {
+
+    pub const fn new() -> Self {
+        DeltaFifoU32 { bytes: StaticFifoU8::new(), count: 0, last_in: 0, last_out: 0 }
+    }
+
+    pub fn init(&mut self) {
+        self.bytes.init();
+        self.count = 0;
+        self.last_in = 0;
+        self.last_out = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    // bytes of storage in use.
+    pub fn bytes_used(&self) -> usize {
+        self.bytes.len()
+    }
+
+    // Full (nothing stored) if the encoded step does not fit.
+    pub fn put(&mut self, data : u32) -> Result<(), StaticFifoError> {
+        let mut enc = [0u8; MAX_VARINT_LEN];
+        let n = encode_varint(data.wrapping_sub(self.last_in), &mut enc);
+        if n > self.bytes.free_space() {
+            return Err(StaticFifoError::Full);
+        }
+        for b in &enc[..n] {
+            let _ = self.bytes.put(*b);
+        }
+        self.last_in = data;
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn get(&mut self) -> Result<u32, StaticFifoError> {
+        let (delta, n) = self.front()?;
+        self.bytes.skip(n);
+        self.count -= 1;
+        self.last_out = self.last_out.wrapping_add(delta);
+        Ok(self.last_out)
+    }
+
+    // next value without removing it.
+    pub fn peek(&self) -> Result<u32, StaticFifoError> {
+        self.front().map(|(delta, _)| self.last_out.wrapping_add(delta))
+    }
+
+    // decode the step at the front, (step, bytes it takes).
+    fn front(&self) -> Result<(u32, usize), StaticFifoError> {
+        if self.count == 0 {
+            return Err(StaticFifoError::Empty);
+        }
+        let mut v : u32 = 0;
+        for i in 0..MAX_VARINT_LEN {
+            let b = self.bytes.peek_at(i).map_err(|_| StaticFifoError::Corrupt)?;
+            v |= ((b & 0x7F) as u32) << (7 * i);
+            if b & 0x80 == 0 {
+                return Ok((v, i + 1));
+            }
+        }
+        Err(StaticFifoError::Corrupt)
+    }
+}
+
+// LEB128, returns the bytes used.
+fn encode_varint(mut v : u32, out : &mut [u8; MAX_VARINT_LEN]) -> usize {
+    let mut n = 0;
+    loop {
+        let b = (v & 0x7F) as u8;
+        v >>= 7;
+        if v == 0 {
+            out[n] = b;
+            return n + 1;
+        }
+        out[n] = b | 0x80;
+        n += 1;
+    }
+}
+
+impl<const N : usize> FifoProducer<u32> for DeltaFifoU32<N> {
+
+    fn put(&mut self, data : u32) -> Result<(), StaticFifoError> {
+        DeltaFifoU32::put(self, data)
+    }
+
+    // values that surely fit, taking the worst case step for each.
+    fn free_space(&self) -> usize {
+        self.bytes.free_space() / MAX_VARINT_LEN
+    }
+}
+
+impl<const N : usize> FifoConsumer<u32> for DeltaFifoU32<N> {
+
+    fn get(&mut self) -> Result<u32, StaticFifoError> {
+        DeltaFifoU32::get(self)
+    }
+
+    fn len(&self) -> usize {
+        DeltaFifoU32::len(self)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn delta_timestamps() {
+        println!("##################### DELTA FIFO TEST ######################################");
+
+        // 1 ms ticks from a large start, one byte each after the first
+        let mut ts : DeltaFifoU32<26> = DeltaFifoU32::new();
+        ts.put(1_000_000).unwrap();
+        let first = ts.bytes_used();
+        for t in 1..=20 {
+            ts.put(1_000_000 + t).unwrap();
+        }
+        assert!(first == 3 && ts.bytes_used() == 23 && ts.len() == 21, "steps not stored small");
+
+        // a step back costs the full 5 bytes, and does not fit any more
+        assert!(ts.put(5) == Err(StaticFifoError::Full) && ts.len() == 21, "oversize step stored");
+
+        assert!(ts.peek() == Ok(1_000_000) && ts.get() == Ok(1_000_000), "first value wrong");
+        for t in 1..=10 {
+            assert!(ts.get() == Ok(1_000_000 + t), "reconstructed value wrong");
+        }
+        ts.put(5).unwrap();
+        ts.put(u32::MAX).unwrap();
+        for t in 11..=20 {
+            assert!(ts.get() == Ok(1_000_000 + t), "wrapped value wrong");
+        }
+        assert!(ts.get() == Ok(5) && ts.get() == Ok(u32::MAX), "backward steps wrong");
+        assert!(ts.get() == Err(StaticFifoError::Empty) && ts.bytes_used() == 0, "fifo not drained");
+    }
+}
@@ -0,0 +1,161 @@
+////////////////////////////////////////////////////////////////
+// Demux
+//
+// Splits a multiplexed serial link into per channel queues.
+// Records on the link are
+//
+//   [tag] [len] [len bytes of payload]
+//
+// and route() moves the payload of each complete record into
+// output fifo number tag, as a plain byte stream. A record
+// only goes once all of it has arrived, so a partly received
+// one just waits in the input.
+//
+// Like Tee, the channels do not hold each other up: a record
+// for a full output (or one without room for all of it) is
+// dropped whole and counted, as are records with a tag past
+// the last output. A record longer than the input can ever hold
+// would wait forever, it is counted as oversize and its bytes
+// are dropped as they arrive.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoU8;
+use crate::bytefifo::ByteFifo;
+
+// tag and length in front of every payload.
+pub const HEADER_LEN : usize = 2;
+
+pub struct Demux<'a, const K : usize> {
+    outputs: [&'a mut dyn ByteFifo; K],
+    dropped: [usize; K],
+    unknown: usize,
+    oversize: usize,
+    // bytes of an oversize record still to drop
+    discard: usize,
+}
+
+impl<'a, const K : usize> Demux<'a, K> {
+
+    pub fn new(outputs : [&'a mut dyn ByteFifo; K]) -> Self {
+        Demux { outputs, dropped: [0; K], unknown: 0, oversize: 0, discard: 0 }
+    }
+
+    // route every complete record in input, returns how many were delivered.
+    pub fn route<const N : usize>(&mut self, input : &mut StaticFifoU8<N>) -> usize {
+        let mut delivered = 0;
+        let n = core::cmp::min(self.discard, input.len());
+        input.skip(n);
+        self.discard -= n;
+        if self.discard > 0 {
+            return delivered;
+        }
+        while let (Ok(tag), Ok(len)) = (input.peek_at(0), input.peek_at(1)) {
+            let len = len as usize;
+            if HEADER_LEN + len > input.soft_capacity() {
+                let n = core::cmp::min(HEADER_LEN + len, input.len());
+                input.skip(n);
+                self.discard = HEADER_LEN + len - n;
+                self.oversize += 1;
+                continue;
+            }
+            if input.len() < HEADER_LEN + len {
+                break;
+            }
+            input.skip(HEADER_LEN);
+            match self.outputs.get_mut(tag as usize) {
+                Some(out) if out.free_space() >= len => {
+                    let (a, b) = input.as_slices();
+                    let first = core::cmp::min(len, a.len());
+                    out.put_slice(&a[..first]);
+                    out.put_slice(&b[..(len - first)]);
+                    delivered += 1;
+                }
+                Some(_) => self.dropped[tag as usize] += 1,
+                None => self.unknown += 1,
+            }
+            input.skip(len);
+        }
+        delivered
+    }
+
+    // records dropped because output i had no room.
+    pub fn dropped(&self, i : usize) -> usize {
+        self.dropped[i]
+    }
+
+    // records dropped for a tag without an output.
+    pub fn unknown(&self) -> usize {
+        self.unknown
+    }
+
+    // records dropped for being longer than the input can hold.
+    pub fn oversize(&self) -> usize {
+        self.oversize
+    }
+
+    // give the outputs back.
+    pub fn into_inner(self) -> [&'a mut dyn ByteFifo; K] {
+        self.outputs
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn route_tagged_records() {
+        println!("##################### DEMUX TEST ######################################");
+
+        let mut link : StaticFifoU8<32> = StaticFifoU8::new();
+        let mut gps : StaticFifoU8<16> = StaticFifoU8::new();
+        let mut console : StaticFifoU8<4> = StaticFifoU8::new();
+
+        // gps, console, unknown tag, then half of a gps record
+        link.put_slice(&[0, 3, b'$', b'G', b'P']);
+        link.put_slice(&[1, 2, b'o', b'k']);
+        link.put_slice(&[7, 1, 0xAA]);
+        link.put_slice(&[0, 2, b'R']);
+
+        let mut demux = Demux::new([&mut gps, &mut console]);
+        assert!(demux.route(&mut link) == 2 && demux.unknown() == 1, "routing wrong");
+        assert!(link.len() == 3, "partial record not left waiting");
+
+        // rest arrives, plus one too big for the console
+        link.put(b'M').unwrap();
+        link.put_slice(&[1, 3, b'x', b'y', b'z']);
+        assert!(demux.route(&mut link) == 1 && demux.dropped(1) == 1 && link.is_empty(), "full output not dropped");
+
+        let [gps, console] = demux.into_inner();
+        let mut out = [0u8; 8];
+        assert!(gps.get_slice(&mut out) == 5 && &out[..5] == b"$GPRM", "gps stream wrong");
+        assert!(console.get_slice(&mut out) == 2 && &out[..2] == b"ok", "console stream wrong");
+    }
+    #[test]
+    fn oversize_record() {
+        println!("##################### DEMUX OVERSIZE TEST ######################################");
+
+        // a length byte past what the link fifo can hold
+        let mut link : StaticFifoU8<8> = StaticFifoU8::new();
+        let mut gps : StaticFifoU8<16> = StaticFifoU8::new();
+        link.put_slice(&[0, 9, 1, 2, 3, 4, 5]);
+
+        let mut demux = Demux::new([&mut gps]);
+        assert!(demux.route(&mut link) == 0 && demux.oversize() == 1 && link.is_empty(), "oversize record not dropped");
+
+        // the rest of it is dropped as it arrives, the next record goes through
+        link.put_slice(&[6, 7, 8, 9, 0, 2, b'o', b'k']);
+        assert!(link.is_full(), "test setup wrong");
+        assert!(demux.route(&mut link) == 0 && link.len() == 3, "tail of oversize record not dropped");
+        link.put(b'k').unwrap();
+        assert!(demux.route(&mut link) == 1 && link.is_empty(), "record after oversize one lost");
+
+        let [gps] = demux.into_inner();
+        let mut out = [0u8; 4];
+        assert!(gps.get_slice(&mut out) == 2 && &out[..2] == b"ok", "gps stream wrong");
+    }
+}
@@ -0,0 +1,168 @@
+////////////////////////////////////////////////////////////////
+// StaticDeque
+//
+// Fixed capacity double ended queue, same ring layout as the
+// fifos (read side = front, write side = back) with pushes and
+// pops allowed at both ends. No dynamic memory.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+
+// note length specified is raw storage container.
+// full pointers take up 1 element. so you will need N+1
+pub struct StaticDeque<T, const N : usize> {
+    buf: [T; N],
+    front: usize,
+    back: usize,
+}
+
+impl<T : Copy + Default, const N : usize> Default for StaticDeque<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T : Copy + Default, const N : usize> StaticDeque<T, N> {
+
+    pub fn new() -> Self {
+        StaticDeque { buf: [T::default(); N], front: 0, back: 0 }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.front = 0;
+        self.back = 0;
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.front == self.back
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        (self.back + 1) % N == self.front
+    }
+
+    pub fn len(&self) -> usize {
+        if self.front > self.back {
+            (N - self.front) + self.back
+        } else {
+            self.back - self.front
+        }
+    }
+
+    pub fn max_len(&self) -> usize {
+        N
+    }
+
+    // number of elements that can still be pushed before the deque is full.
+    pub fn free_space(&self) -> usize {
+        (N - 1) - self.len()
+    }
+
+    #[inline]
+    pub fn push_back(&mut self, data : T) -> Result<(), StaticFifoError> {
+        if self.is_full() {
+            return Err(StaticFifoError::Full);
+        }
+        self.buf[self.back] = data;
+        self.back = (self.back + 1) % N;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn push_front(&mut self, data : T) -> Result<(), StaticFifoError> {
+        if self.is_full() {
+            return Err(StaticFifoError::Full);
+        }
+        self.front = (self.front + N - 1) % N;
+        self.buf[self.front] = data;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn pop_front(&mut self) -> Result<T, StaticFifoError> {
+        let rv = self.front()?;
+        self.front = (self.front + 1) % N;
+        Ok(rv)
+    }
+
+    #[inline]
+    pub fn pop_back(&mut self) -> Result<T, StaticFifoError> {
+        let rv = self.back()?;
+        self.back = (self.back + N - 1) % N;
+        Ok(rv)
+    }
+
+    #[inline]
+    pub fn front(&self) -> Result<T, StaticFifoError> {
+        self.peek_at(0)
+    }
+
+    #[inline]
+    pub fn back(&self) -> Result<T, StaticFifoError> {
+        match self.len() {
+            0 => Err(StaticFifoError::Empty),
+            n => self.peek_at(n - 1),
+        }
+    }
+
+    // look at the element i places from the front without removing it.
+    #[inline]
+    pub fn peek_at(&self, i : usize) -> Result<T, StaticFifoError> {
+        if i >= self.len() {
+            return Err(StaticFifoError::Empty);
+        }
+        Ok(self.buf[(self.front + i) % N])
+    }
+
+    // take out the element i places from the front. the ones in front of it move
+    // back one slot, so the order of the rest is kept.
+    pub fn remove(&mut self, i : usize) -> Result<T, StaticFifoError> {
+        let rv = self.peek_at(i)?;
+        for j in (0..i).rev() {
+            self.buf[(self.front + j + 1) % N] = self.buf[(self.front + j) % N];
+        }
+        self.front = (self.front + 1) % N;
+        Ok(rv)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn deque_both_ends() {
+        println!("##################### DEQUE TEST ######################################");
+
+        let mut dq : StaticDeque<i16, 5> = StaticDeque::new();
+
+        assert!(dq.pop_front() == Err(StaticFifoError::Empty) && dq.back().is_err(), "empty deque returned data");
+        dq.push_back(2).unwrap();
+        dq.push_front(1).unwrap();
+        dq.push_back(3).unwrap();
+        dq.push_front(0).unwrap();
+        assert!(dq.is_full() && dq.push_front(-1) == Err(StaticFifoError::Full), "push on full deque");
+        assert!(dq.front() == Ok(0) && dq.back() == Ok(3), "ends wrong");
+        assert!((0..4).all(|i| dq.peek_at(i) == Ok(i as i16)), "order wrong across the wrap");
+
+        // undo buffer style, newest off the back
+        assert!(dq.pop_back() == Ok(3) && dq.pop_back() == Ok(2), "pop_back wrong");
+        assert!(dq.pop_front() == Ok(0), "pop_front wrong");
+        assert!(dq.len() == 1 && dq.free_space() == 3, "length wrong");
+        assert!(dq.pop_back() == Ok(1) && dq.is_empty(), "deque should be empty");
+
+        // out of the middle, across the wrap
+        for v in [4, 5, 6, 7] {
+            dq.push_back(v).unwrap();
+        }
+        assert!(dq.remove(2) == Ok(6) && dq.remove(5).is_err(), "remove wrong");
+        assert!(dq.len() == 3 && (0..3).all(|i| dq.peek_at(i) == Ok([4, 5, 7][i])), "order after remove wrong");
+    }
+}
@@ -0,0 +1,252 @@
+////////////////////////////////////////////////////////////////
+// ECC protected fifo
+//
+// Byte fifo whose storage holds every element as a Hamming
+// SECDED codeword (8 data bits, 4 check bits, 1 overall
+// parity bit). Single bit flips are corrected on the way out,
+// double flips are detected. scrub() walks the queued data
+// and repairs flips before a second one can land on the same
+// element, which is what keeps SEUs correctable.
+//
+// Only the element storage is protected, the read / write
+// pointers are plain words.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+
+// bit positions (1 based Hamming positions) of the 8 data bits in a codeword.
+const DATA_POS : [u32; 8] = [3, 5, 6, 7, 9, 10, 11, 12];
+
+// bits a codeword uses, position 0 is the overall parity.
+const CODEWORD_MASK : u16 = 0x1FFF;
+
+// what decode() found wrong with a codeword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EccStatus {
+    Clean,
+    // single bit flip, the data returned is the corrected value
+    Corrected,
+    // two (or more) bits flipped, data can not be trusted
+    Uncorrectable,
+}
+
+// xor of the positions of all set bits, 0 for a clean codeword.
+#[inline]
+fn syndrome(cw : u16) -> u32 {
+    (1..13).filter(|&p| (cw >> p) & 1 != 0).fold(0, |s, p| s ^ p)
+}
+
+// Hamming SECDED codeword for data.
+pub fn encode(data : u8) -> u16 {
+    let mut cw : u16 = 0;
+    for (k, p) in DATA_POS.iter().enumerate() {
+        if (data >> k) & 1 != 0 {
+            cw |= 1 << p;
+        }
+    }
+    // set the check bits so the syndrome comes out 0.
+    let s = syndrome(cw);
+    for p in [1, 2, 4, 8] {
+        if s & p != 0 {
+            cw |= 1 << p;
+        }
+    }
+    cw | (cw.count_ones() & 1) as u16
+}
+
+// data held in cw and whether it had to be corrected.
+// with Uncorrectable the returned data is whatever the damaged bits say.
+pub fn decode(cw : u16) -> (u8, EccStatus) {
+    let mut status = if cw & !CODEWORD_MASK != 0 { EccStatus::Corrected } else { EccStatus::Clean };
+    let mut cw = cw & CODEWORD_MASK;
+    let s = syndrome(cw);
+    let odd = cw.count_ones() & 1 != 0;
+    if odd {
+        // one flip, the syndrome points at it (0 means the overall parity bit).
+        if s > 12 {
+            status = EccStatus::Uncorrectable;
+        } else {
+            cw ^= 1 << s;
+            status = EccStatus::Corrected;
+        }
+    } else if s != 0 {
+        status = EccStatus::Uncorrectable;
+    }
+    let data = DATA_POS.iter().enumerate()
+        .fold(0u8, |d, (k, p)| d | ((((cw >> p) & 1) as u8) << k));
+    (data, status)
+}
+
+// result of one scrub() pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScrubReport {
+    // elements that had a single flip and were rewritten clean
+    pub corrected: usize,
+    // elements with more flips than ECC can fix, left in place (get() reports them as Corrupt)
+    pub uncorrectable: usize,
+}
+
+// note length specified is raw storage container.
+// fifo full pointers take up 1 element. so you will need N+1
+// each element takes 2 bytes of storage.
+pub struct EccFifoU8<const N : usize> {
+    buf: [u16; N],
+    read_ptr: usize,
+    write_ptr: usize,
+    capacity: usize,
+}
+
+impl<const N : usize> Default for EccFifoU8<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N : usize> EccFifoU8<N> {
+
+    // create new empty EccFifoU8
+    pub const fn new() -> Self {
+        EccFifoU8 { buf: [0; N], read_ptr: 0, write_ptr: 0, capacity: N }
+    }
+
+    #[inline]
+    pub fn init(&mut self) {
+        self.read_ptr = 0;
+        self.write_ptr = 0;
+        self.capacity = N;
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.read_ptr == self.write_ptr
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        let rp1 : usize = (self.write_ptr+1) % self.capacity;
+        rp1 == self.read_ptr
+    }
+
+    pub fn len(&self) -> usize {
+        if self.read_ptr > self.write_ptr {
+            (self.capacity - self.read_ptr) + self.write_ptr
+        } else {
+            self.write_ptr - self.read_ptr
+        }
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.capacity
+    }
+
+    // number of elements that can still be put before the fifo is full.
+    pub fn free_space(&self) -> usize {
+        (self.capacity - 1) - self.len()
+    }
+
+    #[inline]
+    pub fn put(&mut self, data : u8) -> Result<(), StaticFifoError> {
+        if self.is_full() {
+            return Err(StaticFifoError::Full);
+        }
+        self.buf[self.write_ptr] = encode(data);
+        self.write_ptr = (self.write_ptr + 1) % self.capacity;
+        Ok(())
+    }
+
+    // remove the next element, single bit flips are corrected on the way out.
+    // an element that can not be corrected is dropped and reported as Corrupt.
+    #[inline]
+    pub fn get(&mut self) -> Result<u8, StaticFifoError> {
+        if self.is_empty() {
+            return Err(StaticFifoError::Empty);
+        }
+        let (rv, status) = decode(self.buf[self.read_ptr]);
+        self.read_ptr = (self.read_ptr + 1) % self.capacity;
+        if status == EccStatus::Uncorrectable {
+            return Err(StaticFifoError::Corrupt);
+        }
+        Ok(rv)
+    }
+
+    // look at the element i places from the read side without removing it.
+    // Corrupt if it can not be corrected.
+    pub fn peek_at(&self, i : usize) -> Result<u8, StaticFifoError> {
+        if i >= self.len() {
+            return Err(StaticFifoError::Empty);
+        }
+        match decode(self.buf[(self.read_ptr + i) % self.capacity]) {
+            (_, EccStatus::Uncorrectable) => Err(StaticFifoError::Corrupt),
+            (v, _) => Ok(v),
+        }
+    }
+
+    // walk the queued elements and rewrite every corrected one clean.
+    // call it periodically, often enough that two flips rarely meet in one element.
+    pub fn scrub(&mut self) -> ScrubReport {
+        let mut report = ScrubReport::default();
+        for i in 0..self.len() {
+            let idx = (self.read_ptr + i) % self.capacity;
+            match decode(self.buf[idx]) {
+                (v, EccStatus::Corrected) => {
+                    self.buf[idx] = encode(v);
+                    report.corrected += 1;
+                },
+                (_, EccStatus::Uncorrectable) => report.uncorrectable += 1,
+                (_, EccStatus::Clean) => (),
+            }
+        }
+        report
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn ecc_codeword() {
+        println!("##################### ECC CODEWORD TEST ######################################");
+
+        for d in 0..=255u8 {
+            let cw = encode(d);
+            assert!(decode(cw) == (d, EccStatus::Clean), "clean codeword decoded wrong");
+            for a in 0..16 {
+                assert!(decode(cw ^ (1 << a)) == (d, EccStatus::Corrected), "single flip not corrected");
+                for b in (a + 1)..13 {
+                    assert!(decode(cw ^ (1 << a) ^ (1 << b)).1 == EccStatus::Uncorrectable, "double flip not detected");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ecc_fifo_scrub() {
+        println!("##################### ECC FIFO TEST ######################################");
+
+        let mut fifo : EccFifoU8<8> = EccFifoU8::new();
+
+        for b in [10, 20, 30, 40] {
+            fifo.put(b).unwrap();
+        }
+        assert!(fifo.scrub() == ScrubReport::default(), "clean fifo reported errors");
+
+        // one flip in the first element, two in the third
+        fifo.buf[0] ^= 1 << 5;
+        fifo.buf[2] ^= (1 << 3) | (1 << 9);
+        assert!(fifo.peek_at(0) == Ok(10), "peek did not correct");
+        assert!(fifo.scrub() == ScrubReport { corrected: 1, uncorrectable: 1 }, "scrub report wrong");
+        assert!(fifo.buf[0] == encode(10), "scrub did not rewrite the element");
+        assert!(fifo.scrub() == ScrubReport { corrected: 0, uncorrectable: 1 }, "second scrub report wrong");
+
+        assert!(fifo.get() == Ok(10), "first element wrong");
+        assert!(fifo.get() == Ok(20), "second element wrong");
+        assert!(fifo.get() == Err(StaticFifoError::Corrupt), "uncorrectable element delivered");
+        assert!(fifo.get() == Ok(40), "element after corrupt one wrong");
+        assert!(fifo.is_empty(), "fifo should be empty");
+    }
+}
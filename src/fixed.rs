@@ -0,0 +1,188 @@
+////////////////////////////////////////////////////////////////
+// Fixed length frames
+//
+// Pulls whole fixed size frames out of a StaticFifoU8 and
+// never hands out a partial one. Frame length is either a
+// const generic (get_array) or picked at runtime through the
+// FixedFrames adapter, which can copy a frame out or lend it
+// in place as a grant.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError};
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // remove exactly L bytes as an array, Empty (and nothing removed) if fewer are queued.
+    pub fn get_array<const L : usize>(&mut self) -> Result<[u8; L], StaticFifoError> {
+        if self.len() < L {
+            return Err(StaticFifoError::Empty);
+        }
+        let mut rv : [u8; L] = [0; L];
+        for b in rv.iter_mut() {
+            *b = self.get()?;
+        }
+        Ok(rv)
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // remove exactly L elements as an array, Empty (and nothing removed) if fewer are queued.
+    pub fn get_array<const L : usize>(&mut self) -> Result<[u32; L], StaticFifoError> {
+        if self.len() < L {
+            return Err(StaticFifoError::Empty);
+        }
+        let mut rv : [u32; L] = [0; L];
+        for v in rv.iter_mut() {
+            *v = self.get()?;
+        }
+        Ok(rv)
+    }
+}
+
+// adapter that only ever yields whole frame_len byte frames from the fifo.
+pub struct FixedFrames<'a, const N : usize> {
+    fifo: &'a mut StaticFifoU8<N>,
+    frame_len: usize,
+}
+
+impl<'a, const N : usize> FixedFrames<'a, N> {
+
+    pub fn new(fifo : &'a mut StaticFifoU8<N>, frame_len : usize) -> Self {
+        FixedFrames { fifo, frame_len }
+    }
+
+    pub fn frame_len(&self) -> usize {
+        self.frame_len
+    }
+
+    // number of complete frames queued.
+    pub fn frames_ready(&self) -> usize {
+        if self.frame_len == 0 {
+            return 0;
+        }
+        self.fifo.len() / self.frame_len
+    }
+
+    // copy the next frame into the front of out and remove it from the fifo.
+    // Empty if no whole frame is queued, TooLarge if out is shorter than a frame (nothing removed).
+    pub fn get_frame(&mut self, out : &mut [u8]) -> Result<(), StaticFifoError> {
+        if self.frames_ready() == 0 {
+            return Err(StaticFifoError::Empty);
+        }
+        if out.len() < self.frame_len {
+            return Err(StaticFifoError::TooLarge);
+        }
+        for b in out[..self.frame_len].iter_mut() {
+            *b = self.fifo.get()?;
+        }
+        Ok(())
+    }
+
+    // lend the next frame in place, no copy. the frame is removed when the grant is dropped,
+    // or left queued if the grant is given up with keep().
+    pub fn grant(&mut self) -> Result<FrameGrant<'_, N>, StaticFifoError> {
+        if self.frames_ready() == 0 {
+            return Err(StaticFifoError::Empty);
+        }
+        Ok(FrameGrant { fifo: self.fifo, len: self.frame_len, release: true })
+    }
+}
+
+// one whole frame lent out of the fifo by FixedFrames::grant().
+pub struct FrameGrant<'b, const N : usize> {
+    fifo: &'b mut StaticFifoU8<N>,
+    len: usize,
+    release: bool,
+}
+
+impl<'b, const N : usize> FrameGrant<'b, N> {
+
+    // the frame as two slices, the second one is only used when the frame wraps in the fifo.
+    pub fn as_slices(&self) -> (&[u8], &[u8]) {
+        let (a, b) = self.fifo.as_slices();
+        if a.len() >= self.len {
+            (&a[..self.len], &[])
+        } else {
+            (a, &b[..(self.len - a.len())])
+        }
+    }
+
+    // byte i of the frame.
+    pub fn at(&self, i : usize) -> Option<u8> {
+        if i >= self.len {
+            return None;
+        }
+        self.fifo.peek_at(i).ok()
+    }
+
+    // give the grant back without removing the frame.
+    pub fn keep(mut self) {
+        self.release = false;
+    }
+}
+
+impl<'b, const N : usize> Drop for FrameGrant<'b, N> {
+    fn drop(&mut self) {
+        if self.release {
+            self.fifo.skip(self.len);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn fixed_frames() {
+        println!("##################### FIXED FRAME TEST ######################################");
+
+        let mut fifo = StaticFifoU8::<16> { buf: [0;16], read_ptr: 0, write_ptr: 0, capacity: 16, soft_cap: usize::MAX };
+
+        // start near the end of the buffer so later frames wrap
+        for _ in 0..8 {
+            fifo.put(0).unwrap();
+        }
+        fifo.skip(8);
+
+        // compile time length
+        for b in 0..5 {
+            fifo.put(b).unwrap();
+        }
+        assert!(fifo.get_array::<6>() == Err(StaticFifoError::Empty), "partial array returned");
+        assert!(fifo.get_array::<4>() == Ok([0, 1, 2, 3]), "array contents wrong");
+        assert!(fifo.len() == 1, "trailing byte should be left queued");
+
+        // runtime length, second frame straddles the wrap
+        for b in 10..20 {
+            fifo.put(b).unwrap();
+        }
+        let mut frames = FixedFrames::new(&mut fifo, 3);
+        assert!(frames.frames_ready() == 3, "frames_ready wrong");
+        let mut out = [0u8; 3];
+        assert!(frames.get_frame(&mut out[..2]) == Err(StaticFifoError::TooLarge), "short buffer accepted");
+        frames.get_frame(&mut out).unwrap();
+        assert!(out == [4, 10, 11], "copied frame wrong");
+
+        // grant kept, then released
+        frames.grant().unwrap().keep();
+        assert!(frames.frames_ready() == 2, "kept grant removed a frame");
+        {
+            let g = frames.grant().unwrap();
+            let (a, b) = g.as_slices();
+            assert!(a == [12] && b == [13, 14], "grant slices wrong");
+            assert!(g.at(0) == Some(12) && g.at(2) == Some(14) && g.at(3).is_none(), "grant contents wrong");
+        }
+        assert!(frames.frames_ready() == 1, "dropped grant did not release the frame");
+        frames.get_frame(&mut out).unwrap();
+        assert!(out == [15, 16, 17], "last frame wrong");
+        assert!(frames.get_frame(&mut out) == Err(StaticFifoError::Empty), "partial frame returned");
+        assert!(frames.grant().is_err(), "grant on partial frame");
+        assert!(fifo.len() == 2, "partial frame should be left queued");
+    }
+}
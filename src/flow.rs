@@ -0,0 +1,122 @@
+////////////////////////////////////////////////////////////////
+// RTS / CTS flow control
+//
+// Drives a UART's RTS line from the fill level of its receive
+// fifo: once the fifo reaches the high watermark RTS is
+// deasserted so the far end stops sending, once it has
+// drained to the low watermark RTS is asserted again. The gap
+// between the two keeps the line from toggling on every byte,
+// and the slots above the high mark take what the far end
+// still had in flight.
+//
+// The pin is anything with set_high() / set_low(), an
+// embedded-hal OutputPin fits with a two line impl. RTS is
+// taken as active low, the usual for a UART pin.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::traits::FifoConsumer;
+
+// the RTS output.
+pub trait RtsPin {
+    fn set_high(&mut self);
+    fn set_low(&mut self);
+}
+
+pub struct Rts<P> {
+    pin: P,
+    low: usize,
+    high: usize,
+    asserted: bool,
+}
+
+impl<P : RtsPin> Rts<P> {
+
+    // assert RTS (ready to receive) at len <= low, deassert at len >= high.
+    // the pin is driven asserted right away.
+    pub fn new(mut pin : P, low : usize, high : usize) -> Self {
+        pin.set_low();
+        Rts { pin, low, high: core::cmp::max(high, low.saturating_add(1)), asserted: true }
+    }
+
+    // move the line for fill level len, call after the fifo changed. returns
+    // whether RTS is asserted.
+    pub fn update(&mut self, len : usize) -> bool {
+        if self.asserted && len >= self.high {
+            self.pin.set_high();
+            self.asserted = false;
+        } else if !self.asserted && len <= self.low {
+            self.pin.set_low();
+            self.asserted = true;
+        }
+        self.asserted
+    }
+
+    // update() with the fill level of fifo.
+    pub fn update_from<T, F : FifoConsumer<T>>(&mut self, fifo : &F) -> bool {
+        self.update(fifo.len())
+    }
+
+    pub fn is_asserted(&self) -> bool {
+        self.asserted
+    }
+
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+    use crate::StaticFifoU8;
+
+    // pin level plus how often it changed
+    struct TestPin {
+        high: bool,
+        edges: u32,
+    }
+
+    impl RtsPin for &mut TestPin {
+        fn set_high(&mut self) {
+            self.edges += u32::from(!self.high);
+            self.high = true;
+        }
+
+        fn set_low(&mut self) {
+            self.edges += u32::from(self.high);
+            self.high = false;
+        }
+    }
+
+    #[test]
+    fn rts_follows_watermarks() {
+        println!("##################### RTS FLOW CONTROL TEST ######################################");
+
+        let mut pin = TestPin { high: true, edges: 0 };
+        let mut rx : StaticFifoU8<16> = StaticFifoU8::new();
+        {
+            let mut rts = Rts::new(&mut pin, 4, 12);
+            assert!(rts.is_asserted(), "not ready to receive at start");
+
+            // "uart isr" fills the fifo
+            for b in 0..12 {
+                rx.put(b).unwrap();
+                rts.update_from(&rx);
+            }
+            assert!(!rts.is_asserted(), "rts still asserted at the high mark");
+
+            // main loop drains, the line stays put until the low mark
+            for _ in 0..7 {
+                rx.get().unwrap();
+                assert!(!rts.update_from(&rx), "rts asserted above the low mark");
+            }
+            rx.get().unwrap();
+            assert!(rts.update_from(&rx) && rx.len() == 4, "rts not asserted at the low mark");
+        }
+        assert!(!pin.high && pin.edges == 3, "pin toggled wrong");
+    }
+}
@@ -0,0 +1,428 @@
+////////////////////////////////////////////////////////////////
+// FrameFifo
+//
+// Datagram queue on top of StaticFifoU8 storage. Every
+// message is stored behind a 2 byte (little endian) length
+// prefix, so messages come back out whole with their
+// boundaries kept, instead of as a byte stream.
+//
+// Optionally (see with_crc()) every message also carries a
+// CRC over its prefix and payload, checked on the way out so
+// storage damaged by a stray DMA or another core is reported
+// as Corrupt instead of being handed over.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoError};
+use crate::crc::{crc16_update, crc16_finish, CRC16_INIT, crc32_update, crc32_finish, CRC32_INIT};
+
+// bytes of storage used by each message on top of its payload.
+pub const PREFIX_LEN : usize = 2;
+
+// largest message the length prefix can describe.
+pub const MAX_MESSAGE_LEN : usize = 0xFFFF;
+
+// per message integrity check, stored lsb first after the payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordCrc {
+    None,
+    // CRC-16/X-25, 2 bytes per message
+    Crc16,
+    // CRC-32 (IEEE), 4 bytes per message
+    Crc32,
+}
+
+impl RecordCrc {
+
+    // bytes of storage the check takes up in each message.
+    pub const fn size(self) -> usize {
+        match self {
+            RecordCrc::None => 0,
+            RecordCrc::Crc16 => 2,
+            RecordCrc::Crc32 => 4,
+        }
+    }
+
+    pub(crate) fn compute<I : Iterator<Item = u8>>(self, data : I) -> u32 {
+        match self {
+            RecordCrc::None => 0,
+            RecordCrc::Crc16 => crc16_finish(data.fold(CRC16_INIT, crc16_update)) as u32,
+            RecordCrc::Crc32 => crc32_finish(data.fold(CRC32_INIT, crc32_update)),
+        }
+    }
+}
+
+// note N is raw byte storage, each message costs its length + PREFIX_LEN bytes
+// (+ the crc length if enabled) and one byte is lost to the full marker, same as the plain fifo.
+pub struct FrameFifo<const N : usize> {
+    bytes: StaticFifoU8<N>,
+    count: usize,
+    crc: RecordCrc,
+}
+
+impl<const N : usize> Default for FrameFifo<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N : usize> FrameFifo<N> {
+
+    pub const fn new() -> Self {
+        Self::with_crc(RecordCrc::None)
+    }
+
+    // queue that appends crc to every message on push and checks it on pop.
+    pub const fn with_crc(crc : RecordCrc) -> Self {
+        FrameFifo { bytes: StaticFifoU8::new(), count: 0, crc }
+    }
+
+    // bytes of storage used by each message on top of its payload.
+    #[inline]
+    pub fn overhead(&self) -> usize {
+        PREFIX_LEN + self.crc.size()
+    }
+
+    // number of messages queued.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    // bytes of storage in use, including the per message overhead.
+    pub fn bytes_used(&self) -> usize {
+        self.bytes.len()
+    }
+
+    // largest message that would currently fit.
+    pub fn free_space(&self) -> usize {
+        core::cmp::min(self.bytes.free_space().saturating_sub(self.overhead()), MAX_MESSAGE_LEN)
+    }
+
+    // crc of the n stored bytes starting at raw storage index start.
+    fn crc_at(&self, start : usize, n : usize) -> u32 {
+        let cap = self.bytes.capacity;
+        self.crc.compute((0..n).map(|i| self.bytes.buf[(start + i) % cap]))
+    }
+
+    // put the crc of the message at raw storage index start (written bytes long) behind it.
+    fn put_crc(&mut self, start : usize, written : usize) {
+        let crc = self.crc_at(start, PREFIX_LEN + written);
+        for i in 0..self.crc.size() {
+            let _ = self.bytes.put((crc >> (8 * i)) as u8);
+        }
+    }
+
+    // check the crc of the next message (len bytes of payload).
+    // a damaged message is dropped, if its length can not be trusted to find the
+    // next message the whole queue is dropped. either way Corrupt is returned.
+    fn check_next(&mut self, len : usize) -> Result<(), StaticFifoError> {
+        if self.crc == RecordCrc::None {
+            return Ok(());
+        }
+        let n = PREFIX_LEN + len;
+        if n + self.crc.size() <= self.bytes.len() {
+            let stored = (0..self.crc.size())
+                .fold(0u32, |c, i| c | ((self.bytes.peek_at(n + i).unwrap_or(0) as u32) << (8 * i)));
+            if stored == self.crc_at(self.bytes.read_ptr, n) {
+                return Ok(());
+            }
+            self.bytes.skip(n + self.crc.size());
+            self.count -= 1;
+        } else {
+            self.bytes.skip(self.bytes.len());
+            self.count = 0;
+        }
+        Err(StaticFifoError::Corrupt)
+    }
+
+    // a next message running past the stored bytes can only come from a damaged length
+    // (without a crc nothing else catches it). there is no resyncing from that, so the
+    // whole queue is dropped and Corrupt returned.
+    fn check_stored(&mut self, len : usize) -> Result<(), StaticFifoError> {
+        if self.overhead() + len <= self.bytes.len() {
+            return Ok(());
+        }
+        self.bytes.skip(self.bytes.len());
+        self.count = 0;
+        Err(StaticFifoError::Corrupt)
+    }
+
+    // queue data as one message, all or nothing.
+    // Full if it does not fit right now, TooLarge if it could never fit.
+    pub fn push(&mut self, data : &[u8]) -> Result<(), StaticFifoError> {
+        if data.len() > MAX_MESSAGE_LEN || data.len() + self.overhead() > N.saturating_sub(1) {
+            return Err(StaticFifoError::TooLarge);
+        }
+        if data.len() > self.free_space() {
+            return Err(StaticFifoError::Full);
+        }
+        // space was checked above, puts can not fail.
+        let start = self.bytes.write_ptr;
+        let _ = self.bytes.put((data.len() & 0xFF) as u8);
+        let _ = self.bytes.put((data.len() >> 8) as u8);
+        for b in data {
+            let _ = self.bytes.put(*b);
+        }
+        self.put_crc(start, data.len());
+        self.count += 1;
+        Ok(())
+    }
+
+    // length of the next message, without removing it.
+    pub fn next_len(&self) -> Option<usize> {
+        if self.count == 0 {
+            return None;
+        }
+        let lo = self.bytes.peek_at(0).ok()? as usize;
+        let hi = self.bytes.peek_at(1).ok()? as usize;
+        Some(lo | (hi << 8))
+    }
+
+    // copy the next message into the front of out and remove it, returns its length.
+    // Empty if nothing is queued. TooLarge if out is too short, the message stays queued
+    // so it can be read with a bigger buffer (see next_len()) or dropped with discard().
+    // Corrupt if the message fails its crc check or its length runs past the stored bytes
+    // (see check_next() / check_stored()).
+    pub fn pop(&mut self, out : &mut [u8]) -> Result<usize, StaticFifoError> {
+        let len = match self.next_len() {
+            Some(l) => l,
+            None => return Err(StaticFifoError::Empty),
+        };
+        self.check_stored(len)?;
+        if len > out.len() {
+            return Err(StaticFifoError::TooLarge);
+        }
+        self.check_next(len)?;
+        self.bytes.skip(PREFIX_LEN);
+        for b in out[..len].iter_mut() {
+            *b = self.bytes.get()?;
+        }
+        self.bytes.skip(self.crc.size());
+        self.count -= 1;
+        Ok(len)
+    }
+
+    // lend the next message to f as one slice and remove it, returns what f returned.
+    // if the message wraps in storage the fifo is rotated first (see make_contiguous).
+    // a message that fails its crc check or whose length runs past the stored bytes is never
+    // lent out, see check_next() / check_stored().
+    pub fn pop_with<R, F : FnOnce(&[u8]) -> R>(&mut self, f : F) -> Result<R, StaticFifoError> {
+        let len = match self.next_len() {
+            Some(l) => l,
+            None => return Err(StaticFifoError::Empty),
+        };
+        self.check_stored(len)?;
+        self.check_next(len)?;
+        self.bytes.skip(PREFIX_LEN);
+        let rv = if self.bytes.as_slices().0.len() >= len {
+            f(&self.bytes.as_slices().0[..len])
+        } else {
+            f(self.bytes.make_contiguous().get(..len).ok_or(StaticFifoError::Corrupt)?)
+        };
+        self.bytes.skip(len + self.crc.size());
+        self.count -= 1;
+        Ok(rv)
+    }
+
+    // start a message whose length is not known up front, bytes go straight into storage.
+    // the message is only queued by MessageWriter::finish(), dropping the writer throws it away.
+    pub fn writer(&mut self) -> Result<MessageWriter<'_, N>, StaticFifoError> {
+        if self.bytes.free_space() < self.overhead() {
+            return Err(StaticFifoError::Full);
+        }
+        let start = self.bytes.write_ptr;
+        let _ = self.bytes.put(0);
+        let _ = self.bytes.put(0);
+        Ok(MessageWriter { fifo: self, start, written: 0, done: false })
+    }
+
+    // drop the next message, returns its length. Corrupt (and the queue dropped) if its
+    // length runs past the stored bytes.
+    pub fn discard(&mut self) -> Result<usize, StaticFifoError> {
+        let len = match self.next_len() {
+            Some(l) => l,
+            None => return Err(StaticFifoError::Empty),
+        };
+        self.check_stored(len)?;
+        self.bytes.skip(self.overhead() + len);
+        self.count -= 1;
+        Ok(len)
+    }
+}
+
+// message being assembled in place, see FrameFifo::writer().
+pub struct MessageWriter<'a, const N : usize> {
+    fifo: &'a mut FrameFifo<N>,
+    start: usize,
+    written: usize,
+    done: bool,
+}
+
+impl<'a, const N : usize> MessageWriter<'a, N> {
+
+    // append data to the message, all or nothing. Full if it does not fit
+    // (room for the crc is kept back).
+    pub fn write(&mut self, data : &[u8]) -> Result<(), StaticFifoError> {
+        if self.written + data.len() > MAX_MESSAGE_LEN
+            || data.len() + self.fifo.crc.size() > self.fifo.bytes.free_space() {
+            return Err(StaticFifoError::Full);
+        }
+        for b in data {
+            let _ = self.fifo.bytes.put(*b);
+        }
+        self.written += data.len();
+        Ok(())
+    }
+
+    // bytes written to the message so far.
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    // fill in the length prefix (and crc) and queue the message, returns its length.
+    pub fn finish(mut self) -> usize {
+        let cap = self.fifo.bytes.capacity;
+        self.fifo.bytes.buf[self.start] = (self.written & 0xFF) as u8;
+        self.fifo.bytes.buf[(self.start + 1) % cap] = (self.written >> 8) as u8;
+        self.fifo.put_crc(self.start, self.written);
+        self.fifo.count += 1;
+        self.done = true;
+        self.written
+    }
+}
+
+impl<'a, const N : usize> Drop for MessageWriter<'a, N> {
+    fn drop(&mut self) {
+        if !self.done {
+            // unwind everything this writer put in, prefix included.
+            self.fifo.bytes.write_ptr = self.start;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn frame_fifo_functional() {
+        println!("##################### FRAME FIFO TEST ######################################");
+
+        let mut q : FrameFifo<16> = FrameFifo::new();
+        let mut out = [0u8; 8];
+
+        assert!(q.is_empty() && q.next_len().is_none(), "new queue not empty");
+        assert!(q.pop(&mut out) == Err(StaticFifoError::Empty), "pop on empty queue");
+        assert!(q.free_space() == 13, "free space wrong on empty queue");
+        assert!(q.push(&[0; 14]) == Err(StaticFifoError::TooLarge), "message that can never fit accepted");
+
+        // boundaries are kept, including empty messages
+        q.push(&[1, 2, 3]).unwrap();
+        q.push(&[]).unwrap();
+        q.push(&[4, 5]).unwrap();
+        assert!(q.len() == 3, "message count wrong");
+        assert!(q.bytes_used() == 11, "byte usage wrong");
+        assert!(q.push(&[9, 9, 9]) == Err(StaticFifoError::Full), "overfull push accepted");
+        assert!(q.len() == 3 && q.bytes_used() == 11, "failed push modified the queue");
+
+        assert!(q.pop(&mut out[..2]) == Err(StaticFifoError::TooLarge), "short buffer accepted");
+        assert!(q.next_len() == Some(3), "next_len wrong");
+        assert!(q.pop(&mut out) == Ok(3) && out[..3] == [1, 2, 3], "first message wrong");
+        assert!(q.pop(&mut out) == Ok(0), "empty message wrong");
+
+        // wrap the storage
+        q.push(&[6, 7, 8, 9, 10]).unwrap();
+        assert!(q.discard() == Ok(2), "discard returned wrong length");
+        assert!(q.pop(&mut out) == Ok(5) && out[..5] == [6, 7, 8, 9, 10], "wrapped message wrong");
+        assert!(q.is_empty() && q.bytes_used() == 0, "queue not empty at the end");
+    }
+
+    #[test]
+    fn frame_fifo_writer_and_pop_with() {
+        println!("##################### FRAME FIFO WRITER TEST ######################################");
+
+        let mut q : FrameFifo<16> = FrameFifo::new();
+
+        // message built from parts, straddling the wrap
+        q.push(&[0; 8]).unwrap();
+        q.discard().unwrap();
+        let mut w = q.writer().unwrap();
+        w.write(&[1, 2, 3]).unwrap();
+        w.write(&[4, 5, 6, 7]).unwrap();
+        assert!(w.write(&[0; 7]) == Err(StaticFifoError::Full), "overfull write accepted");
+        assert!(w.finish() == 7, "finished length wrong");
+        assert!(q.len() == 1 && q.next_len() == Some(7), "finished message not queued");
+        assert!(q.pop_with(|m| m == [1, 2, 3, 4, 5, 6, 7]) == Ok(true), "wrapped message not lent whole");
+
+        // abandoned writer leaves nothing behind
+        q.push(&[9]).unwrap();
+        {
+            let mut w = q.writer().unwrap();
+            w.write(&[1, 2, 3]).unwrap();
+        }
+        assert!(q.len() == 1 && q.bytes_used() == 3, "abandoned writer left data behind");
+        assert!(q.pop_with(|m| m.len()) == Ok(1), "message after abandoned writer wrong");
+        assert!(q.pop_with(|m| m.len()) == Err(StaticFifoError::Empty), "pop_with on empty queue");
+    }
+
+    #[test]
+    fn frame_fifo_crc() {
+        println!("##################### FRAME FIFO CRC TEST ######################################");
+
+        let mut q : FrameFifo<24> = FrameFifo::with_crc(RecordCrc::Crc32);
+        let mut out = [0u8; 8];
+
+        assert!(q.free_space() == 17, "free space does not account for the crc");
+        q.push(&[1, 2, 3]).unwrap();
+        q.push(&[4, 5]).unwrap();
+        assert!(q.bytes_used() == 17, "byte usage wrong");
+
+        // flip a payload bit in the first message, only that message is lost
+        q.bytes.buf[3] ^= 0x10;
+        assert!(q.pop(&mut out) == Err(StaticFifoError::Corrupt), "damaged message delivered");
+        assert!(q.len() == 1, "damaged message not dropped");
+        assert!(q.pop(&mut out) == Ok(2) && out[..2] == [4, 5], "message after damage wrong");
+
+        // message built with the writer straddling the wrap is checked the same way
+        let mut w = q.writer().unwrap();
+        w.write(&[6, 7, 8, 9]).unwrap();
+        w.finish();
+        assert!(q.pop_with(|m| m == [6, 7, 8, 9]) == Ok(true), "written message wrong");
+
+        // a damaged length that runs past the stored data drops everything
+        let mut q : FrameFifo<24> = FrameFifo::with_crc(RecordCrc::Crc16);
+        q.push(&[1, 2, 3]).unwrap();
+        q.push(&[4, 5]).unwrap();
+        q.bytes.buf[1] = 0x40;
+        assert!(q.pop_with(|m| m.len()) == Err(StaticFifoError::Corrupt), "damaged length accepted");
+        assert!(q.is_empty() && q.bytes_used() == 0, "queue not flushed after damaged length");
+
+        // same without a crc, the length is caught before anything is lent out
+        let mut q : FrameFifo<24> = FrameFifo::new();
+        q.push(&[1, 2, 3]).unwrap();
+        q.bytes.buf[0] = 0x30;
+        assert!(q.pop_with(|m| m.len()) == Err(StaticFifoError::Corrupt), "damaged length lent out");
+        assert!(q.is_empty() && q.bytes_used() == 0, "queue not flushed after damaged length");
+
+        // pop with a buffer big enough for the damaged length, and discard
+        q.push(&[1, 2, 3]).unwrap();
+        q.push(&[4]).unwrap();
+        q.bytes.buf[q.bytes.read_ptr] = 8;
+        let mut big = [0u8; 16];
+        assert!(q.pop(&mut big) == Err(StaticFifoError::Corrupt), "damaged length popped");
+        assert!(q.is_empty() && q.bytes_used() == 0, "queue not flushed after damaged length");
+        q.push(&[1, 2, 3]).unwrap();
+        q.bytes.buf[q.bytes.read_ptr] = 8;
+        assert!(q.discard() == Err(StaticFifoError::Corrupt), "damaged length discarded");
+        assert!(q.is_empty() && q.bytes_used() == 0, "queue not flushed after damaged length");
+        q.push(&[5, 6]).unwrap();
+        assert!(q.pop(&mut big) == Ok(2) && big[..2] == [5, 6], "queue unusable after flush");
+    }
+}
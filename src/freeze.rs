@@ -0,0 +1,102 @@
+////////////////////////////////////////////////////////////////
+// Freeze / thaw
+//
+// For maintenance windows: a firmware update or flush routine
+// calls freeze(), drains the queue and knows nothing new got
+// in meanwhile. While frozen every put fails at once with
+// Frozen (not Full, so producers can tell "try later" from
+// "shut"), gets carry on as usual.
+//
+// The fifo is only reachable through the wrapper, there is no
+// inner_mut() to put around the freeze with.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+pub struct Freezable<F> {
+    fifo: F,
+    frozen: bool,
+}
+
+impl<F> Freezable<F> {
+
+    pub fn new(fifo : F) -> Self {
+        Freezable { fifo, frozen: false }
+    }
+
+    // refuse puts from now on, gets still work.
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    // accept puts again.
+    pub fn thaw(&mut self) {
+        self.frozen = false;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.fifo
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo
+    }
+}
+
+impl<T, F : FifoProducer<T>> FifoProducer<T> for Freezable<F> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        if self.frozen {
+            return Err(StaticFifoError::Frozen);
+        }
+        self.fifo.put(data)
+    }
+
+    // nothing fits while frozen.
+    fn free_space(&self) -> usize {
+        if self.frozen { 0 } else { self.fifo.free_space() }
+    }
+}
+
+impl<T, F : FifoConsumer<T>> FifoConsumer<T> for Freezable<F> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        self.fifo.get()
+    }
+
+    fn len(&self) -> usize {
+        self.fifo.len()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+    use crate::StaticFifoU8;
+
+    #[test]
+    fn frozen_fifo_drains() {
+        println!("##################### FREEZE TEST ######################################");
+
+        let mut fifo = Freezable::new(StaticFifoU8::<8>::new());
+        fifo.put(1).unwrap();
+        fifo.put(2).unwrap();
+
+        fifo.freeze();
+        assert!(fifo.put(3) == Err(StaticFifoError::Frozen) && FifoProducer::<u8>::free_space(&fifo) == 0, "frozen fifo took data");
+        assert!(fifo.get() == Ok(1) && fifo.get() == Ok(2), "frozen fifo would not drain");
+        assert!(fifo.get() == Err(StaticFifoError::Empty) && fifo.is_frozen(), "data sneaked in");
+
+        fifo.thaw();
+        assert!(fifo.put(3).is_ok() && fifo.get() == Ok(3), "thawed fifo refused data");
+    }
+}
@@ -0,0 +1,239 @@
+////////////////////////////////////////////////////////////////
+// HDLC style framing
+//
+// Async HDLC byte stuffing (as used by PPP) on top of
+// StaticFifoU8. Frames sit between 0x7E flags, 0x7E / 0x7D
+// inside a frame are sent as 0x7D followed by the byte ^ 0x20.
+// The _fcs variants append and check a FCS-16 (CRC-16/X-25)
+// the way PPP does, plain variants leave that to the caller.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoError};
+use crate::crc::{crc16, crc16_update, CRC16_INIT, CRC16_GOOD};
+
+pub const FLAG : u8 = 0x7E;
+pub const ESCAPE : u8 = 0x7D;
+pub const XOR : u8 = 0x20;
+
+// worst case encoded size of a len byte frame (no FCS), including both flags.
+pub const fn max_encoded_len(len : usize) -> usize {
+    (len * 2) + 2
+}
+
+#[inline]
+fn needs_escape(b : u8) -> bool {
+    b == FLAG || b == ESCAPE
+}
+
+fn put_stuffed<const N : usize>(fifo : &mut StaticFifoU8<N>, data : &[u8], fcs : &[u8]) -> Result<(), StaticFifoError> {
+    let escapes = data.iter().chain(fcs.iter()).filter(|&&b| needs_escape(b)).count();
+    if data.len() + fcs.len() + escapes + 2 > fifo.free_space() {
+        return Err(StaticFifoError::Full);
+    }
+    // space was checked above, puts can not fail.
+    let _ = fifo.put(FLAG);
+    for b in data.iter().chain(fcs.iter()) {
+        if needs_escape(*b) {
+            let _ = fifo.put(ESCAPE);
+            let _ = fifo.put(*b ^ XOR);
+        } else {
+            let _ = fifo.put(*b);
+        }
+    }
+    let _ = fifo.put(FLAG);
+    Ok(())
+}
+
+// stuff data as one frame into the fifo.
+// all or nothing, returns Full without touching the fifo if the frame does not fit.
+pub fn put_frame<const N : usize>(fifo : &mut StaticFifoU8<N>, data : &[u8]) -> Result<(), StaticFifoError> {
+    put_stuffed(fifo, data, &[])
+}
+
+// same as put_frame() but appends the FCS-16 of data (lsb first).
+pub fn put_frame_fcs<const N : usize>(fifo : &mut StaticFifoU8<N>, data : &[u8]) -> Result<(), StaticFifoError> {
+    let fcs = crc16(data);
+    put_stuffed(fifo, data, &[(fcs & 0xFF) as u8, (fcs >> 8) as u8])
+}
+
+// true if a complete frame is queued and get_frame() will return it (or drop it as bad).
+pub fn frame_ready<const N : usize>(fifo : &StaticFifoU8<N>) -> bool {
+    let mut start : usize = 0;
+    while fifo.peek_at(start) == Ok(FLAG) {
+        start += 1;
+    }
+    (start..fifo.len()).any(|i| fifo.peek_at(i) == Ok(FLAG))
+}
+
+// de-stuff the bytes in front of the flag at offset end.
+// with fcs set the last two de-stuffed bytes are the FCS, they are checked and not copied out.
+fn destuff<const N : usize>(fifo : &StaticFifoU8<N>, end : usize, out : &mut [u8], fcs : bool) -> Result<usize, StaticFifoError> {
+    let hold : usize = if fcs { 2 } else { 0 };
+    let mut held : [u8; 2] = [0; 2];
+    let mut held_n : usize = 0;
+    let mut crc : u16 = CRC16_INIT;
+    let mut i : usize = 0;
+    let mut n : usize = 0;
+    while i < end {
+        let mut b = fifo.peek_at(i)?;
+        i += 1;
+        if b == ESCAPE {
+            // escape right before the flag is the abort sequence.
+            if i >= end {
+                return Err(StaticFifoError::Malformed);
+            }
+            b = fifo.peek_at(i)? ^ XOR;
+            i += 1;
+        }
+        crc = crc16_update(crc, b);
+        // delay output by the FCS length so the FCS never lands in out.
+        if held_n < hold {
+            held[held_n] = b;
+            held_n += 1;
+            continue;
+        }
+        let v = if hold == 0 { b } else { let v = held[0]; held[0] = held[1]; held[1] = b; v };
+        if n >= out.len() {
+            return Err(StaticFifoError::TooLarge);
+        }
+        out[n] = v;
+        n += 1;
+    }
+    if fcs && (held_n < hold || crc != CRC16_GOOD) {
+        return Err(StaticFifoError::Corrupt);
+    }
+    Ok(n)
+}
+
+fn get_stuffed<const N : usize>(fifo : &mut StaticFifoU8<N>, out : &mut [u8], fcs : bool) -> Result<usize, StaticFifoError> {
+    // back to back flags carry nothing, drop them.
+    while fifo.peek_at(0) == Ok(FLAG) {
+        fifo.skip(1);
+    }
+    let end = match fifo.find(FLAG) {
+        Some(e) => e,
+        None if fifo.is_full() => {
+            fifo.skip(fifo.len());
+            return Err(StaticFifoError::Malformed);
+        },
+        None => return Err(StaticFifoError::Empty),
+    };
+    let rv = destuff(fifo, end, out, fcs);
+    fifo.skip(end + 1);
+    rv
+}
+
+// pull one complete frame out of the fifo and de-stuff it into out, returns frame length.
+// Empty if no complete frame is queued yet (partial frames are left alone).
+// a frame that is Malformed or TooLarge for out is dropped so the link stays in sync.
+// a full fifo with no FLAG in it can never complete a frame, it is dropped as Malformed.
+pub fn get_frame<const N : usize>(fifo : &mut StaticFifoU8<N>, out : &mut [u8]) -> Result<usize, StaticFifoError> {
+    get_stuffed(fifo, out, false)
+}
+
+// same as get_frame() but checks and strips the trailing FCS-16.
+// a frame with a bad FCS is dropped and reported as Corrupt.
+pub fn get_frame_fcs<const N : usize>(fifo : &mut StaticFifoU8<N>, out : &mut [u8]) -> Result<usize, StaticFifoError> {
+    get_stuffed(fifo, out, true)
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    fn new_fifo() -> StaticFifoU8<64> {
+        StaticFifoU8::<64> { buf: [0;64], read_ptr: 0, write_ptr: 0, capacity: 64, soft_cap: usize::MAX }
+    }
+
+    #[test]
+    fn hdlc_round_trip() {
+        println!("##################### HDLC ROUND TRIP TEST ######################################");
+
+        let mut fifo = new_fifo();
+        let mut out = [0u8; 16];
+
+        let data = [0x01, FLAG, 0x02, ESCAPE, 0x03];
+        put_frame(&mut fifo, &data).unwrap();
+        let expect = [FLAG, 0x01, ESCAPE, 0x5E, 0x02, ESCAPE, 0x5D, 0x03, FLAG];
+        assert!(fifo.len() == expect.len(), "encoded length wrong");
+        for (i, b) in expect.iter().enumerate() {
+            assert!(fifo.peek_at(i) == Ok(*b), "encoded byte {} wrong", i);
+        }
+        assert!(frame_ready(&fifo), "complete frame not detected");
+        assert!(get_frame(&mut fifo, &mut out) == Ok(data.len()), "decoded length wrong");
+        assert!(out[..data.len()] == data, "decoded data wrong");
+        assert!(fifo.is_empty(), "fifo should be empty after reading the only frame");
+
+        // fcs round trip, out only needs room for the payload
+        put_frame_fcs(&mut fifo, &data).unwrap();
+        let mut exact = [0u8; 5];
+        assert!(get_frame_fcs(&mut fifo, &mut exact) == Ok(data.len()), "fcs frame length wrong");
+        assert!(exact == data, "fcs frame data wrong");
+
+        // frame that does not fit leaves the fifo untouched
+        let mut small = StaticFifoU8::<8> { buf: [0;8], read_ptr: 0, write_ptr: 0, capacity: 8, soft_cap: usize::MAX };
+        assert!(put_frame(&mut small, &[FLAG, ESCAPE, 1, 2]) == Err(StaticFifoError::Full), "oversized put accepted");
+        assert!(small.is_empty(), "failed put modified the fifo");
+        assert!(put_frame(&mut small, &[FLAG, ESCAPE, 1]) == Ok(()), "frame that fits exactly was rejected");
+        assert!(small.is_full(), "fifo should be exactly full");
+    }
+
+    #[test]
+    fn hdlc_rx_side() {
+        println!("##################### HDLC RX TEST ######################################");
+
+        let mut fifo = new_fifo();
+        let mut out = [0u8; 4];
+
+        // partial frame stays queued until its closing flag arrives
+        for b in [FLAG, FLAG, 0x11, ESCAPE] {
+            fifo.put(b).unwrap();
+        }
+        assert!(!frame_ready(&fifo), "partial frame reported ready");
+        assert!(get_frame(&mut fifo, &mut out) == Err(StaticFifoError::Empty), "partial frame returned");
+        assert!(fifo.len() == 2, "partial frame should be left queued");
+        fifo.put(0x5E).unwrap();
+        fifo.put(FLAG).unwrap();
+        assert!(get_frame(&mut fifo, &mut out) == Ok(2) && out[..2] == [0x11, FLAG], "completed frame not returned");
+
+        // abort sequence is malformed and dropped
+        for b in [0x11, ESCAPE, FLAG, 0x44, FLAG] {
+            fifo.put(b).unwrap();
+        }
+        assert!(get_frame(&mut fifo, &mut out) == Err(StaticFifoError::Malformed), "aborted frame accepted");
+        assert!(get_frame(&mut fifo, &mut out) == Ok(1) && out[0] == 0x44, "frame after aborted one lost");
+
+        // damaged fcs frame is dropped as corrupt
+        put_frame_fcs(&mut fifo, &[1, 2, 3]).unwrap();
+        fifo.buf[(fifo.read_ptr + 2) % fifo.capacity] ^= 0x01;
+        put_frame_fcs(&mut fifo, &[6]).unwrap();
+        assert!(get_frame_fcs(&mut fifo, &mut out) == Err(StaticFifoError::Corrupt), "bad fcs accepted");
+        assert!(get_frame_fcs(&mut fifo, &mut out) == Ok(1) && out[0] == 6, "frame after corrupt one lost");
+
+        // frame bigger than the output buffer is dropped
+        put_frame(&mut fifo, &[1, 2, 3, 4, 5]).unwrap();
+        put_frame(&mut fifo, &[6]).unwrap();
+        assert!(get_frame(&mut fifo, &mut out) == Err(StaticFifoError::TooLarge), "oversized frame accepted");
+        assert!(get_frame(&mut fifo, &mut out) == Ok(1) && out[0] == 6, "frame after oversized one lost");
+        assert!(fifo.is_empty(), "fifo should be empty");
+    }
+    #[test]
+    fn hdlc_full_without_flag() {
+        println!("##################### HDLC FULL RX TEST ######################################");
+
+        // line noise fills the rx fifo without a flag
+        let mut fifo = new_fifo();
+        let mut out = [0u8; 4];
+        while fifo.put(0x55).is_ok() {}
+        assert!(get_frame(&mut fifo, &mut out) == Err(StaticFifoError::Malformed), "wedged fifo not reported");
+        assert!(fifo.is_empty(), "wedged fifo not dropped");
+
+        // decoder picks up again with the next frame
+        put_frame(&mut fifo, &[0x11, FLAG]).unwrap();
+        assert!(get_frame(&mut fifo, &mut out) == Ok(2) && out[..2] == [0x11, FLAG], "frame after wedged fifo lost");
+    }
+}
@@ -0,0 +1,128 @@
+////////////////////////////////////////////////////////////////
+// Reserved headroom
+//
+// Keeps the last K free slots of a fifo for one priority
+// producer. Ordinary puts see a fifo K smaller and get Full
+// early; the Priority handle from priority() can still fill
+// the reserve, so a fault report or similar always finds room
+// even when the normal traffic has backed the queue up.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+pub struct Headroom<F> {
+    fifo: F,
+    reserve: usize,
+}
+
+impl<F> Headroom<F> {
+
+    // reserve is the number of slots only the priority handle may use.
+    pub fn new(fifo : F, reserve : usize) -> Self {
+        Headroom { fifo, reserve }
+    }
+
+    pub fn reserve(&self) -> usize {
+        self.reserve
+    }
+
+    pub fn set_reserve(&mut self, reserve : usize) {
+        self.reserve = reserve;
+    }
+
+    // the producer allowed into the reserved slots.
+    pub fn priority(&mut self) -> Priority<'_, F> {
+        Priority { fifo: &mut self.fifo }
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.fifo
+    }
+
+    pub fn inner_mut(&mut self) -> &mut F {
+        &mut self.fifo
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo
+    }
+}
+
+// normal puts, the reserve is not visible here.
+impl<T, F : FifoProducer<T>> FifoProducer<T> for Headroom<F> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        if self.fifo.free_space() <= self.reserve {
+            return Err(StaticFifoError::Full);
+        }
+        self.fifo.put(data)
+    }
+
+    fn free_space(&self) -> usize {
+        self.fifo.free_space().saturating_sub(self.reserve)
+    }
+}
+
+impl<T, F : FifoConsumer<T>> FifoConsumer<T> for Headroom<F> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        self.fifo.get()
+    }
+
+    fn len(&self) -> usize {
+        self.fifo.len()
+    }
+}
+
+// the priority producer, sees the whole fifo including the reserve.
+pub struct Priority<'a, F> {
+    fifo: &'a mut F,
+}
+
+impl<'a, T, F : FifoProducer<T>> FifoProducer<T> for Priority<'a, F> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        self.fifo.put(data)
+    }
+
+    fn free_space(&self) -> usize {
+        self.fifo.free_space()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+    use crate::StaticFifoU32;
+
+    #[test]
+    fn priority_gets_reserve() {
+        println!("##################### HEADROOM TEST ######################################");
+
+        // 7 slots, 2 kept for the priority producer
+        let mut fifo = Headroom::new(StaticFifoU32::<8>::new(), 2);
+        assert!(FifoProducer::<u32>::free_space(&fifo) == 5, "reserve not hidden");
+        for v in 0..5 {
+            fifo.put(v).unwrap();
+        }
+        assert!(fifo.put(5) == Err(StaticFifoError::Full), "normal put went into the reserve");
+
+        let mut fault = fifo.priority();
+        assert!(fault.free_space() == 2, "priority does not see the reserve");
+        fault.put(0xDEAD).unwrap();
+        fault.put(0xBEEF).unwrap();
+        assert!(fault.put(0) == Err(StaticFifoError::Full), "fifo overfilled");
+
+        // draining frees normal space again
+        for v in 0..5 {
+            assert!(fifo.get() == Ok(v), "order wrong");
+        }
+        assert!(fifo.get() == Ok(0xDEAD) && fifo.get() == Ok(0xBEEF), "priority data lost");
+        assert!(fifo.put(9) == Ok(()) && fifo.len() == 1, "put after drain wrong");
+    }
+}
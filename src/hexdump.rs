@@ -0,0 +1,84 @@
+////////////////////////////////////////////////////////////////
+// Hex dump
+//
+// Display adapter that renders the queued bytes of a
+// StaticFifoU8 as classic hex dump lines, offset + 16 hex
+// bytes + ASCII column, oldest byte first:
+//
+//   0000: 48 65 6c 6c 6f 00 ff                             |Hello..|
+//
+// Nothing is copied or removed, it is formatted straight from
+// the storage when displayed.
+//
+///////////////////////////////////////////////////////////////
+
+use core::fmt;
+
+use crate::StaticFifoU8;
+
+// bytes per dump line.
+pub const HEX_DUMP_WIDTH : usize = 16;
+
+// see StaticFifoU8::hex_dump().
+pub struct HexDump<'a, const N : usize> {
+    fifo: &'a StaticFifoU8<N>,
+}
+
+impl<'a, const N : usize> fmt::Display for HexDump<'a, N> {
+    fn fmt(&self, f : &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len = self.fifo.len();
+        for line in (0..len).step_by(HEX_DUMP_WIDTH) {
+            let n = core::cmp::min(HEX_DUMP_WIDTH, len - line);
+            let byte = |i : usize| self.fifo.peek_at(line + i).unwrap_or(0);
+            write!(f, "{:04x}:", line)?;
+            for i in 0..HEX_DUMP_WIDTH {
+                if i < n {
+                    write!(f, " {:02x}", byte(i))?;
+                } else {
+                    f.write_str("   ")?;
+                }
+            }
+            f.write_str("  |")?;
+            for i in 0..n {
+                let b = byte(i);
+                let c = if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' };
+                write!(f, "{}", c)?;
+            }
+            f.write_str("|\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // hex dump of the queued bytes for {} formatting (println!, defmt Display2Format ..)
+    pub fn hex_dump(&self) -> HexDump<'_, N> {
+        HexDump { fifo: self }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use std::format;
+    use super::*;
+
+    #[test]
+    fn hex_dump_lines() {
+        println!("##################### HEX DUMP TEST ######################################");
+
+        let mut fifo : StaticFifoU8<32> = StaticFifoU8::new();
+        for b in b"Hello, fifo!\x00\x7f\xff\x1a\x01" {
+            fifo.put(*b).unwrap();
+        }
+        let dump = format!("{}", fifo.hex_dump());
+        println!("{}", dump);
+        assert!(dump == "0000: 48 65 6c 6c 6f 2c 20 66 69 66 6f 21 00 7f ff 1a  |Hello, fifo!....|\n\
+                         0010: 01                                               |.|\n", "dump wrong");
+        assert!(fifo.len() == 17, "dump modified the fifo");
+        assert!(format!("{}", StaticFifoU8::<4>::new().hex_dump()).is_empty(), "empty dump not empty");
+    }
+}
@@ -0,0 +1,121 @@
+////////////////////////////////////////////////////////////////
+// Occupancy histogram
+//
+// Instrumentation wrapper for sizing buffers from what the
+// device really does: after every put and get the fill level
+// goes into one of B equal width bins, bin 0 for an empty fifo
+// up to bin B-1 for a full one. Leave it in a field build for
+// a while, read bins() out over telemetry, and the spread shows
+// whether the fifo is oversized or runs close to the edge.
+// Failed puts / gets are not counted, the level did not change.
+//
+// Counters stick at u32::MAX. Without the wrapper there is no
+// cost at all; HighWater is the cheaper option when only the
+// peak matters.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+pub struct OccupancyHistogram<F, const B : usize> {
+    fifo: F,
+    bins: [u32; B],
+}
+
+impl<F, const B : usize> OccupancyHistogram<F, B> {
+
+    const BINS_OK : () = assert!(B >= 1, "OccupancyHistogram needs B >= 1");
+
+    pub fn new(fifo : F) -> Self {
+        let () = Self::BINS_OK;
+        OccupancyHistogram { fifo, bins: [0; B] }
+    }
+
+    // samples per bin, bin i covers fill levels from i / B to (i + 1) / B of the capacity.
+    pub fn bins(&self) -> &[u32; B] {
+        &self.bins
+    }
+
+    // samples over all bins.
+    pub fn samples(&self) -> u64 {
+        self.bins.iter().map(|n| *n as u64).sum()
+    }
+
+    pub fn reset(&mut self) {
+        self.bins = [0; B];
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.fifo
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo
+    }
+
+    fn record(&mut self, len : usize, capacity : usize) {
+        let bin = (len * B).checked_div(capacity).unwrap_or(0).min(B - 1);
+        self.bins[bin] = self.bins[bin].saturating_add(1);
+    }
+}
+
+impl<T, F : FifoProducer<T> + FifoConsumer<T>, const B : usize> FifoProducer<T> for OccupancyHistogram<F, B> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        self.fifo.put(data)?;
+        let len = self.fifo.len();
+        self.record(len, len + self.fifo.free_space());
+        Ok(())
+    }
+
+    fn free_space(&self) -> usize {
+        self.fifo.free_space()
+    }
+}
+
+impl<T, F : FifoProducer<T> + FifoConsumer<T>, const B : usize> FifoConsumer<T> for OccupancyHistogram<F, B> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        let rv = self.fifo.get()?;
+        let len = self.fifo.len();
+        self.record(len, len + self.fifo.free_space());
+        Ok(rv)
+    }
+
+    fn len(&self) -> usize {
+        self.fifo.len()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+    use crate::StaticFifoU32;
+
+    #[test]
+    fn occupancy_bins() {
+        println!("##################### OCCUPANCY HISTOGRAM TEST ######################################");
+
+        // 8 elements of room, 4 bins of 2 levels each
+        let mut q : OccupancyHistogram<StaticFifoU32<9>, 4> = OccupancyHistogram::new(StaticFifoU32::new());
+        for v in 0..8 {
+            q.put(v).unwrap();
+        }
+        assert!(q.put(8).is_err(), "full fifo took data");
+        assert!(q.bins() == &[1, 2, 2, 3], "fill up wrong");
+
+        for _ in 0..8 {
+            q.get().unwrap();
+        }
+        assert!(q.get().is_err() && q.samples() == 16, "failed get counted");
+        assert!(q.bins() == &[3, 4, 4, 5], "drain wrong");
+
+        q.reset();
+        q.put(1).unwrap();
+        assert!(q.bins() == &[1, 0, 0, 0] && q.inner().len() == 1, "reset wrong");
+    }
+}
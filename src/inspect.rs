@@ -0,0 +1,221 @@
+////////////////////////////////////////////////////////////////
+// Inspector
+//
+// Read only view of a fifo for diagnostics / telemetry tasks.
+// It can report fill level and high watermark and walk the
+// queued elements, but has no way to put or get, so a queue
+// owned by another subsystem can be watched without the
+// watcher being able to disturb it.
+//
+// HighWater wraps a fifo and remembers the highest fill level
+// seen after a put, which the Inspector then reports.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError};
+use crate::traits::{FifoProducer, FifoConsumer};
+use crate::iter::Iter;
+
+// what an Inspector needs from a fifo.
+pub trait Inspect {
+    type Item : Copy;
+
+    // queued elements in order, the second slice is only used when the data wraps.
+    fn as_slices(&self) -> (&[Self::Item], &[Self::Item]);
+
+    // elements the fifo can hold at most.
+    fn capacity(&self) -> usize;
+
+    // highest fill level seen, if the fifo keeps track.
+    fn high_water(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl<const N : usize> Inspect for StaticFifoU8<N> {
+    type Item = u8;
+
+    fn as_slices(&self) -> (&[u8], &[u8]) {
+        StaticFifoU8::as_slices(self)
+    }
+
+    fn capacity(&self) -> usize {
+        self.soft_capacity()
+    }
+}
+
+impl<const N : usize> Inspect for StaticFifoU32<N> {
+    type Item = u32;
+
+    fn as_slices(&self) -> (&[u32], &[u32]) {
+        StaticFifoU32::as_slices(self)
+    }
+
+    fn capacity(&self) -> usize {
+        self.soft_capacity()
+    }
+}
+
+// the read only handle. cheap to make and to copy, a fifo reference plus nothing.
+pub struct Inspector<'a, F> {
+    fifo: &'a F,
+}
+
+impl<'a, F> Clone for Inspector<'a, F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, F> Copy for Inspector<'a, F> {}
+
+impl<'a, F : Inspect> Inspector<'a, F> {
+
+    pub fn new(fifo : &'a F) -> Self {
+        Inspector { fifo }
+    }
+
+    pub fn len(&self) -> usize {
+        let (a, b) = self.fifo.as_slices();
+        a.len() + b.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.fifo.capacity()
+    }
+
+    pub fn free_space(&self) -> usize {
+        self.capacity().saturating_sub(self.len())
+    }
+
+    // fill level in percent of the capacity, rounded down.
+    pub fn occupancy(&self) -> usize {
+        self.len() * 100 / core::cmp::max(self.capacity(), 1)
+    }
+
+    pub fn high_water(&self) -> Option<usize> {
+        self.fifo.high_water()
+    }
+
+    // the element i places from the read side.
+    pub fn peek_at(&self, i : usize) -> Result<F::Item, StaticFifoError> {
+        self.iter().nth(i).ok_or(StaticFifoError::Empty)
+    }
+
+    // the queued elements, oldest first. rev() for the most recent first.
+    pub fn iter(&self) -> core::iter::Copied<Iter<'a, F::Item>> {
+        let (a, b) = self.fifo.as_slices();
+        Iter::new(a, b).copied()
+    }
+}
+
+// fifo wrapper keeping the high watermark.
+pub struct HighWater<F> {
+    fifo: F,
+    high: usize,
+}
+
+impl<F> HighWater<F> {
+
+    pub fn new(fifo : F) -> Self {
+        HighWater { fifo, high: 0 }
+    }
+
+    // start tracking again from the current fill level.
+    pub fn reset_high_water<T>(&mut self) where F : FifoConsumer<T> {
+        self.high = self.fifo.len();
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.fifo
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo
+    }
+}
+
+impl<F : Inspect> Inspect for HighWater<F> {
+    type Item = F::Item;
+
+    fn as_slices(&self) -> (&[F::Item], &[F::Item]) {
+        self.fifo.as_slices()
+    }
+
+    fn capacity(&self) -> usize {
+        self.fifo.capacity()
+    }
+
+    fn high_water(&self) -> Option<usize> {
+        Some(self.high)
+    }
+}
+
+impl<T, F : FifoProducer<T> + FifoConsumer<T>> FifoProducer<T> for HighWater<F> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        self.fifo.put(data)?;
+        self.high = core::cmp::max(self.high, self.fifo.len());
+        Ok(())
+    }
+
+    fn free_space(&self) -> usize {
+        self.fifo.free_space()
+    }
+}
+
+impl<T, F : FifoConsumer<T>> FifoConsumer<T> for HighWater<F> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        self.fifo.get()
+    }
+
+    fn len(&self) -> usize {
+        self.fifo.len()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use std::vec::Vec;
+    use super::*;
+
+    // telemetry side, only ever sees the Inspector
+    fn report(q : Inspector<'_, HighWater<StaticFifoU32<8>>>) -> (usize, usize, Option<usize>, Vec<u32>) {
+        (q.len(), q.occupancy(), q.high_water(), q.iter().collect())
+    }
+
+    #[test]
+    fn read_only_view() {
+        println!("##################### INSPECTOR TEST ######################################");
+
+        let mut fifo = HighWater::new(StaticFifoU32::<8>::new());
+        for v in 1..=5 {
+            fifo.put(v).unwrap();
+        }
+        fifo.get().unwrap();
+        fifo.get().unwrap();
+        fifo.put(6).unwrap();
+
+        assert!(report(Inspector::new(&fifo)) == (4, 57, Some(5), [3, 4, 5, 6].to_vec()), "report wrong");
+        let q = Inspector::new(&fifo);
+        assert!(q.peek_at(1) == Ok(4) && q.peek_at(4) == Err(StaticFifoError::Empty), "peek wrong");
+        assert!(q.capacity() == 7 && q.free_space() == 3, "capacity wrong");
+
+        fifo.reset_high_water();
+        assert!(Inspector::new(&fifo).high_water() == Some(4), "reset high water wrong");
+
+        // a plain fifo can be inspected too, it just has no watermark
+        let mut bytes : StaticFifoU8<4> = StaticFifoU8::new();
+        bytes.put(b'x').unwrap();
+        let q = Inspector::new(&bytes);
+        assert!(q.len() == 1 && q.high_water().is_none() && q.iter().eq([b'x']), "byte fifo view wrong");
+    }
+}
@@ -0,0 +1,133 @@
+////////////////////////////////////////////////////////////////
+// Interleaved channel frames
+//
+// Treats the fifo as a stream of frames of C interleaved
+// channels (one sample per channel per frame). Frames go in
+// and come out whole or not at all, so the channels can never
+// slip out of phase with each other.
+//
+// put_iq() / get_iq() are the two channel case for SDR
+// pipelines: an I/Q sample pair, I first.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError};
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // queue one sample per channel, all or nothing. Full if the whole frame does not fit.
+    pub fn put_frame<const C : usize>(&mut self, frame : &[u8; C]) -> Result<(), StaticFifoError> {
+        if C > self.free_space() {
+            return Err(StaticFifoError::Full);
+        }
+        for s in frame {
+            let _ = self.put(*s);
+        }
+        Ok(())
+    }
+
+    // remove one whole frame of C channels, Empty (nothing removed) if a frame is not complete yet.
+    pub fn get_frame<const C : usize>(&mut self) -> Result<[u8; C], StaticFifoError> {
+        self.get_array::<C>()
+    }
+
+    // number of complete C channel frames queued.
+    pub fn frames_ready<const C : usize>(&self) -> usize {
+        self.len().checked_div(C).unwrap_or(0)
+    }
+
+    // queue an I/Q pair, both or neither.
+    pub fn put_iq(&mut self, i : u8, q : u8) -> Result<(), StaticFifoError> {
+        self.put_frame(&[i, q])
+    }
+
+    // remove one whole I/Q pair, Empty (nothing removed) if only half of one is queued.
+    pub fn get_iq(&mut self) -> Result<(u8, u8), StaticFifoError> {
+        self.get_frame::<2>().map(|[i, q]| (i, q))
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // queue one sample per channel, all or nothing. Full if the whole frame does not fit.
+    pub fn put_frame<const C : usize>(&mut self, frame : &[u32; C]) -> Result<(), StaticFifoError> {
+        if C > self.free_space() {
+            return Err(StaticFifoError::Full);
+        }
+        for s in frame {
+            let _ = self.put(*s);
+        }
+        Ok(())
+    }
+
+    // remove one whole frame of C channels, Empty (nothing removed) if a frame is not complete yet.
+    pub fn get_frame<const C : usize>(&mut self) -> Result<[u32; C], StaticFifoError> {
+        self.get_array::<C>()
+    }
+
+    // number of complete C channel frames queued.
+    pub fn frames_ready<const C : usize>(&self) -> usize {
+        self.len().checked_div(C).unwrap_or(0)
+    }
+
+    // queue an I/Q pair, both or neither.
+    pub fn put_iq(&mut self, i : u32, q : u32) -> Result<(), StaticFifoError> {
+        self.put_frame(&[i, q])
+    }
+
+    // remove one whole I/Q pair, Empty (nothing removed) if only half of one is queued.
+    pub fn get_iq(&mut self) -> Result<(u32, u32), StaticFifoError> {
+        self.get_frame::<2>().map(|[i, q]| (i, q))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn interleaved_frames() {
+        println!("##################### INTERLEAVE TEST ######################################");
+
+        // stereo, left / right
+        let mut audio : StaticFifoU32<6> = StaticFifoU32::new();
+        audio.put_frame(&[1, 2]).unwrap();
+        audio.put_frame(&[3, 4]).unwrap();
+        assert!(audio.put_frame(&[5, 6]) == Err(StaticFifoError::Full), "partial frame fit");
+        assert!(audio.len() == 4, "failed put left a partial frame");
+        assert!(audio.frames_ready::<2>() == 2, "frames_ready wrong");
+        assert!(audio.get_frame::<2>() == Ok([1, 2]), "first frame wrong");
+        audio.put_frame(&[5, 6]).unwrap();
+        assert!(audio.get_frame::<2>() == Ok([3, 4]) && audio.get_frame::<2>() == Ok([5, 6]), "wrapped frames wrong");
+
+        // a stray sample never gets read as half a frame
+        let mut imu : StaticFifoU8<8> = StaticFifoU8::new();
+        imu.put_frame(&[10, 20, 30]).unwrap();
+        imu.put(40).unwrap();
+        assert!(imu.get_frame::<3>() == Ok([10, 20, 30]), "imu frame wrong");
+        assert!(imu.get_frame::<3>() == Err(StaticFifoError::Empty) && imu.len() == 1, "partial frame returned");
+    }
+
+    #[test]
+    fn iq_pairs() {
+        println!("##################### IQ PAIR TEST ######################################");
+
+        let mut iq : StaticFifoU32<6> = StaticFifoU32::new();
+        iq.put_iq(100, 200).unwrap();
+        iq.put_iq(101, 201).unwrap();
+        assert!(iq.put_iq(102, 202) == Err(StaticFifoError::Full) && iq.len() == 4, "half a pair queued");
+        assert!(iq.get_iq() == Ok((100, 200)), "first pair wrong");
+        iq.put_iq(102, 202).unwrap();
+        assert!(iq.get_iq() == Ok((101, 201)) && iq.get_iq() == Ok((102, 202)), "wrapped pairs wrong");
+
+        // a lone I sample stays put until its Q arrives
+        let mut bytes : StaticFifoU8<4> = StaticFifoU8::new();
+        bytes.put(7).unwrap();
+        assert!(bytes.get_iq() == Err(StaticFifoError::Empty) && bytes.len() == 1, "half pair returned");
+        bytes.put(9).unwrap();
+        assert!(bytes.get_iq() == Ok((7, 9)), "pair wrong");
+    }
+}
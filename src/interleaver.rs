@@ -0,0 +1,163 @@
+////////////////////////////////////////////////////////////////
+// Block interleaver
+//
+// Spreads burst errors on a downlink: elements are written in
+// row order into an R x C block and read out in column order,
+// so neighbours in the input end up R apart on the channel and
+// a burst of up to R hits lands as single errors in R
+// different codewords once deinterleaved. R is the depth.
+//
+// The block is filled completely before anything comes out,
+// and takes no more puts until it is drained again. Reading a
+// block in columns is writing its transpose in rows, so the
+// matching deinterleaver is the same adapter with R and C
+// swapped, BlockDeinterleaver<T, R, C>. A short last block
+// goes out with flush(), padded with a fill value.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+// R rows (the depth) of C elements.
+pub struct BlockInterleaver<T, const R : usize, const C : usize> {
+    buf: [[T; C]; R],
+    // elements written into the block, row order
+    written: usize,
+    // elements read out of a complete block, column order
+    read: usize,
+}
+
+// undoes BlockInterleaver<T, R, C>.
+pub type BlockDeinterleaver<T, const R : usize, const C : usize> = BlockInterleaver<T, C, R>;
+
+impl<T : Copy + Default, const R : usize, const C : usize> Default for BlockInterleaver<T, R, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T : Copy + Default, const R : usize, const C : usize> BlockInterleaver<T, R, C> {
+
+    const SIZE_OK : () = assert!(R >= 1 && C >= 1, "BlockInterleaver needs R >= 1 and C >= 1");
+
+    pub fn new() -> Self {
+        let () = Self::SIZE_OK;
+        BlockInterleaver { buf: [[T::default(); C]; R], written: 0, read: 0 }
+    }
+
+    // elements in one block.
+    pub const fn block_len(&self) -> usize {
+        R * C
+    }
+
+    // elements written into a block that is not complete yet.
+    pub fn pending(&self) -> usize {
+        if self.is_complete() { 0 } else { self.written }
+    }
+
+    // pad a partly written block with fill so it can be read out. returns how many were padded.
+    pub fn flush(&mut self, fill : T) -> usize {
+        if self.written == 0 || self.is_complete() {
+            return 0;
+        }
+        let padded = R * C - self.written;
+        while self.written < R * C {
+            self.buf[self.written / C][self.written % C] = fill;
+            self.written += 1;
+        }
+        padded
+    }
+
+    pub fn init(&mut self) {
+        self.written = 0;
+        self.read = 0;
+    }
+
+    fn is_complete(&self) -> bool {
+        self.written == R * C
+    }
+}
+
+// row order in. Full while a complete block waits to be read out.
+impl<T : Copy + Default, const R : usize, const C : usize> FifoProducer<T> for BlockInterleaver<T, R, C> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        if self.is_complete() {
+            return Err(StaticFifoError::Full);
+        }
+        self.buf[self.written / C][self.written % C] = data;
+        self.written += 1;
+        Ok(())
+    }
+
+    fn free_space(&self) -> usize {
+        R * C - self.written
+    }
+}
+
+// column order out. Empty until a block is complete.
+impl<T : Copy + Default, const R : usize, const C : usize> FifoConsumer<T> for BlockInterleaver<T, R, C> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        if !self.is_complete() {
+            return Err(StaticFifoError::Empty);
+        }
+        let rv = self.buf[self.read % R][self.read / R];
+        self.read += 1;
+        if self.read == R * C {
+            self.init();
+        }
+        Ok(rv)
+    }
+
+    // elements ready to read.
+    fn len(&self) -> usize {
+        if self.is_complete() { R * C - self.read } else { 0 }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn interleave_round_trip() {
+        println!("##################### BLOCK INTERLEAVER TEST ######################################");
+
+        let mut il : BlockInterleaver<u8, 3, 4> = BlockInterleaver::new();
+        let mut dil : BlockDeinterleaver<u8, 3, 4> = BlockDeinterleaver::new();
+
+        for v in 0..11 {
+            il.put(v).unwrap();
+        }
+        assert!(il.is_empty() && il.pending() == 11, "incomplete block readable");
+        il.put(11).unwrap();
+        assert!(il.put(12) == Err(StaticFifoError::Full) && il.len() == 12, "complete block takes puts");
+
+        // columns of the 3 x 4 block
+        let mut out = [0u8; 12];
+        for o in out.iter_mut() {
+            *o = il.get().unwrap();
+        }
+        assert!(out == [0, 4, 8, 1, 5, 9, 2, 6, 10, 3, 7, 11], "column order wrong");
+        assert!(il.free_space() == 12, "drained block not released");
+
+        for v in out {
+            dil.put(v).unwrap();
+        }
+        for v in 0..12 {
+            assert!(dil.get() == Ok(v), "deinterleave wrong");
+        }
+
+        // short block padded out
+        for v in 0..5 {
+            il.put(v).unwrap();
+        }
+        assert!(il.flush(0xFF) == 7 && il.len() == 12, "flush did not pad");
+        assert!(il.get() == Ok(0) && il.get() == Ok(4) && il.get() == Ok(0xFF), "padded block wrong");
+    }
+}
@@ -0,0 +1,214 @@
+////////////////////////////////////////////////////////////////
+// Element iterators
+//
+// Non consuming iterators over the queued elements, oldest
+// first, walking the two as_slices() halves in turn. Both are
+// double ended (iter().rev().take(3) for the last three) and
+// know their exact length. &fifo / &mut fifo go straight into
+// a for loop.
+//
+///////////////////////////////////////////////////////////////
+
+use core::iter::FusedIterator;
+use core::slice;
+
+use crate::{StaticFifoU8, StaticFifoU32};
+
+// see StaticFifoU8::iter() / StaticFifoU32::iter().
+pub struct Iter<'a, T> {
+    a: slice::Iter<'a, T>,
+    b: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iter<'a, T> {
+
+    pub(crate) fn new(a : &'a [T], b : &'a [T]) -> Self {
+        Iter { a: a.iter(), b: b.iter() }
+    }
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter { a: self.a.clone(), b: self.b.clone() }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.a.next().or_else(|| self.b.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.a.len() + self.b.len();
+        (left, Some(left))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.b.next_back().or_else(|| self.a.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+// see StaticFifoU8::iter_mut() / StaticFifoU32::iter_mut().
+pub struct IterMut<'a, T> {
+    a: slice::IterMut<'a, T>,
+    b: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.a.next().or_else(|| self.b.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.a.len() + self.b.len();
+        (left, Some(left))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.b.next_back().or_else(|| self.a.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // the queued elements, oldest first, without removing them.
+    pub fn iter(&self) -> Iter<'_, u8> {
+        let (a, b) = self.as_slices();
+        Iter::new(a, b)
+    }
+
+    // the queued elements, oldest first, for fixing them up in place (byte swap,
+    // scaling ..) without popping and putting them back.
+    pub fn iter_mut(&mut self) -> IterMut<'_, u8> {
+        let (a, b) = self.as_mut_slices();
+        IterMut { a: a.iter_mut(), b: b.iter_mut() }
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // the queued elements, oldest first, without removing them.
+    pub fn iter(&self) -> Iter<'_, u32> {
+        let (a, b) = self.as_slices();
+        Iter::new(a, b)
+    }
+
+    // the queued elements, oldest first, for fixing them up in place (byte swap,
+    // scaling ..) without popping and putting them back.
+    pub fn iter_mut(&mut self) -> IterMut<'_, u32> {
+        let (a, b) = self.as_mut_slices();
+        IterMut { a: a.iter_mut(), b: b.iter_mut() }
+    }
+}
+
+impl<'a, const N : usize> IntoIterator for &'a StaticFifoU8<N> {
+    type Item = &'a u8;
+    type IntoIter = Iter<'a, u8>;
+
+    fn into_iter(self) -> Iter<'a, u8> {
+        self.iter()
+    }
+}
+
+impl<'a, const N : usize> IntoIterator for &'a StaticFifoU32<N> {
+    type Item = &'a u32;
+    type IntoIter = Iter<'a, u32>;
+
+    fn into_iter(self) -> Iter<'a, u32> {
+        self.iter()
+    }
+}
+
+impl<'a, const N : usize> IntoIterator for &'a mut StaticFifoU8<N> {
+    type Item = &'a mut u8;
+    type IntoIter = IterMut<'a, u8>;
+
+    fn into_iter(self) -> IterMut<'a, u8> {
+        self.iter_mut()
+    }
+}
+
+impl<'a, const N : usize> IntoIterator for &'a mut StaticFifoU32<N> {
+    type Item = &'a mut u32;
+    type IntoIter = IterMut<'a, u32>;
+
+    fn into_iter(self) -> IterMut<'a, u32> {
+        self.iter_mut()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn exact_lengths() {
+        println!("##################### ITER LENGTH TEST ######################################");
+
+        // wrapped, so both halves are in use
+        let mut fifo : StaticFifoU32<6> = StaticFifoU32::new();
+        for v in 0..5 {
+            fifo.put(v).unwrap();
+        }
+        fifo.skip(3);
+        for v in 5..8 {
+            fifo.put(v).unwrap();
+        }
+
+        let mut it = fifo.iter();
+        assert!(it.len() == 5 && it.size_hint() == (5, Some(5)), "length wrong");
+        it.next();
+        it.next_back();
+        assert!(it.len() == 3, "length after both ends wrong");
+
+        // the three most recent into a fixed array
+        let mut last = [0u32; 3];
+        for (slot, v) in last.iter_mut().zip(fifo.iter().rev()) {
+            *slot = *v;
+        }
+        assert!(last == [7, 6, 5] && fifo.iter_mut().len() == 5, "rev wrong");
+
+        let mut bytes : StaticFifoU8<8> = StaticFifoU8::new();
+        for b in 0..4 {
+            bytes.put(b).unwrap();
+        }
+        assert!(bytes.chunks::<2>().len() == 2, "chunks length wrong");
+    }
+
+    #[test]
+    fn for_over_reference() {
+        println!("##################### ITER FOR LOOP TEST ######################################");
+
+        let mut fifo : StaticFifoU8<8> = StaticFifoU8::new();
+        for b in *b"abc" {
+            fifo.put(b).unwrap();
+        }
+        for b in &mut fifo {
+            *b -= 32;
+        }
+        let mut seen = std::vec::Vec::new();
+        for b in &fifo {
+            seen.push(*b);
+        }
+        assert!(seen == b"ABC" && fifo.len() == 3, "for loop over fifo wrong");
+    }
+}
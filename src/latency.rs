@@ -0,0 +1,158 @@
+////////////////////////////////////////////////////////////////
+// Queueing latency
+//
+// Wrapper measuring how long elements sit in a fifo, so a real
+// time system can check in the field that queueing delays stay
+// inside their budget. Each put stamps the element with the
+// Clock time (in a ring of stamps next to the fifo, the fifo
+// itself is untouched), each get works out the time in queue
+// and folds it into min / max / last. With a budget set, gets
+// over it are counted too.
+//
+// N is the raw size of the stamp ring and has to be at least
+// the fifo's, or puts fail Full before the fifo is. Ticks wrap,
+// like everywhere the Clock is used.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU32, StaticFifoError};
+use crate::timed::Clock;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+// time in queue over the gets since the last reset, in clock ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LatencyStats {
+    // gets measured
+    pub count: u32,
+    pub min: u32,
+    pub max: u32,
+    // the most recent get
+    pub last: u32,
+    // gets over the budget
+    pub over_budget: u32,
+}
+
+pub struct Latency<F, C, const N : usize> {
+    fifo: F,
+    clock: C,
+    stamps: StaticFifoU32<N>,
+    stats: LatencyStats,
+    budget: Option<u32>,
+}
+
+impl<F, C : Clock, const N : usize> Latency<F, C, N> {
+
+    pub fn new(fifo : F, clock : C) -> Self {
+        Latency { fifo, clock, stamps: StaticFifoU32::new(), stats: LatencyStats::default(), budget: None }
+    }
+
+    // count gets whose element waited longer than budget ticks.
+    pub fn set_budget(&mut self, budget : Option<u32>) {
+        self.budget = budget;
+    }
+
+    pub fn budget(&self) -> Option<u32> {
+        self.budget
+    }
+
+    pub fn stats(&self) -> LatencyStats {
+        self.stats
+    }
+
+    // start measuring again, elements already queued keep their stamps.
+    pub fn reset_stats(&mut self) {
+        self.stats = LatencyStats::default();
+    }
+
+    // ticks the oldest element has waited so far, None if nothing is queued.
+    pub fn age_of_front(&self) -> Option<u32> {
+        self.stamps.peek_at(0).ok().map(|t| self.clock.now().wrapping_sub(t))
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.fifo
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo
+    }
+
+    fn record(&mut self, waited : u32) {
+        let s = &mut self.stats;
+        s.min = if s.count == 0 { waited } else { s.min.min(waited) };
+        s.max = s.max.max(waited);
+        s.last = waited;
+        s.count = s.count.saturating_add(1);
+        if self.budget.is_some_and(|b| waited > b) {
+            s.over_budget = s.over_budget.saturating_add(1);
+        }
+    }
+}
+
+impl<T, F : FifoProducer<T>, C : Clock, const N : usize> FifoProducer<T> for Latency<F, C, N> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        if self.stamps.is_full() {
+            return Err(StaticFifoError::Full);
+        }
+        self.fifo.put(data)?;
+        let _ = self.stamps.put(self.clock.now());
+        Ok(())
+    }
+
+    fn free_space(&self) -> usize {
+        core::cmp::min(self.fifo.free_space(), self.stamps.free_space())
+    }
+}
+
+impl<T, F : FifoConsumer<T>, C : Clock, const N : usize> FifoConsumer<T> for Latency<F, C, N> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        let rv = self.fifo.get()?;
+        if let Ok(t) = self.stamps.get() {
+            self.record(self.clock.now().wrapping_sub(t));
+        }
+        Ok(rv)
+    }
+
+    fn len(&self) -> usize {
+        self.fifo.len()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use core::cell::Cell;
+    use super::*;
+
+    #[test]
+    fn time_in_queue() {
+        println!("##################### LATENCY TEST ######################################");
+
+        let ticks = Cell::new(u32::MAX - 2);
+        let mut q : Latency<StaticFifoU32<8>, _, 8> = Latency::new(StaticFifoU32::new(), || ticks.get());
+        q.set_budget(Some(5));
+
+        q.put(1).unwrap();
+        ticks.set(ticks.get().wrapping_add(4));
+        q.put(2).unwrap();
+        q.put(3).unwrap();
+        ticks.set(ticks.get().wrapping_add(3));
+        assert!(q.age_of_front() == Some(7), "front age wrong across the wrap");
+
+        assert!(q.get() == Ok(1) && q.get() == Ok(2), "data wrong");
+        ticks.set(ticks.get().wrapping_add(1));
+        assert!(q.get() == Ok(3), "data wrong");
+        let s = q.stats();
+        assert!(s.count == 3 && s.min == 3 && s.max == 7 && s.last == 4, "latency stats wrong");
+        assert!(s.over_budget == 1, "budget overruns wrong");
+
+        q.reset_stats();
+        q.put(4).unwrap();
+        assert!(q.get() == Ok(4) && q.stats().min == 0 && q.stats().count == 1, "reset wrong");
+        assert!(q.get().is_err() && q.stats().count == 1, "empty get measured");
+    }
+}
@@ -14,6 +14,160 @@
 use core::result::Result;
 use core::result::Result::{Ok,Err};
 
+pub mod crc;
+pub mod marker;
+pub mod fixed;
+pub mod framefifo;
+pub mod timed;
+pub mod coalesce;
+pub mod dedup;
+pub mod priority;
+pub mod deque;
+pub mod stack;
+pub mod delay;
+pub mod windows;
+pub mod iter;
+pub mod chunks;
+pub mod decimate;
+pub mod interleave;
+pub mod pingpong;
+pub mod transfer;
+pub mod tee;
+pub mod zip;
+pub mod pool;
+pub mod blockpool;
+pub mod bytefifo;
+pub mod traits;
+pub mod spsc;
+pub mod convert;
+pub mod hexdump;
+pub mod numfmt;
+pub mod text;
+pub mod ratelimit;
+pub mod watchdog;
+pub mod blocking;
+pub mod select;
+pub mod mailbox;
+pub mod lock;
+pub mod cellfifo;
+pub mod inspect;
+pub mod writer;
+pub mod headroom;
+pub mod quota;
+pub mod freeze;
+pub mod sequence;
+pub mod overwrite;
+pub mod flow;
+pub mod can;
+pub mod audio;
+pub mod adc;
+pub mod packed;
+pub mod bufqueue;
+mod atomic;
+pub mod arena;
+pub mod interleaver;
+pub mod randomizer;
+pub mod bitfifo;
+pub mod delta;
+pub mod rle;
+pub mod stats;
+pub mod histogram;
+pub mod latency;
+pub mod demux;
+pub mod vectored;
+pub mod padded;
+
+#[cfg(feature = "postcard")]
+pub mod typed;
+
+#[cfg(all(kani, verification))]
+mod proofs;
+#[cfg(test)]
+mod proptests;
+#[cfg(test)]
+mod sendsync;
+
+pub use framefifo::{FrameFifo, RecordCrc};
+pub use timed::{Clock, TimedFifo};
+pub use coalesce::CoalesceFifo;
+pub use dedup::Dedup;
+pub use priority::{PriorityFifo, Lane};
+pub use deque::StaticDeque;
+pub use stack::StaticStack;
+pub use pingpong::PingPongBuffer;
+pub use pool::{StaticPool, PoolHandle};
+pub use blockpool::{BlockPool, Block};
+pub use bytefifo::ByteFifo;
+pub use traits::{FifoProducer, FifoConsumer};
+pub use ratelimit::RateLimiter;
+pub use watchdog::Watchdog;
+pub use blocking::{BlockingProducer, BlockingConsumer};
+pub use select::{Ready, poll_any, poll_any_from, ready_set, get_any, RoundRobin};
+pub use mailbox::{Mailbox, MailboxProducer, MailboxConsumer, MailboxStats, Barrier, Fence};
+pub use lock::{Lock, LockedFifo};
+pub use cellfifo::{CellFifo, CellProducer, CellConsumer};
+pub use inspect::{Inspect, Inspector, HighWater};
+pub use writer::Writer;
+pub use headroom::{Headroom, Priority};
+pub use quota::{QuotaFifo, QuotaProducer, ProducerStats};
+pub use freeze::Freezable;
+pub use sequence::{Sequenced, SeqTracker, SeqCheck};
+pub use overwrite::Overwrite;
+pub use flow::{Rts, RtsPin};
+pub use can::{CanFifo, CanFrame};
+pub use audio::{AudioTx, AudioRx};
+pub use adc::{AdcStream, OverrunPolicy};
+pub use packed::{PackedFifo, Packable, Nibble, NibbleFifo, BoolFifo};
+pub use bufqueue::{BufferQueue, BufProducer, BufConsumer, Packet};
+pub use arena::ArenaQueue;
+pub use interleaver::{BlockInterleaver, BlockDeinterleaver};
+pub use randomizer::{Randomizer, RandomizeOn};
+pub use bitfifo::StaticBitFifo;
+pub use delta::DeltaFifoU32;
+pub use rle::RleFifo;
+pub use histogram::OccupancyHistogram;
+pub use latency::{Latency, LatencyStats};
+pub use demux::Demux;
+#[cfg(feature = "postcard")]
+pub use typed::TypedFifo;
+
+#[cfg(feature = "cobs")]
+pub mod cobs;
+#[cfg(feature = "slip")]
+pub mod slip;
+#[cfg(feature = "hdlc")]
+pub mod hdlc;
+#[cfg(feature = "lz")]
+pub mod lz;
+#[cfg(feature = "lz")]
+pub use lz::{LzWriter, LzReader};
+#[cfg(feature = "ufmt")]
+pub mod ufmt;
+#[cfg(feature = "std")]
+pub mod channel;
+#[cfg(feature = "zeroize")]
+mod scrub;
+#[cfg(feature = "cortex-m")]
+pub mod basepri;
+#[cfg(feature = "cortex-m")]
+pub use basepri::BasepriFifo;
+#[cfg(feature = "riscv")]
+pub mod mie;
+#[cfg(feature = "riscv")]
+pub use mie::MieFifo;
+#[cfg(feature = "usb-device")]
+pub mod usb;
+#[cfg(feature = "usb-device")]
+pub use usb::{UsbSerialBuffers, CdcPackets};
+#[cfg(feature = "ecc")]
+pub mod ecc;
+#[cfg(feature = "ecc")]
+pub use ecc::EccFifoU8;
+#[cfg(feature = "tmr")]
+pub mod tmr;
+#[cfg(feature = "tmr")]
+pub use tmr::TmrFifoU32;
+
 // note length specified is raw storage container.
 // fifo full pointers take up 1 element. so you will need N+1
 pub struct StaticFifoU8<const N : usize> {
@@ -21,15 +175,46 @@ pub struct StaticFifoU8<const N : usize> {
     read_ptr: usize,
     write_ptr: usize,
     capacity: usize,
+    // runtime cap on the queued elements, usize::MAX for none.
+    soft_cap: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StaticFifoError {
     Empty,
     Full,
+    // frame in the fifo could not be decoded (and was dropped)
+    Malformed,
+    // decoded frame would not fit in the callers buffer (and was dropped)
+    TooLarge,
+    // data failed its integrity check (and was dropped)
+    Corrupt,
+    // data is queued but may not be taken yet (rate limit)
+    Throttled,
+    // fifo is frozen, puts are refused until it is thawed
+    Frozen,
+    // the other end of a channel is gone
+    Disconnected,
+}
+
+impl<const N : usize> Default for StaticFifoU8<N> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<const N : usize> StaticFifoU8<N> {
 
+    // one slot is the full marker, so below 2 the fifo could never hold anything.
+    // evaluated when new() is instantiated, StaticFifoU8::<1>::new() fails to build.
+    const CAPACITY_OK : () = assert!(N >= 2, "StaticFifoU8 needs N >= 2, one slot is lost to the full marker");
+
+    // create new empty StaticFifoU8
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY_OK;
+        StaticFifoU8 { buf: [0; N], read_ptr: 0, write_ptr: 0, capacity: N, soft_cap: usize::MAX }
+    }
+
     #[inline]
     pub fn init(&mut self) {
         self.read_ptr = 0;
@@ -37,57 +222,71 @@ impl<const N : usize> StaticFifoU8<N> {
         self.capacity = N;
     }
 
+    // drop everything queued. with the zeroize feature the whole storage is
+    // scrubbed as well, not just the queued part.
+    pub fn clear(&mut self) {
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut self.buf);
+        self.init();
+    }
+
+    // p folded back into the storage, for p below twice the capacity.
+    // compare and subtract instead of %, no divide and no divide by zero. there is no
+    // bitmask variant, so N does not have to be a power of two.
+    #[inline]
+    fn wrap(&self, p : usize) -> usize {
+        if p >= self.capacity { p.wrapping_sub(self.capacity) } else { p }
+    }
+
     #[inline]
     fn increment_readptr(&mut self) {
-        self.read_ptr = (self.read_ptr + 1) % self.capacity;
+        self.read_ptr = self.wrap(self.read_ptr.wrapping_add(1));
     }
 
     #[inline]
     fn increment_writeptr(&mut self) {
-        self.write_ptr = (self.write_ptr + 1) % self.capacity;
+        self.write_ptr = self.wrap(self.write_ptr.wrapping_add(1));
     }
     
     #[inline]
     pub fn is_empty(&self) -> bool {
-        if self.read_ptr == self.write_ptr {
-            return true;
-        }
-        return false;
+        self.read_ptr == self.write_ptr
     }
 
+    // also true once the soft capacity is reached.
     #[inline]
     pub fn is_full(&self) -> bool {
-        let rp1 : usize = (self.write_ptr+1) % self.capacity;
-        if rp1 == self.read_ptr {
-            true
-        } else {
-            false
-        }
+        let rp1 : usize = self.wrap(self.write_ptr.wrapping_add(1));
+        rp1 == self.read_ptr || self.len() >= self.soft_cap
     }
 
+    // pointers outside the storage can only come from memory corruption, Corrupt then.
     #[inline]
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
     pub fn get(&mut self) -> Result<u8, StaticFifoError> {
         if self.is_empty() {
             return Err(StaticFifoError::Empty);
         }
-        let rv :u8 = self.buf[self.read_ptr];
+        let rv :u8 = *self.buf.get(self.read_ptr).ok_or(StaticFifoError::Corrupt)?;
         self.increment_readptr();
-        return Ok(rv)
+        Ok(rv)
     }
 
     #[inline]
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
     pub fn put(&mut self, data : u8) -> Result<(), StaticFifoError> {
         if self.is_full() {
             return Err(StaticFifoError::Full);
         }
-        self.buf[self.write_ptr] = data; 
+        *self.buf.get_mut(self.write_ptr).ok_or(StaticFifoError::Corrupt)? = data;
         self.increment_writeptr();
-        return Ok(())
+        Ok(())
     }
 
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
     pub fn len(&self) -> usize {
         if self.read_ptr > self.write_ptr {
-            (self.capacity - self.read_ptr) + self.write_ptr
+            self.capacity.wrapping_sub(self.read_ptr).wrapping_add(self.write_ptr)
         } else {
             self.write_ptr - self.read_ptr
         }
@@ -96,6 +295,100 @@ impl<const N : usize> StaticFifoU8<N> {
     pub fn max_len(&self) -> usize {
         self.capacity
     }
+
+    // number of elements that can still be put before the fifo is full.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn free_space(&self) -> usize {
+        core::cmp::min(self.capacity.saturating_sub(1), self.soft_cap).saturating_sub(self.len())
+    }
+
+    // make put() act as if the fifo held only n elements, for shrinking the buffer
+    // at runtime (low power mode ..). n is clamped to the real capacity. data already
+    // queued past n stays and drains normally. kept over init() / clear().
+    pub fn set_soft_capacity(&mut self, n : usize) {
+        self.soft_cap = n;
+    }
+
+    // elements put() will accept at most right now.
+    pub fn soft_capacity(&self) -> usize {
+        core::cmp::min(self.capacity.saturating_sub(1), self.soft_cap)
+    }
+
+    // look at the element i places from the read side without removing it.
+    #[inline]
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn peek_at(&self, i : usize) -> Result<u8, StaticFifoError> {
+        if i >= self.len() {
+            return Err(StaticFifoError::Empty);
+        }
+        self.buf.get(self.wrap(self.read_ptr.wrapping_add(i))).copied().ok_or(StaticFifoError::Empty)
+    }
+
+    // offset from the read side of the first queued element equal to value.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn find(&self, value : u8) -> Option<usize> {
+        (0..self.len()).find(|&i| self.buf.get(self.wrap(self.read_ptr.wrapping_add(i))) == Some(&value))
+    }
+
+    // queued elements in order as two slices, the second one is only used when the data wraps.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn as_slices(&self) -> (&[u8], &[u8]) {
+        if self.read_ptr <= self.write_ptr {
+            (self.buf.get(self.read_ptr..self.write_ptr).unwrap_or(&[]), &[])
+        } else {
+            (self.buf.get(self.read_ptr..self.capacity).unwrap_or(&[]), self.buf.get(..self.write_ptr).unwrap_or(&[]))
+        }
+    }
+
+    // as_slices(), writable. the elements can be changed in place, not added or removed.
+    pub fn as_mut_slices(&mut self) -> (&mut [u8], &mut [u8]) {
+        let (r, w) = (self.read_ptr, self.write_ptr);
+        if r <= w {
+            (self.buf.get_mut(r..w).unwrap_or(&mut []), &mut [])
+        } else {
+            let len = self.capacity.wrapping_sub(r);
+            match self.buf.split_at_mut_checked(r) {
+                Some((head, tail)) => (tail.get_mut(..len).unwrap_or(&mut []), head.get_mut(..w).unwrap_or(&mut [])),
+                None => (&mut [], &mut []),
+            }
+        }
+    }
+
+    // rotate the storage so the queued elements sit in one slice, returns that slice.
+    // costs two passes over the whole buffer when the data wraps, nothing otherwise.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn make_contiguous(&mut self) -> &mut [u8] {
+        if self.read_ptr > self.write_ptr {
+            let len = self.len();
+            // rotate_left() by hand with three reversals, rotate_left() itself can panic.
+            if let Some((head, tail)) = self.buf.split_at_mut_checked(self.read_ptr) {
+                head.reverse();
+                tail.reverse();
+                self.buf.reverse();
+                self.read_ptr = 0;
+                self.write_ptr = len;
+            }
+        }
+        self.buf.get_mut(self.read_ptr..self.write_ptr).unwrap_or(&mut [])
+    }
+
+    // drop up to n elements from the read side, returns how many were dropped.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn skip(&mut self, n : usize) -> usize {
+        let n = core::cmp::min(n, self.len());
+        self.read_ptr = self.wrap(self.read_ptr.wrapping_add(n));
+        n
+    }
+
+    // take the next element only if f approves of it, the fifo is left as it was otherwise.
+    pub fn pop_if(&mut self, f : impl FnOnce(&u8) -> bool) -> Option<u8> {
+        let next = self.peek_at(0).ok()?;
+        if !f(&next) {
+            return None;
+        }
+        self.increment_readptr();
+        Some(next)
+    }
 }
 
 
@@ -107,10 +400,28 @@ pub struct StaticFifoU32<const N : usize> {
     read_ptr: usize,
     write_ptr: usize,
     capacity: usize,
+    // runtime cap on the queued elements, usize::MAX for none.
+    soft_cap: usize,
+}
+
+impl<const N : usize> Default for StaticFifoU32<N> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<const N : usize> StaticFifoU32<N> {
 
+    // one slot is the full marker, so below 2 the fifo could never hold anything.
+    // evaluated when new() is instantiated, StaticFifoU32::<1>::new() fails to build.
+    const CAPACITY_OK : () = assert!(N >= 2, "StaticFifoU32 needs N >= 2, one slot is lost to the full marker");
+
+    // create new empty StaticFifoU32
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY_OK;
+        StaticFifoU32 { buf: [0; N], read_ptr: 0, write_ptr: 0, capacity: N, soft_cap: usize::MAX }
+    }
+
     // create new StaticFifoU8
     #[inline]
     pub fn init(&mut self) {
@@ -119,57 +430,71 @@ impl<const N : usize> StaticFifoU32<N> {
         self.capacity = N;
     }
 
+    // drop everything queued. with the zeroize feature the whole storage is
+    // scrubbed as well, not just the queued part.
+    pub fn clear(&mut self) {
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut self.buf);
+        self.init();
+    }
+
+    // p folded back into the storage, for p below twice the capacity.
+    // compare and subtract instead of %, no divide and no divide by zero. there is no
+    // bitmask variant, so N does not have to be a power of two.
+    #[inline]
+    fn wrap(&self, p : usize) -> usize {
+        if p >= self.capacity { p.wrapping_sub(self.capacity) } else { p }
+    }
+
     #[inline]
     fn increment_readptr(&mut self) {
-        self.read_ptr = (self.read_ptr + 1) % self.capacity;
+        self.read_ptr = self.wrap(self.read_ptr.wrapping_add(1));
     }
 
     #[inline]
     fn increment_writeptr(&mut self) {
-        self.write_ptr = (self.write_ptr + 1) % self.capacity;
+        self.write_ptr = self.wrap(self.write_ptr.wrapping_add(1));
     }
     
     #[inline]
     pub fn is_empty(&self) -> bool {
-        if self.read_ptr == self.write_ptr {
-            return true;
-        }
-        return false;
+        self.read_ptr == self.write_ptr
     }
 
+    // also true once the soft capacity is reached.
     #[inline]
     pub fn is_full(&self) -> bool {
-        let rp1 : usize = (self.write_ptr+1) % self.capacity;
-        if rp1 == self.read_ptr {
-            true
-        } else {
-            false
-        }
+        let rp1 : usize = self.wrap(self.write_ptr.wrapping_add(1));
+        rp1 == self.read_ptr || self.len() >= self.soft_cap
     }
 
+    // pointers outside the storage can only come from memory corruption, Corrupt then.
     #[inline]
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
     pub fn get(&mut self) -> Result<u32, StaticFifoError> {
         if self.is_empty() {
             return Err(StaticFifoError::Empty);
         }
-        let rv :u32 = self.buf[self.read_ptr];
+        let rv :u32 = *self.buf.get(self.read_ptr).ok_or(StaticFifoError::Corrupt)?;
         self.increment_readptr();
-        return Ok(rv)
+        Ok(rv)
     }
 
     #[inline]
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
     pub fn put(&mut self, data : u32) -> Result<(), StaticFifoError> {
         if self.is_full() {
             return Err(StaticFifoError::Full);
         }
-        self.buf[self.write_ptr] = data; 
+        *self.buf.get_mut(self.write_ptr).ok_or(StaticFifoError::Corrupt)? = data;
         self.increment_writeptr();
-        return Ok(())
+        Ok(())
     }
 
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
     pub fn len(&self) -> usize {
         if self.read_ptr > self.write_ptr {
-            (self.capacity - self.read_ptr) + self.write_ptr
+            self.capacity.wrapping_sub(self.read_ptr).wrapping_add(self.write_ptr)
         } else {
             self.write_ptr - self.read_ptr
         }
@@ -178,6 +503,100 @@ impl<const N : usize> StaticFifoU32<N> {
     pub fn max_len(&self) -> usize {
         self.capacity
     }
+
+    // number of elements that can still be put before the fifo is full.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn free_space(&self) -> usize {
+        core::cmp::min(self.capacity.saturating_sub(1), self.soft_cap).saturating_sub(self.len())
+    }
+
+    // make put() act as if the fifo held only n elements, for shrinking the buffer
+    // at runtime (low power mode ..). n is clamped to the real capacity. data already
+    // queued past n stays and drains normally. kept over init() / clear().
+    pub fn set_soft_capacity(&mut self, n : usize) {
+        self.soft_cap = n;
+    }
+
+    // elements put() will accept at most right now.
+    pub fn soft_capacity(&self) -> usize {
+        core::cmp::min(self.capacity.saturating_sub(1), self.soft_cap)
+    }
+
+    // look at the element i places from the read side without removing it.
+    #[inline]
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn peek_at(&self, i : usize) -> Result<u32, StaticFifoError> {
+        if i >= self.len() {
+            return Err(StaticFifoError::Empty);
+        }
+        self.buf.get(self.wrap(self.read_ptr.wrapping_add(i))).copied().ok_or(StaticFifoError::Empty)
+    }
+
+    // offset from the read side of the first queued element equal to value.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn find(&self, value : u32) -> Option<usize> {
+        (0..self.len()).find(|&i| self.buf.get(self.wrap(self.read_ptr.wrapping_add(i))) == Some(&value))
+    }
+
+    // queued elements in order as two slices, the second one is only used when the data wraps.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn as_slices(&self) -> (&[u32], &[u32]) {
+        if self.read_ptr <= self.write_ptr {
+            (self.buf.get(self.read_ptr..self.write_ptr).unwrap_or(&[]), &[])
+        } else {
+            (self.buf.get(self.read_ptr..self.capacity).unwrap_or(&[]), self.buf.get(..self.write_ptr).unwrap_or(&[]))
+        }
+    }
+
+    // as_slices(), writable. the elements can be changed in place, not added or removed.
+    pub fn as_mut_slices(&mut self) -> (&mut [u32], &mut [u32]) {
+        let (r, w) = (self.read_ptr, self.write_ptr);
+        if r <= w {
+            (self.buf.get_mut(r..w).unwrap_or(&mut []), &mut [])
+        } else {
+            let len = self.capacity.wrapping_sub(r);
+            match self.buf.split_at_mut_checked(r) {
+                Some((head, tail)) => (tail.get_mut(..len).unwrap_or(&mut []), head.get_mut(..w).unwrap_or(&mut [])),
+                None => (&mut [], &mut []),
+            }
+        }
+    }
+
+    // rotate the storage so the queued elements sit in one slice, returns that slice.
+    // costs two passes over the whole buffer when the data wraps, nothing otherwise.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn make_contiguous(&mut self) -> &mut [u32] {
+        if self.read_ptr > self.write_ptr {
+            let len = self.len();
+            // rotate_left() by hand with three reversals, rotate_left() itself can panic.
+            if let Some((head, tail)) = self.buf.split_at_mut_checked(self.read_ptr) {
+                head.reverse();
+                tail.reverse();
+                self.buf.reverse();
+                self.read_ptr = 0;
+                self.write_ptr = len;
+            }
+        }
+        self.buf.get_mut(self.read_ptr..self.write_ptr).unwrap_or(&mut [])
+    }
+
+    // drop up to n elements from the read side, returns how many were dropped.
+    #[cfg_attr(all(feature = "no-panic", not(debug_assertions)), no_panic::no_panic)]
+    pub fn skip(&mut self, n : usize) -> usize {
+        let n = core::cmp::min(n, self.len());
+        self.read_ptr = self.wrap(self.read_ptr.wrapping_add(n));
+        n
+    }
+
+    // take the next element only if f approves of it, the fifo is left as it was otherwise.
+    pub fn pop_if(&mut self, f : impl FnOnce(&u32) -> bool) -> Option<u32> {
+        let next = self.peek_at(0).ok()?;
+        if !f(&next) {
+            return None;
+        }
+        self.increment_readptr();
+        Some(next)
+    }
 }
 
 
@@ -188,6 +607,7 @@ impl<const N : usize> StaticFifoU32<N> {
 /////////////////////////////////////
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::unused_unit, clippy::len_zero, clippy::unnecessary_cast)]
 mod tests {
 
     use std::println;
@@ -199,7 +619,7 @@ mod tests {
         println!("##################### FIFO FUNCTIONAL TEST U8 ######################################");
         
         // create static fifo of 16 bytes
-        let mut byte_fifo : StaticFifoU8<16> = StaticFifoU8::<16> { buf: [0;16], read_ptr: 0, write_ptr: 0, capacity: 16 };
+        let mut byte_fifo : StaticFifoU8<16> = StaticFifoU8::<16> { buf: [0;16], read_ptr: 0, write_ptr: 0, capacity: 16, soft_cap: usize::MAX };
 
 
         assert!( byte_fifo.is_empty(), "FIFO not empty at startup..");
@@ -325,7 +745,7 @@ mod tests {
         println!("##################### FIFO FUNCTIONAL TEST U32 ######################################");
 
         // create static fifo of 16 bytes
-        let mut byte_fifo : StaticFifoU32<16> = StaticFifoU32::<16> { buf: [0;16], read_ptr:0, write_ptr:0, capacity:16 };
+        let mut byte_fifo : StaticFifoU32<16> = StaticFifoU32::<16> { buf: [0;16], read_ptr:0, write_ptr:0, capacity:16, soft_cap: usize::MAX };
 
 
         assert!( byte_fifo.is_empty(), "FIFO not empty at startup..");
@@ -443,5 +863,126 @@ mod tests {
 
         Ok(())
     }
-}
 
+    #[test]
+    fn fifo_corrupt_pointers_do_not_panic() {
+
+        println!("##################### FIFO CORRUPT POINTER TEST ######################################");
+
+        // pointers knocked out of range (bit flip, stray write ..) give errors, not panics.
+        let mut byte_fifo : StaticFifoU8<16> = StaticFifoU8::<16> { buf: [0;16], read_ptr: 40, write_ptr: 3, capacity: 16, soft_cap: usize::MAX };
+        assert!( byte_fifo.get() == Err(StaticFifoError::Corrupt), "get with bad read pointer" );
+        let _ = byte_fifo.peek_at(0);
+        let _ = byte_fifo.as_slices();
+        let _ = byte_fifo.make_contiguous();
+        let _ = byte_fifo.free_space();
+
+        let mut word_fifo : StaticFifoU32<16> = StaticFifoU32::<16> { buf: [0;16], read_ptr: 0, write_ptr: 99, capacity: 16, soft_cap: usize::MAX };
+        assert!( word_fifo.put(1).is_err(), "put with bad write pointer" );
+        let _ = word_fifo.skip(100);
+        let _ = word_fifo.as_slices();
+        let _ = word_fifo.make_contiguous();
+    }
+
+    #[test]
+    fn fifo_soft_capacity() {
+
+        println!("##################### FIFO SOFT CAPACITY TEST ######################################");
+
+        let mut byte_fifo : StaticFifoU8<16> = StaticFifoU8::new();
+        assert!( byte_fifo.soft_capacity() == 15, "default soft capacity not the full fifo" );
+        byte_fifo.set_soft_capacity(4);
+        for i in 0..4 {
+            byte_fifo.put(i).unwrap();
+        }
+        assert!( byte_fifo.put(4) == Err(StaticFifoError::Full) && byte_fifo.is_full(), "put past soft capacity" );
+        assert!( byte_fifo.free_space() == 0, "free space ignores soft capacity" );
+
+        // shrinking below what is queued keeps the data, puts wait for the drain
+        byte_fifo.set_soft_capacity(2);
+        assert!( byte_fifo.get() == Ok(0) && byte_fifo.put(9).is_err(), "put while over soft capacity" );
+        byte_fifo.skip(2);
+        assert!( byte_fifo.put(9).is_ok() && byte_fifo.len() == 2, "put after drain" );
+
+        // clamped to the real capacity, and kept over clear()
+        let mut word_fifo : StaticFifoU32<4> = StaticFifoU32::new();
+        word_fifo.set_soft_capacity(100);
+        assert!( word_fifo.soft_capacity() == 3 && word_fifo.free_space() == 3, "soft capacity not clamped" );
+        word_fifo.set_soft_capacity(1);
+        word_fifo.clear();
+        assert!( word_fifo.put(1).is_ok() && word_fifo.put(2).is_err(), "soft capacity lost on clear" );
+    }
+
+    #[test]
+    fn fifo_pop_if() {
+
+        println!("##################### FIFO POP IF TEST ######################################");
+
+        // deadlines, pop only the ones that have passed
+        let mut word_fifo : StaticFifoU32<8> = StaticFifoU32::new();
+        for d in [10, 20, 30] {
+            word_fifo.put(d).unwrap();
+        }
+        let now = 15;
+        assert!( word_fifo.pop_if(|&d| d <= now) == Some(10), "due element not popped" );
+        assert!( word_fifo.pop_if(|&d| d <= now).is_none() && word_fifo.len() == 2, "element popped too early" );
+        assert!( word_fifo.peek_at(0) == Ok(20), "fifo touched on refusal" );
+
+        let mut byte_fifo : StaticFifoU8<4> = StaticFifoU8::new();
+        assert!( byte_fifo.pop_if(|_| true).is_none(), "empty fifo popped" );
+        byte_fifo.put(b'a').unwrap();
+        assert!( byte_fifo.pop_if(|&b| b == b'a') == Some(b'a') && byte_fifo.is_empty(), "byte pop_if wrong" );
+    }
+
+    #[test]
+    fn fifo_iter_mut() {
+
+        println!("##################### FIFO ITER MUT TEST ######################################");
+
+        // raw big endian samples, wrapped around the end of the storage
+        let mut word_fifo : StaticFifoU32<4> = StaticFifoU32::new();
+        word_fifo.put(0).unwrap();
+        word_fifo.put(0).unwrap();
+        word_fifo.skip(2);
+        for v in [1u32, 2, 3] {
+            word_fifo.put(v.to_be()).unwrap();
+        }
+        for v in word_fifo.iter_mut() {
+            *v = u32::from_be(*v) * 10;
+        }
+        assert!( word_fifo.get() == Ok(10) && word_fifo.get() == Ok(20) && word_fifo.get() == Ok(30), "in place fix up wrong" );
+
+        let mut byte_fifo : StaticFifoU8<8> = StaticFifoU8::new();
+        assert!( byte_fifo.iter_mut().next().is_none(), "empty fifo iterated" );
+        byte_fifo.put(b'a').unwrap();
+        byte_fifo.put(b'b').unwrap();
+        byte_fifo.iter_mut().for_each(|b| b.make_ascii_uppercase());
+        assert!( byte_fifo.as_slices().0 == b"AB", "byte fix up wrong" );
+    }
+
+    #[test]
+    fn fifo_iter_both_ends() {
+
+        println!("##################### FIFO ITER TEST ######################################");
+
+        // last n events, across the wrap
+        let mut word_fifo : StaticFifoU32<6> = StaticFifoU32::new();
+        for v in 0..5 {
+            word_fifo.put(v).unwrap();
+        }
+        word_fifo.skip(3);
+        for v in 5..8 {
+            word_fifo.put(v).unwrap();
+        }
+        assert!( word_fifo.iter().copied().eq(3..8), "forward order wrong" );
+        assert!( word_fifo.iter().rev().take(3).copied().eq([7, 6, 5]), "last three wrong" );
+        assert!( word_fifo.len() == 5, "iter removed data" );
+
+        let mut byte_fifo : StaticFifoU8<4> = StaticFifoU8::new();
+        assert!( byte_fifo.iter().next_back().is_none(), "empty fifo iterated" );
+        byte_fifo.put(1).unwrap();
+        byte_fifo.put(2).unwrap();
+        let mut it = byte_fifo.iter();
+        assert!( it.next() == Some(&1) && it.next_back() == Some(&2) && it.next().is_none(), "ends did not meet" );
+    }
+}
@@ -0,0 +1,179 @@
+////////////////////////////////////////////////////////////////
+// Pluggable lock
+//
+// LockedFifo guards a fifo with whatever exclusion the part
+// provides, through the Lock trait: a hardware spinlock (RP2040
+// SIO, NXP SEMA42 ..), a spinlock plus interrupt mask, an RTOS
+// mutex. Any number of producers / consumers on any core can
+// then share one fifo.
+//
+// acquire() hands back a token that release() gets again, so a
+// lock can carry state over, e.g. the interrupt enable it saved
+// before taking the spinlock.
+//
+///////////////////////////////////////////////////////////////
+
+use core::cell::UnsafeCell;
+
+use crate::StaticFifoError;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+// safety: while a token from acquire() is live, no other acquire() on the same
+// lock (from any core or context) may return.
+#[allow(clippy::missing_safety_doc)]
+pub unsafe trait Lock {
+    type Token;
+
+    // wait for the lock and take it.
+    fn acquire(&self) -> Self::Token;
+
+    // give the lock back.
+    fn release(&self, token : Self::Token);
+}
+
+// holds the token while the fifo is in use and gives the lock back on drop, so a
+// panic in the caller's closure unwinds through a release instead of leaving a
+// hardware spinlock taken for good.
+struct Held<'a, L : Lock> {
+    lock: &'a L,
+    token: Option<L::Token>,
+}
+
+impl<'a, L : Lock> Drop for Held<'a, L> {
+    fn drop(&mut self) {
+        if let Some(token) = self.token.take() {
+            self.lock.release(token);
+        }
+    }
+}
+
+pub struct LockedFifo<F, L> {
+    fifo: UnsafeCell<F>,
+    lock: L,
+}
+
+// the lock lets only one context at a time reach the fifo.
+unsafe impl<F : Send, L : Lock + Sync> Sync for LockedFifo<F, L> {}
+
+impl<F, L : Lock> LockedFifo<F, L> {
+
+    // const so it can sit in a static shared between cores.
+    pub const fn new(fifo : F, lock : L) -> Self {
+        LockedFifo { fifo: UnsafeCell::new(fifo), lock }
+    }
+
+    // run f on the fifo with the lock held. f must not lock the same fifo
+    // again, with a spinlock that never returns. released even if f panics.
+    pub fn with<R>(&self, f : impl FnOnce(&mut F) -> R) -> R {
+        let _held = Held { lock: &self.lock, token: Some(self.lock.acquire()) };
+        // the lock rules out any other &mut while it is held.
+        f(unsafe { &mut *self.fifo.get() })
+    }
+
+    pub fn put<T>(&self, data : T) -> Result<(), StaticFifoError> where F : FifoProducer<T> {
+        self.with(|fifo| fifo.put(data))
+    }
+
+    pub fn get<T>(&self) -> Result<T, StaticFifoError> where F : FifoConsumer<T> {
+        self.with(|fifo| fifo.get())
+    }
+
+    pub fn lock(&self) -> &L {
+        &self.lock
+    }
+
+    // the fifo itself, no locking needed with exclusive access.
+    pub fn get_mut(&mut self) -> &mut F {
+        self.fifo.get_mut()
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo.into_inner()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use std::thread;
+    use std::vec::Vec;
+    use crate::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use super::*;
+    use crate::StaticFifoU32;
+
+    // stand in for a hardware spinlock register, counts how often it was taken
+    struct TestSpinLock {
+        held: AtomicBool,
+        taken: AtomicUsize,
+    }
+
+    unsafe impl Lock for TestSpinLock {
+        type Token = ();
+
+        fn acquire(&self) {
+            while self.held.swap(true, Ordering::Acquire) {
+                thread::yield_now();
+            }
+            self.taken.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn release(&self, _token : ()) {
+            self.held.store(false, Ordering::Release);
+        }
+    }
+
+    static SHARED : LockedFifo<StaticFifoU32<64>, TestSpinLock> =
+        LockedFifo::new(StaticFifoU32::new(), TestSpinLock { held: AtomicBool::new(false), taken: AtomicUsize::new(0) });
+
+    #[test]
+    fn mpmc_through_lock() {
+        println!("##################### LOCKED FIFO TEST ######################################");
+
+        // 4 producers, 2 consumers
+        let producers : Vec<_> = (0..4u32).map(|p| thread::spawn(move || {
+            for i in 0..100 {
+                while SHARED.put(p * 1000 + i).is_err() {
+                    thread::yield_now();
+                }
+            }
+        })).collect();
+        let consumers : Vec<_> = (0..2).map(|_| thread::spawn(|| {
+            let mut got = Vec::new();
+            while got.len() < 200 {
+                match SHARED.get() {
+                    Ok(v) => got.push(v),
+                    Err(_) => thread::yield_now(),
+                }
+            }
+            got
+        })).collect();
+        for p in producers {
+            p.join().unwrap();
+        }
+        let mut all : Vec<u32> = consumers.into_iter().flat_map(|c| c.join().unwrap()).collect();
+        all.sort();
+        let mut expect : Vec<u32> = (0..4).flat_map(|p| (0..100).map(move |i| p * 1000 + i)).collect();
+        expect.sort();
+        assert!(all == expect, "elements lost or duplicated");
+        assert!(SHARED.with(|f| f.is_empty()), "fifo not empty");
+        assert!(SHARED.lock().taken.load(Ordering::Relaxed) >= 800, "lock not taken per access");
+    }
+    #[test]
+    fn released_on_panic() {
+        println!("##################### LOCKED FIFO PANIC TEST ######################################");
+
+        let locked = LockedFifo::new(StaticFifoU32::<4>::new(), TestSpinLock { held: AtomicBool::new(false), taken: AtomicUsize::new(0) });
+        let rv = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            locked.with(|f| {
+                f.put(1).unwrap();
+                panic!("closure failed with the lock held");
+            })
+        }));
+        assert!(rv.is_err(), "panic not propagated");
+        // a lock still held here would spin forever
+        assert!(!locked.lock().held.load(Ordering::Relaxed), "lock not released on panic");
+        assert!(locked.get() == Ok(1), "fifo unusable after the panic");
+    }
+}
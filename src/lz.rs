@@ -0,0 +1,341 @@
+////////////////////////////////////////////////////////////////
+// LZ compression
+//
+// heatshrink style LZSS layered on a byte fifo, so slow
+// telemetry logs fit in a smaller static buffer at the cost of
+// some CPU. LzWriter compresses what is written into the fifo,
+// LzReader decompresses on the way out. Both keep the last W
+// bytes (a power of two, 16 to 4096) as the window; the two
+// ends must use the same W.
+//
+// The stream is msb first bits, a symbol at a time:
+//
+//   1 + 8 bits              literal byte
+//   0 + log2(W) + 4 bits    back reference: distance - 1,
+//                           length - 2 (2 to 16 bytes)
+//   0 + zeros + 1111        sync, then pad to a byte boundary
+//
+// The writer holds back up to 16 bytes to look for a match;
+// flush() pushes them out and ends with a sync, so everything
+// written so far can be read back. The window carries on over
+// a flush, the stream does not start over.
+//
+// Matches are found by a plain search of the window, slow but
+// without tables, in line with the rest of the crate.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::bytefifo::ByteFifo;
+
+// shortest / longest back reference.
+pub const MIN_MATCH : usize = 2;
+pub const MAX_MATCH : usize = 16;
+
+// length code of the sync symbol, the one no match uses.
+const SYNC_LEN : u32 = 0x0F;
+
+// bit accumulator, shared by both ends. msb first.
+struct Bits {
+    acc: u32,
+    n: u32,
+}
+
+impl Bits {
+
+    const fn new() -> Self {
+        Bits { acc: 0, n: 0 }
+    }
+
+    // keep only the n lowest bits of acc.
+    fn mask(&mut self) {
+        self.acc &= (1u32 << self.n).wrapping_sub(1);
+    }
+}
+
+pub struct LzWriter<const W : usize> {
+    window: [u8; W],
+    // next window slot to write
+    pos: usize,
+    // bytes of history in the window, up to W
+    filled: usize,
+    // bytes held back to match
+    ahead: [u8; MAX_MATCH],
+    ahead_len: usize,
+    bits: Bits,
+}
+
+impl<const W : usize> Default for LzWriter<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W : usize> LzWriter<W> {
+
+    const WINDOW_OK : () = assert!(W.is_power_of_two() && W >= 16 && W <= 4096, "LzWriter needs W a power of two, 16 to 4096");
+
+    const WINDOW_BITS : u32 = W.trailing_zeros();
+
+    pub const fn new() -> Self {
+        let () = Self::WINDOW_OK;
+        LzWriter { window: [0; W], pos: 0, filled: 0, ahead: [0; MAX_MATCH], ahead_len: 0, bits: Bits::new() }
+    }
+
+    // compress as much of data into fifo as there is room for, returns how many bytes were taken.
+    pub fn write<F : ByteFifo + ?Sized>(&mut self, fifo : &mut F, data : &[u8]) -> usize {
+        for (i, b) in data.iter().enumerate() {
+            if self.ahead_len == MAX_MATCH && !self.emit(fifo) {
+                return i;
+            }
+            self.ahead[self.ahead_len] = *b;
+            self.ahead_len += 1;
+        }
+        data.len()
+    }
+
+    // push out everything held back and end with a sync, so the reader gets all of it.
+    // Full if the fifo ran out of room; what did fit stays written, call again once there is room.
+    pub fn flush<F : ByteFifo + ?Sized>(&mut self, fifo : &mut F) -> Result<(), StaticFifoError> {
+        while self.ahead_len > 0 {
+            if !self.emit(fifo) {
+                return Err(StaticFifoError::Full);
+            }
+        }
+        let pad = (8 - (self.bits.n + 1 + Self::WINDOW_BITS + 4) % 8) % 8;
+        if !self.put_bits(fifo, SYNC_LEN << pad, 1 + Self::WINDOW_BITS + 4 + pad) {
+            return Err(StaticFifoError::Full);
+        }
+        Ok(())
+    }
+
+    // bytes taken but not yet in the fifo.
+    pub fn pending(&self) -> usize {
+        self.ahead_len
+    }
+
+    // byte j of a match at distance d, running on into the held back bytes.
+    fn at(&self, d : usize, j : usize) -> u8 {
+        if j < d {
+            self.window[(self.pos + W - d + j) % W]
+        } else {
+            self.ahead[j - d]
+        }
+    }
+
+    // longest match for the held back bytes, (distance, length).
+    fn find_match(&self) -> (usize, usize) {
+        let mut best = (0, 0);
+        for d in 1..=self.filled {
+            let len = (0..self.ahead_len).take_while(|j| self.at(d, *j) == self.ahead[*j]).count();
+            if len > best.1 {
+                best = (d, len);
+                if len == self.ahead_len {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    // code the next symbol, false (nothing done) if the fifo has no room for it.
+    fn emit<F : ByteFifo + ?Sized>(&mut self, fifo : &mut F) -> bool {
+        let (d, len) = self.find_match();
+        let (used, ok) = if len >= MIN_MATCH {
+            let sym = ((d as u32 - 1) << 4) | (len - MIN_MATCH) as u32;
+            (len, self.put_bits(fifo, sym, 1 + Self::WINDOW_BITS + 4))
+        } else {
+            (1, self.put_bits(fifo, 0x100 | self.ahead[0] as u32, 9))
+        };
+        if !ok {
+            return false;
+        }
+        for j in 0..used {
+            self.window[self.pos] = self.ahead[j];
+            self.pos = (self.pos + 1) % W;
+        }
+        self.filled = core::cmp::min(self.filled + used, W);
+        self.ahead.copy_within(used..self.ahead_len, 0);
+        self.ahead_len -= used;
+        true
+    }
+
+    // append count bits and move whole bytes into the fifo, all or nothing.
+    fn put_bits<F : ByteFifo + ?Sized>(&mut self, fifo : &mut F, v : u32, count : u32) -> bool {
+        if ((self.bits.n + count) / 8) as usize > fifo.free_space() {
+            return false;
+        }
+        self.bits.acc = (self.bits.acc << count) | v;
+        self.bits.n += count;
+        while self.bits.n >= 8 {
+            self.bits.n -= 8;
+            let _ = fifo.put_u8((self.bits.acc >> self.bits.n) as u8);
+        }
+        self.bits.mask();
+        true
+    }
+}
+
+pub struct LzReader<const W : usize> {
+    window: [u8; W],
+    pos: usize,
+    // back reference being copied out
+    dist: usize,
+    left: usize,
+    bits: Bits,
+}
+
+impl<const W : usize> Default for LzReader<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W : usize> LzReader<W> {
+
+    const WINDOW_OK : () = assert!(W.is_power_of_two() && W >= 16 && W <= 4096, "LzReader needs W a power of two, 16 to 4096");
+
+    const WINDOW_BITS : u32 = W.trailing_zeros();
+
+    pub const fn new() -> Self {
+        let () = Self::WINDOW_OK;
+        LzReader { window: [0; W], pos: 0, dist: 0, left: 0, bits: Bits::new() }
+    }
+
+    // decompress from fifo into out until out is full or the fifo runs dry, returns how many bytes were written.
+    // part of a symbol is kept back here until the rest of it arrives.
+    pub fn read<F : ByteFifo + ?Sized>(&mut self, fifo : &mut F, out : &mut [u8]) -> usize {
+        let mut n = 0;
+        while n < out.len() {
+            if self.left > 0 {
+                let b = self.window[(self.pos + W - self.dist) % W];
+                self.left -= 1;
+                out[n] = self.push(b);
+                n += 1;
+                continue;
+            }
+            if !self.fill(fifo, 1) {
+                break;
+            }
+            let literal = (self.bits.acc >> (self.bits.n - 1)) & 1 != 0;
+            let need = if literal { 9 } else { 1 + Self::WINDOW_BITS + 4 };
+            if !self.fill(fifo, need) {
+                break;
+            }
+            self.bits.n -= need;
+            let sym = self.bits.acc >> self.bits.n;
+            self.bits.mask();
+            if literal {
+                out[n] = self.push(sym as u8);
+                n += 1;
+            } else if sym & 0x0F == SYNC_LEN {
+                // the rest of the byte is padding
+                self.bits.n -= self.bits.n % 8;
+                self.bits.mask();
+            } else {
+                self.dist = ((sym >> 4) & ((1 << Self::WINDOW_BITS) - 1)) as usize + 1;
+                self.left = (sym & 0x0F) as usize + MIN_MATCH;
+            }
+        }
+        n
+    }
+
+    // pull bytes until count bits are buffered, false if the fifo runs dry first.
+    fn fill<F : ByteFifo + ?Sized>(&mut self, fifo : &mut F, count : u32) -> bool {
+        while self.bits.n < count {
+            match fifo.get_u8() {
+                Ok(b) => {
+                    self.bits.acc = (self.bits.acc << 8) | b as u32;
+                    self.bits.n += 8;
+                }
+                Err(_) => return false,
+            }
+        }
+        true
+    }
+
+    fn push(&mut self, b : u8) -> u8 {
+        self.window[self.pos] = b;
+        self.pos = (self.pos + 1) % W;
+        b
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use std::vec::Vec;
+    use super::*;
+    use crate::StaticFifoU8;
+
+    #[test]
+    fn lz_round_trip() {
+        println!("##################### LZ TEST ######################################");
+
+        // repetitive telemetry lines
+        let mut log = Vec::new();
+        for i in 0..40u32 {
+            log.extend_from_slice(b"T=");
+            log.push(b'0' + (i % 10) as u8);
+            log.extend_from_slice(b" batt=3.71V temp=21C ok\n");
+        }
+
+        let mut fifo : StaticFifoU8<512> = StaticFifoU8::new();
+        let mut tx : LzWriter<256> = LzWriter::new();
+        let mut rx : LzReader<256> = LzReader::new();
+
+        assert!(tx.write(&mut fifo, &log) == log.len(), "log did not fit");
+        tx.flush(&mut fifo).unwrap();
+        assert!(tx.pending() == 0 && fifo.len() < log.len() / 3, "log barely compressed");
+
+        let mut out = [0u8; 2048];
+        let n = rx.read(&mut fifo, &mut out);
+        assert!(out[..n] == log[..], "decompressed log wrong");
+
+        // the window carries on over the flush, read in small pieces
+        assert!(tx.write(&mut fifo, b"T=7 batt=3.71V temp=21C ok\n") == 27, "second write short");
+        tx.flush(&mut fifo).unwrap();
+        let mut n = 0;
+        while n < 27 {
+            let got = rx.read(&mut fifo, &mut out[n..(n + 5).min(27)]);
+            assert!(got > 0, "reader stalled");
+            n += got;
+        }
+        assert!(&out[..27] == b"T=7 batt=3.71V temp=21C ok\n", "second block wrong");
+        // only the sync is left, it yields nothing
+        assert!(rx.read(&mut fifo, &mut out) == 0 && fifo.is_empty(), "sync not consumed");
+    }
+
+    #[test]
+    fn lz_full_fifo() {
+        println!("##################### LZ FULL TEST ######################################");
+
+        // incompressible data into a small fifo: write stops, nothing is lost
+        let data : Vec<u8> = (0..200u32).map(|i| (i.wrapping_mul(167) ^ (i >> 3)) as u8).collect();
+        let mut fifo : StaticFifoU8<64> = StaticFifoU8::new();
+        let mut tx : LzWriter<64> = LzWriter::new();
+        let mut rx : LzReader<64> = LzReader::new();
+        let mut out = Vec::new();
+        let mut buf = [0u8; 32];
+        let mut taken = 0;
+        while taken < data.len() {
+            taken += tx.write(&mut fifo, &data[taken..]);
+            let n = rx.read(&mut fifo, &mut buf);
+            out.extend_from_slice(&buf[..n]);
+        }
+        while tx.flush(&mut fifo).is_err() {
+            let n = rx.read(&mut fifo, &mut buf);
+            out.extend_from_slice(&buf[..n]);
+        }
+        loop {
+            let n = rx.read(&mut fifo, &mut buf);
+            if n == 0 {
+                break;
+            }
+            out.extend_from_slice(&buf[..n]);
+        }
+        assert!(out == data, "data lost through a full fifo");
+    }
+}
@@ -0,0 +1,429 @@
+////////////////////////////////////////////////////////////////
+// Inter-core mailbox
+//
+// Single producer / single consumer fifo meant to live in a
+// memory region both cores can reach (e.g. RP2040 core0 feeds
+// core1, or an M4 / M0+ pair on a dual core part). Each index
+// is owned by one side and only ever loaded / stored, never
+// read-modify-written, so plain atomic loads and stores do the
+// job; that works on cores without CAS (ARMv6-M) too. Element
+// slots are accessed volatile.
+//
+// Placement: either a static (`#[link_section = ".shared"]`)
+// built with the const new(), or init_in_place() on memory at
+// a fixed address handed out by the linker script / the other
+// core.
+//
+// Barrier hooks: before an index is published and after the
+// other side's index is read, a Barrier runs. The default
+// Fence is a release / acquire fence, enough for coherent
+// SRAM. Parts with caches or write buffers between the cores
+// plug in their own (DMB / DSB, cache clean / invalidate).
+//
+// Like the other fifos, a Mailbox<T, N> holds N-1 elements.
+//
+// The mailbox is Sync and the producer / consumer handles are
+// Send once T is, so either side can move to the other core or
+// an interrupt context. Neither handle is Clone, so each side
+// keeps exactly one owner (see sendsync.rs for the checks).
+//
+// Statistics: the producer counts puts, overflows and the high
+// watermark under a sequence counter (a seqlock with only the
+// producer writing), the consumer counts gets. stats_snapshot()
+// retries until it read the producer side between two updates,
+// so a snapshot is coherent even with the producer running on
+// the other core. stats_reset() does not touch the sides'
+// counters, it records the current values as the new zero
+// (and asks the producer to restart the watermark), so it is
+// safe alongside both. Snapshot / reset from one context at a
+// time.
+//
+///////////////////////////////////////////////////////////////
+
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use crate::atomic::{AtomicU32, AtomicUsize, Ordering, fence};
+
+use crate::StaticFifoError;
+
+// memory ordering hooks around the index handoff.
+pub trait Barrier {
+    // runs after the slots are written / read and before the index that hands
+    // them to the other core is stored.
+    fn release();
+    // runs after the other core's index is loaded and before the slots it covers
+    // are touched.
+    fn acquire();
+}
+
+// release / acquire fences, for SRAM both cores see coherently.
+pub struct Fence;
+
+impl Barrier for Fence {
+    #[inline]
+    fn release() {
+        fence(Ordering::Release);
+    }
+
+    #[inline]
+    fn acquire() {
+        fence(Ordering::Acquire);
+    }
+}
+
+// repr(C) so both cores agree on the layout even when built separately.
+#[repr(C)]
+pub struct Mailbox<T, const N : usize, B = Fence> {
+    // written by the consumer only
+    read_ptr: AtomicUsize,
+    // written by the producer only
+    write_ptr: AtomicUsize,
+    buf: [UnsafeCell<MaybeUninit<T>>; N],
+    // statistics, see the top of the file
+    stats: Stats,
+    _b: PhantomData<B>,
+}
+
+// counters as seen since the last stats_reset().
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MailboxStats {
+    pub puts: u32,
+    pub gets: u32,
+    // puts refused because the mailbox was full
+    pub overflows: u32,
+    pub high_water: usize,
+}
+
+#[repr(C)]
+struct Stats {
+    // written by the producer only, odd while it updates the fields below
+    seq: AtomicU32,
+    puts: AtomicU32,
+    overflows: AtomicU32,
+    high: AtomicUsize,
+    // the hw_gen the watermark was last restarted for
+    hw_seen: AtomicU32,
+    // written by the consumer only
+    gets: AtomicU32,
+    // written by stats_reset() only
+    base_puts: AtomicU32,
+    base_gets: AtomicU32,
+    base_overflows: AtomicU32,
+    hw_gen: AtomicU32,
+}
+
+impl Stats {
+    const fn new() -> Self {
+        Stats {
+            seq: AtomicU32::new(0), puts: AtomicU32::new(0), overflows: AtomicU32::new(0),
+            high: AtomicUsize::new(0), hw_seen: AtomicU32::new(0), gets: AtomicU32::new(0),
+            base_puts: AtomicU32::new(0), base_gets: AtomicU32::new(0), base_overflows: AtomicU32::new(0),
+            hw_gen: AtomicU32::new(0),
+        }
+    }
+
+    // producer side: one coherent update of its counters.
+    fn record_put(&self, ok : bool, len : usize) {
+        let seq = self.seq.load(Ordering::Relaxed);
+        self.seq.store(seq.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+        if ok {
+            self.puts.store(self.puts.load(Ordering::Relaxed).wrapping_add(1), Ordering::Relaxed);
+        } else {
+            self.overflows.store(self.overflows.load(Ordering::Relaxed).wrapping_add(1), Ordering::Relaxed);
+        }
+        let gen = self.hw_gen.load(Ordering::Relaxed);
+        if gen != self.hw_seen.load(Ordering::Relaxed) {
+            self.high.store(len, Ordering::Relaxed);
+            self.hw_seen.store(gen, Ordering::Relaxed);
+        } else if len > self.high.load(Ordering::Relaxed) {
+            self.high.store(len, Ordering::Relaxed);
+        }
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+    }
+
+    // raw counters (puts, gets, overflows, high, hw_seen), producer side read
+    // between two of its updates.
+    fn read(&self) -> (u32, u32, u32, usize, u32) {
+        loop {
+            // gets first, so the puts read after it are never behind it
+            let gets = self.gets.load(Ordering::Acquire);
+            let seq = self.seq.load(Ordering::Acquire);
+            if seq & 1 == 1 {
+                core::hint::spin_loop();
+                continue;
+            }
+            let puts = self.puts.load(Ordering::Relaxed);
+            let overflows = self.overflows.load(Ordering::Relaxed);
+            let high = self.high.load(Ordering::Relaxed);
+            let hw_seen = self.hw_seen.load(Ordering::Relaxed);
+            fence(Ordering::Acquire);
+            if self.seq.load(Ordering::Relaxed) == seq {
+                return (puts, gets, overflows, high, hw_seen);
+            }
+        }
+    }
+}
+
+// the slots between the indices belong to exactly one side at a time, handed
+// over through the indices, so the mailbox can be shared once T can move.
+unsafe impl<T : Send, const N : usize, B> Sync for Mailbox<T, N, B> {}
+
+impl<T : Copy, const N : usize, B : Barrier> Default for Mailbox<T, N, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the safety rules of the unsafe fns are in the comments above them.
+#[allow(clippy::missing_safety_doc)]
+impl<T : Copy, const N : usize, B : Barrier> Mailbox<T, N, B> {
+
+    const CAPACITY_OK : () = assert!(N >= 2, "Mailbox needs N >= 2, one slot is lost to the full marker");
+
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY_OK;
+        Mailbox {
+            read_ptr: AtomicUsize::new(0),
+            write_ptr: AtomicUsize::new(0),
+            buf: [const { UnsafeCell::new(MaybeUninit::uninit()) }; N],
+            stats: Stats::new(),
+            _b: PhantomData,
+        }
+    }
+
+    // set up an empty mailbox in the memory at slot, without building one on the
+    // stack first. only the indices are written, the slots stay as they are.
+    //
+    // safety: slot must be valid for writes, aligned for Self and not used by
+    // anything else for 'a. call once, from one core, before the other core
+    // touches the mailbox.
+    pub unsafe fn init_in_place<'a>(slot : *mut Self) -> &'a Self {
+        let () = Self::CAPACITY_OK;
+        core::ptr::addr_of_mut!((*slot).read_ptr).write(AtomicUsize::new(0));
+        core::ptr::addr_of_mut!((*slot).write_ptr).write(AtomicUsize::new(0));
+        core::ptr::addr_of_mut!((*slot).stats).write(Stats::new());
+        fence(Ordering::SeqCst);
+        &*slot
+    }
+
+    // elements the mailbox can hold, one less than N.
+    pub const fn capacity(&self) -> usize {
+        N - 1
+    }
+
+    // snapshot, may already be stale when it returns.
+    pub fn len(&self) -> usize {
+        let w = self.write_ptr.load(Ordering::Acquire);
+        let r = self.read_ptr.load(Ordering::Acquire);
+        if r > w { N - r + w } else { w - r }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // all counters from one moment, safe while both sides run.
+    pub fn stats_snapshot(&self) -> MailboxStats {
+        let st = &self.stats;
+        let (puts, gets, overflows, high, hw_seen) = st.read();
+        // a restart the producer has not picked up yet, the fill level is the watermark
+        let high_water = if hw_seen == st.hw_gen.load(Ordering::Relaxed) { high } else { self.len() };
+        MailboxStats {
+            puts: puts.wrapping_sub(st.base_puts.load(Ordering::Relaxed)),
+            gets: gets.wrapping_sub(st.base_gets.load(Ordering::Relaxed)),
+            overflows: overflows.wrapping_sub(st.base_overflows.load(Ordering::Relaxed)),
+            high_water,
+        }
+    }
+
+    // start counting from zero again, safe while both sides run.
+    pub fn stats_reset(&self) {
+        let st = &self.stats;
+        let (puts, gets, overflows, _, _) = st.read();
+        st.base_puts.store(puts, Ordering::Relaxed);
+        st.base_gets.store(gets, Ordering::Relaxed);
+        st.base_overflows.store(overflows, Ordering::Relaxed);
+        st.hw_gen.store(st.hw_gen.load(Ordering::Relaxed).wrapping_add(1), Ordering::Relaxed);
+    }
+
+    // the write side. safety: at most one producer may exist at a time, across
+    // both cores.
+    pub unsafe fn producer(&self) -> MailboxProducer<'_, T, N, B> {
+        MailboxProducer { mb: self }
+    }
+
+    // the read side. safety: at most one consumer may exist at a time, across
+    // both cores.
+    pub unsafe fn consumer(&self) -> MailboxConsumer<'_, T, N, B> {
+        MailboxConsumer { mb: self }
+    }
+
+    // both sides, for when the mailbox is exclusively borrowed (tests, one core).
+    pub fn split(&mut self) -> (MailboxProducer<'_, T, N, B>, MailboxConsumer<'_, T, N, B>) {
+        let mb : &Self = self;
+        (MailboxProducer { mb }, MailboxConsumer { mb })
+    }
+
+    #[inline]
+    fn next(p : usize) -> usize {
+        if p + 1 >= N { 0 } else { p + 1 }
+    }
+}
+
+pub struct MailboxProducer<'a, T, const N : usize, B> {
+    mb: &'a Mailbox<T, N, B>,
+}
+
+pub struct MailboxConsumer<'a, T, const N : usize, B> {
+    mb: &'a Mailbox<T, N, B>,
+}
+
+impl<'a, T : Copy, const N : usize, B : Barrier> MailboxProducer<'a, T, N, B> {
+
+    pub fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        let w = self.mb.write_ptr.load(Ordering::Relaxed);
+        let next = Mailbox::<T, N, B>::next(w);
+        let r = self.mb.read_ptr.load(Ordering::Acquire);
+        if next == r {
+            self.mb.stats.record_put(false, N - 1);
+            return Err(StaticFifoError::Full);
+        }
+        // the consumer is done with this slot, it moved read_ptr past it.
+        B::acquire();
+        let slot = self.mb.buf.get(w).ok_or(StaticFifoError::Corrupt)?;
+        unsafe { core::ptr::write_volatile(slot.get(), MaybeUninit::new(data)) };
+        B::release();
+        self.mb.write_ptr.store(next, Ordering::Release);
+        // fill level as of this put, the consumer can only have made it smaller
+        self.mb.stats.record_put(true, if r > next { N - r + next } else { next - r });
+        Ok(())
+    }
+
+    pub fn free_space(&self) -> usize {
+        (N - 1).saturating_sub(self.mb.len())
+    }
+}
+
+impl<'a, T : Copy, const N : usize, B : Barrier> MailboxConsumer<'a, T, N, B> {
+
+    pub fn get(&mut self) -> Result<T, StaticFifoError> {
+        let r = self.mb.read_ptr.load(Ordering::Relaxed);
+        if r == self.mb.write_ptr.load(Ordering::Acquire) {
+            return Err(StaticFifoError::Empty);
+        }
+        B::acquire();
+        let slot = self.mb.buf.get(r).ok_or(StaticFifoError::Corrupt)?;
+        // written by the producer before it published write_ptr past it.
+        let rv = unsafe { core::ptr::read_volatile(slot.get()).assume_init() };
+        B::release();
+        self.mb.read_ptr.store(Mailbox::<T, N, B>::next(r), Ordering::Release);
+        let gets = &self.mb.stats.gets;
+        gets.store(gets.load(Ordering::Relaxed).wrapping_add(1), Ordering::Release);
+        Ok(rv)
+    }
+
+    pub fn len(&self) -> usize {
+        self.mb.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mb.is_empty()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use std::thread;
+    use super::*;
+
+    // stands in for the shared SRAM region
+    static SHARED : Mailbox<u32, 8> = Mailbox::new();
+
+    #[test]
+    fn cross_core_mailbox() {
+        println!("##################### MAILBOX TEST ######################################");
+
+        // a thread each for "core0" and "core1"
+        let core0 = thread::spawn(|| {
+            let mut tx = unsafe { SHARED.producer() };
+            for i in 0..1000u32 {
+                while tx.put(i).is_err() {
+                    thread::yield_now();
+                }
+            }
+        });
+        let mut rx = unsafe { SHARED.consumer() };
+        for i in 0..1000u32 {
+            let v = loop {
+                if let Ok(v) = rx.get() {
+                    break v;
+                }
+                thread::yield_now();
+            };
+            assert!(v == i, "order wrong");
+        }
+        core0.join().unwrap();
+        assert!(SHARED.is_empty(), "mailbox not empty");
+        let st = SHARED.stats_snapshot();
+        assert!(st.puts == 1000 && st.gets == 1000 && st.high_water <= 7, "stats wrong");
+
+        // placement on raw memory
+        let mut region = MaybeUninit::<Mailbox<u16, 4>>::uninit();
+        let mb = unsafe { Mailbox::init_in_place(region.as_mut_ptr()) };
+        let (mut tx, mut rx) = (unsafe { mb.producer() }, unsafe { mb.consumer() });
+        assert!(mb.capacity() == 3 && mb.is_empty(), "placed mailbox not empty");
+        for i in 0..3 {
+            tx.put(i).unwrap();
+        }
+        assert!(tx.put(3) == Err(StaticFifoError::Full) && tx.free_space() == 0, "full mailbox took data");
+        assert!(rx.get() == Ok(0) && rx.len() == 2, "placed mailbox get wrong");
+    }
+
+    static WATCHED : Mailbox<u32, 8> = Mailbox::new();
+
+    #[test]
+    fn stats_alongside_producer() {
+        println!("##################### MAILBOX STATS TEST ######################################");
+
+        let core0 = thread::spawn(|| {
+            let mut tx = unsafe { WATCHED.producer() };
+            for i in 0..2000u32 {
+                while tx.put(i).is_err() {
+                    thread::yield_now();
+                }
+            }
+        });
+        let mut rx = unsafe { WATCHED.consumer() };
+        let (mut got, mut resets) = (0, 0);
+        while got < 2000 {
+            if rx.get().is_ok() {
+                got += 1;
+            }
+            // telemetry: a snapshot never sees more taken out than put in, plus
+            // what was already queued at the last reset
+            let st = WATCHED.stats_snapshot();
+            let queued_at_reset = if resets == 0 { 0 } else { 7 };
+            assert!(st.gets <= st.puts + queued_at_reset && st.high_water <= 7, "incoherent snapshot");
+            if got % 500 == 0 && resets < got / 500 {
+                WATCHED.stats_reset();
+                resets += 1;
+            }
+            thread::yield_now();
+        }
+        core0.join().unwrap();
+        WATCHED.stats_reset();
+        assert!(WATCHED.stats_snapshot() == MailboxStats::default(), "reset did not zero");
+
+        let mut tx = unsafe { WATCHED.producer() };
+        for i in 0..8 {
+            let _ = tx.put(i);
+        }
+        let st = WATCHED.stats_snapshot();
+        assert!(st.puts == 7 && st.overflows == 1 && st.high_water == 7, "counts after reset wrong");
+    }
+}
@@ -0,0 +1,94 @@
+////////////////////////////////////////////////////////////////
+// Sync marker search
+//
+// Locates a sync word (e.g. the CCSDS attached sync marker)
+// anywhere in the queued bytes of a StaticFifoU8 and lines the
+// read side up on it, so the frame that follows can be pulled
+// out with plain get() calls.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoError};
+
+// CCSDS attached sync marker for telemetry transfer frames.
+pub const CCSDS_ASM : [u8; 4] = [0x1A, 0xCF, 0xFC, 0x1D];
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // true if marker sits at offset i from the read side.
+    fn marker_at(&self, i : usize, marker : &[u8]) -> bool {
+        marker.iter().enumerate().all(|(k, m)| self.peek_at(i + k) == Ok(*m))
+    }
+
+    // offset from the read side of the first complete copy of marker, fifo is not modified.
+    pub fn find_sync(&self, marker : &[u8]) -> Option<usize> {
+        if marker.len() > self.len() {
+            return None;
+        }
+        (0..=(self.len() - marker.len())).find(|&i| self.marker_at(i, marker))
+    }
+
+    // discard everything in front of the first marker so it sits at the read side.
+    // returns how many bytes were dropped to get aligned (0 means we were already in sync).
+    // Empty if no complete marker is queued yet, bytes that can not be the start of
+    // a marker are dropped anyway so the search does not fill the fifo with noise.
+    pub fn resync_to_marker(&mut self, marker : &[u8]) -> Result<usize, StaticFifoError> {
+        match self.find_sync(marker) {
+            Some(i) => {
+                self.skip(i);
+                Ok(i)
+            },
+            None => {
+                // keep a tail that could still grow into a marker.
+                let keep = core::cmp::min(self.len(), marker.len().saturating_sub(1));
+                let len = self.len();
+                let drop = ((len - keep)..len)
+                    .find(|&p| self.marker_at(p, &marker[..(len - p)]))
+                    .unwrap_or(len);
+                self.skip(drop);
+                Err(StaticFifoError::Empty)
+            },
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn find_and_resync() {
+        println!("##################### SYNC MARKER TEST ######################################");
+
+        let mut fifo = StaticFifoU8::<16> { buf: [0;16], read_ptr: 0, write_ptr: 0, capacity: 16, soft_cap: usize::MAX };
+
+        // push the write pointer near the end so the marker straddles the wrap
+        for _ in 0..12 {
+            fifo.put(0).unwrap();
+        }
+        fifo.skip(12);
+        for b in [0x55, 0x1A, 0x1A, 0xCF, 0xFC, 0x1D, 0x01, 0x02] {
+            fifo.put(b).unwrap();
+        }
+        assert!(fifo.find_sync(&CCSDS_ASM) == Some(2), "marker not found across the wrap");
+        assert!(fifo.len() == 8, "find_sync modified the fifo");
+        assert!(fifo.resync_to_marker(&CCSDS_ASM) == Ok(2), "resync reported wrong slip");
+        assert!(fifo.resync_to_marker(&CCSDS_ASM) == Ok(0), "already aligned fifo reported slip");
+        fifo.skip(CCSDS_ASM.len());
+        assert!(fifo.get() == Ok(0x01), "frame data after marker wrong");
+
+        // no marker yet, noise is dropped but a possible marker prefix is kept
+        fifo.skip(fifo.len());
+        for b in [0x00, 0x11, 0x22, 0x1A, 0xCF] {
+            fifo.put(b).unwrap();
+        }
+        assert!(fifo.resync_to_marker(&CCSDS_ASM) == Err(StaticFifoError::Empty), "marker found in noise");
+        assert!(fifo.len() == 2, "marker prefix not kept");
+        fifo.put(0xFC).unwrap();
+        fifo.put(0x1D).unwrap();
+        assert!(fifo.resync_to_marker(&CCSDS_ASM) == Ok(0), "marker completed later not found");
+    }
+}
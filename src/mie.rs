@@ -0,0 +1,67 @@
+////////////////////////////////////////////////////////////////
+// RISC-V interrupt masked fifo
+//
+// The RISC-V counterpart of BasepriFifo: shares a fifo between
+// main code and trap handlers by clearing mstatus.MIE around
+// each access (riscv::interrupt::free), for single hart parts
+// like the ESP32-C3 or GD32VF103. MIE is restored to what it
+// was, so a put / get from inside a handler leaves interrupts
+// off.
+//
+// There is no priority ceiling on RISC-V's global enable, so
+// unlike BASEPRI every interrupt waits while the fifo is
+// locked. Only protects against the local hart, a second hart
+// needs a real lock.
+//
+///////////////////////////////////////////////////////////////
+
+use core::cell::{Cell, UnsafeCell};
+
+use crate::StaticFifoError;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+pub struct MieFifo<F> {
+    fifo: UnsafeCell<F>,
+    // set while a lock() closure runs, catches a nested lock() on the same fifo.
+    busy: Cell<bool>,
+}
+
+// every access goes through lock(), which runs with interrupts off on the only
+// hart, so only one context is ever inside.
+unsafe impl<F : Send> Sync for MieFifo<F> {}
+
+impl<F> MieFifo<F> {
+
+    // const so it can sit in a static shared with the trap handlers.
+    pub const fn new(fifo : F) -> Self {
+        MieFifo { fifo: UnsafeCell::new(fifo), busy: Cell::new(false) }
+    }
+
+    // run f on the fifo with mstatus.MIE cleared. panics if f locks the same fifo again.
+    pub fn lock<R>(&self, f : impl FnOnce(&mut F) -> R) -> R {
+        riscv::interrupt::free(|| {
+            assert!(!self.busy.replace(true), "nested lock on a MieFifo");
+            // interrupts off, and busy rules out a second &mut from this context.
+            let rv = f(unsafe { &mut *self.fifo.get() });
+            self.busy.set(false);
+            rv
+        })
+    }
+
+    pub fn put<T>(&self, data : T) -> Result<(), StaticFifoError> where F : FifoProducer<T> {
+        self.lock(|fifo| fifo.put(data))
+    }
+
+    pub fn get<T>(&self) -> Result<T, StaticFifoError> where F : FifoConsumer<T> {
+        self.lock(|fifo| fifo.get())
+    }
+
+    // the fifo itself, no locking needed with exclusive access.
+    pub fn get_mut(&mut self) -> &mut F {
+        self.fifo.get_mut()
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo.into_inner()
+    }
+}
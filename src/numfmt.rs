@@ -0,0 +1,109 @@
+////////////////////////////////////////////////////////////////
+// Number to text
+//
+// Formats integers as ASCII straight into the byte fifo, for
+// simple telemetry strings without the code size of core::fmt.
+// Each number goes in whole or not at all.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoError};
+
+const HEX_DIGITS : &[u8; 16] = b"0123456789abcdef";
+
+// write v in decimal to the end of buf, returns where the digits start.
+// buf has one byte spare in front of the longest number, for a sign.
+fn dec_digits(v : u32, buf : &mut [u8; 11]) -> usize {
+    let mut i = buf.len();
+    let mut v = v;
+    loop {
+        i -= 1;
+        buf[i] = b'0' + (v % 10) as u8;
+        v /= 10;
+        if v == 0 {
+            return i;
+        }
+    }
+}
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // v in decimal, no padding.
+    pub fn put_u32_dec(&mut self, v : u32) -> Result<(), StaticFifoError> {
+        let mut buf = [0u8; 11];
+        let i = dec_digits(v, &mut buf);
+        self.put_all(&buf[i..])
+    }
+
+    // v in decimal with a leading '-' when negative.
+    pub fn put_i32_dec(&mut self, v : i32) -> Result<(), StaticFifoError> {
+        // sign and digits go in together.
+        let mut buf = [0u8; 11];
+        let mut i = dec_digits(v.unsigned_abs(), &mut buf);
+        if v < 0 {
+            i -= 1;
+            buf[i] = b'-';
+        }
+        self.put_all(&buf[i..])
+    }
+
+    // v in lower case hex, no prefix or padding.
+    pub fn put_u32_hex(&mut self, v : u32) -> Result<(), StaticFifoError> {
+        let mut buf = [0u8; 8];
+        let mut i = buf.len();
+        let mut v = v;
+        loop {
+            i -= 1;
+            buf[i] = HEX_DIGITS[(v & 0xF) as usize];
+            v >>= 4;
+            if v == 0 {
+                break;
+            }
+        }
+        self.put_all(&buf[i..])
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    fn text<const N : usize>(fifo : &mut StaticFifoU8<N>) -> ([u8; 32], usize) {
+        let mut out = [0u8; 32];
+        let n = fifo.copy_to_slice(&mut out);
+        fifo.skip(n);
+        (out, n)
+    }
+
+    #[test]
+    fn numbers_to_text() {
+        println!("##################### NUMBER TEXT TEST ######################################");
+
+        let mut fifo : StaticFifoU8<64> = StaticFifoU8::new();
+
+        fifo.put_u32_dec(0).unwrap();
+        fifo.put(b' ').unwrap();
+        fifo.put_u32_dec(u32::MAX).unwrap();
+        fifo.put(b' ').unwrap();
+        fifo.put_i32_dec(i32::MIN).unwrap();
+        fifo.put(b' ').unwrap();
+        fifo.put_i32_dec(-5).unwrap();
+        let (out, n) = text(&mut fifo);
+        assert!(&out[..n] == b"0 4294967295 -2147483648 -5", "decimal text wrong");
+
+        fifo.put_u32_hex(0).unwrap();
+        fifo.put(b' ').unwrap();
+        fifo.put_u32_hex(0xDEAD_BEEF).unwrap();
+        let (out, n) = text(&mut fifo);
+        assert!(&out[..n] == b"0 deadbeef", "hex text wrong");
+
+        // all or nothing, the sign does not go in on its own
+        let mut small : StaticFifoU8<4> = StaticFifoU8::new();
+        assert!(small.put_i32_dec(-100) == Err(StaticFifoError::Full) && small.is_empty(), "partial number written");
+        assert!(small.put_u32_dec(1234) == Err(StaticFifoError::Full) && small.is_empty(), "partial number written");
+        assert!(small.put_i32_dec(-10) == Ok(()) && small.len() == 3, "fitting number refused");
+    }
+}
@@ -0,0 +1,118 @@
+////////////////////////////////////////////////////////////////
+// Overwrite oldest
+//
+// Lossy mode for data where the newest matters most (sensor
+// streams, log rings): a put into a full fifo evicts the
+// oldest element to make room instead of failing. Evictions
+// are counted, and pop_with_loss() hands the count since the
+// last pop back with the element, so the consumer knows data
+// went missing right before it and how much.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+pub struct Overwrite<F> {
+    fifo: F,
+    // evicted since the last pop
+    lost: u32,
+    // evicted overall
+    total_lost: u32,
+}
+
+impl<F> Overwrite<F> {
+
+    pub fn new(fifo : F) -> Self {
+        Overwrite { fifo, lost: 0, total_lost: 0 }
+    }
+
+    // evicted since the last pop.
+    pub fn lost(&self) -> u32 {
+        self.lost
+    }
+
+    // evicted since new().
+    pub fn total_lost(&self) -> u32 {
+        self.total_lost
+    }
+
+    // next element plus how many were evicted since the previous pop.
+    pub fn pop_with_loss<T>(&mut self) -> Result<(T, u32), StaticFifoError> where F : FifoConsumer<T> {
+        let rv = self.fifo.get()?;
+        Ok((rv, core::mem::take(&mut self.lost)))
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.fifo
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo
+    }
+}
+
+// never Full, the oldest element goes instead. other errors from the fifo come through.
+impl<T, F : FifoProducer<T> + FifoConsumer<T>> FifoProducer<T> for Overwrite<F> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        if self.fifo.is_full() {
+            self.fifo.get()?;
+            self.lost = self.lost.wrapping_add(1);
+            self.total_lost = self.total_lost.wrapping_add(1);
+        }
+        self.fifo.put(data)
+    }
+
+    // room left before puts start evicting.
+    fn free_space(&self) -> usize {
+        self.fifo.free_space()
+    }
+
+    fn is_full(&self) -> bool {
+        false
+    }
+}
+
+// plain get(), the loss count keeps adding up until pop_with_loss() collects it.
+impl<T, F : FifoConsumer<T>> FifoConsumer<T> for Overwrite<F> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        self.fifo.get()
+    }
+
+    fn len(&self) -> usize {
+        self.fifo.len()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+    use crate::StaticFifoU32;
+
+    #[test]
+    fn evictions_reported() {
+        println!("##################### OVERWRITE TEST ######################################");
+
+        // 3 slots, 5 samples: the two oldest go
+        let mut ring = Overwrite::new(StaticFifoU32::<4>::new());
+        for v in 1..=5 {
+            ring.put(v).unwrap();
+        }
+        assert!(ring.lost() == 2 && ring.len() == 3, "evictions not counted");
+        assert!(ring.pop_with_loss() == Ok((3, 2)), "loss not reported with the pop");
+        assert!(ring.pop_with_loss() == Ok((4, 0)), "loss reported twice");
+
+        // 5 is still queued, 8 pushes it out
+        for v in 6..=8 {
+            ring.put(v).unwrap();
+        }
+        assert!(ring.pop_with_loss() == Ok((6, 1)) && ring.total_lost() == 3, "second eviction wrong");
+        assert!(ring.get() == Ok(7) && ring.get() == Ok(8), "drain wrong");
+        assert!(ring.pop_with_loss::<u32>() == Err(StaticFifoError::Empty), "empty pop wrong");
+    }
+}
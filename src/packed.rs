@@ -0,0 +1,322 @@
+////////////////////////////////////////////////////////////////
+// Bit packed fifo
+//
+// For elements that only need a few bits (bool, fieldless
+// enums with a handful of variants): PackedFifo keeps each
+// one in T::BITS bits of a byte array instead of a whole slot,
+// so a fifo of 1000 bools takes 125 bytes, not 1000 (or 4000
+// in a u32 fifo).
+//
+// T says how through the Packable trait. BITS has to be 1, 2,
+// 4 or 8 so an element never straddles two bytes; this is
+// checked at build time. For an enum:
+//
+//   impl Packable for Led {
+//       const BITS : u32 = 2;
+//       fn pack(self) -> u8 { self as u8 }
+//       fn unpack(bits : u8) -> Self { match bits { 0 => Led::Off, 1 => Led::On, _ => Led::Blink } }
+//   }
+//
+// BYTES is the storage in bytes. Like the other fifos one
+// slot is the full marker, so it holds BYTES * 8 / BITS - 1.
+//
+// NibbleFifo is the 4 bit case for low resolution ADC / DAC
+// streams: two samples per byte, put_nibble() / get_nibble()
+// take and hand back the sample as the low 4 bits of a u8.
+// BoolFifo is the 1 bit case, a flag per bit for event / edge
+// queues. Both are sized in bytes like PackedFifo, not in
+// elements like StaticFifoU8<N>.
+//
+///////////////////////////////////////////////////////////////
+
+use core::marker::PhantomData;
+
+use crate::StaticFifoError;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+// elements that fit in a few bits.
+pub trait Packable : Copy {
+    // bits per element, 1, 2, 4 or 8.
+    const BITS : u32;
+    // the element as its low BITS bits.
+    fn pack(self) -> u8;
+    // back from the low BITS bits, other bits are zero.
+    fn unpack(bits : u8) -> Self;
+}
+
+impl Packable for bool {
+    const BITS : u32 = 1;
+
+    fn pack(self) -> u8 {
+        u8::from(self)
+    }
+
+    fn unpack(bits : u8) -> Self {
+        bits != 0
+    }
+}
+
+// a 4 bit sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Nibble(u8);
+
+impl Nibble {
+
+    // None if v does not fit in 4 bits.
+    pub const fn new(v : u8) -> Option<Self> {
+        if v <= 0x0F { Some(Nibble(v)) } else { None }
+    }
+
+    // keeps the low 4 bits of v.
+    pub const fn from_low_bits(v : u8) -> Self {
+        Nibble(v & 0x0F)
+    }
+
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+}
+
+impl Packable for Nibble {
+    const BITS : u32 = 4;
+
+    fn pack(self) -> u8 {
+        self.0
+    }
+
+    fn unpack(bits : u8) -> Self {
+        Nibble(bits)
+    }
+}
+
+// two 4 bit samples per byte of storage, holds BYTES * 2 - 1.
+pub type NibbleFifo<const BYTES : usize> = PackedFifo<Nibble, BYTES>;
+
+// one flag per bit of storage, holds BYTES * 8 - 1. BYTES is storage, not flags:
+// BoolFifo<16> holds 127 flags where StaticFifoU8<16> holds 15 bytes.
+pub type BoolFifo<const BYTES : usize> = PackedFifo<bool, BYTES>;
+
+pub struct PackedFifo<T, const BYTES : usize> {
+    buf: [u8; BYTES],
+    read_ptr: usize,
+    write_ptr: usize,
+    _t: PhantomData<T>,
+}
+
+impl<T : Packable, const BYTES : usize> Default for PackedFifo<T, BYTES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T : Packable, const BYTES : usize> PackedFifo<T, BYTES> {
+
+    const LAYOUT_OK : () = assert!(matches!(T::BITS, 1 | 2 | 4 | 8) && BYTES >= 1, "PackedFifo needs BITS of 1, 2, 4 or 8 and BYTES >= 1");
+
+    // element slots in the storage.
+    const SLOTS : usize = BYTES * 8 / T::BITS as usize;
+
+    pub const fn new() -> Self {
+        let () = Self::LAYOUT_OK;
+        PackedFifo { buf: [0; BYTES], read_ptr: 0, write_ptr: 0, _t: PhantomData }
+    }
+
+    pub fn init(&mut self) {
+        self.read_ptr = 0;
+        self.write_ptr = 0;
+    }
+
+    // elements the fifo can hold.
+    pub const fn capacity(&self) -> usize {
+        Self::SLOTS - 1
+    }
+
+    pub fn len(&self) -> usize {
+        if self.read_ptr > self.write_ptr {
+            Self::SLOTS - self.read_ptr + self.write_ptr
+        } else {
+            self.write_ptr - self.read_ptr
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read_ptr == self.write_ptr
+    }
+
+    pub fn is_full(&self) -> bool {
+        Self::next(self.write_ptr) == self.read_ptr
+    }
+
+    pub fn free_space(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    pub fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        if self.is_full() {
+            return Err(StaticFifoError::Full);
+        }
+        let (byte, shift, mask) = Self::locate(self.write_ptr);
+        self.buf[byte] = (self.buf[byte] & !(mask << shift)) | ((data.pack() & mask) << shift);
+        self.write_ptr = Self::next(self.write_ptr);
+        Ok(())
+    }
+
+    pub fn get(&mut self) -> Result<T, StaticFifoError> {
+        let rv = self.peek_at(0)?;
+        self.read_ptr = Self::next(self.read_ptr);
+        Ok(rv)
+    }
+
+    // look at the element i places from the read side without removing it.
+    pub fn peek_at(&self, i : usize) -> Result<T, StaticFifoError> {
+        if i >= self.len() {
+            return Err(StaticFifoError::Empty);
+        }
+        let (byte, shift, mask) = Self::locate((self.read_ptr + i) % Self::SLOTS);
+        Ok(T::unpack((self.buf[byte] >> shift) & mask))
+    }
+
+    // byte, bit offset and mask of slot p.
+    #[inline]
+    fn locate(p : usize) -> (usize, u32, u8) {
+        let bit = p * T::BITS as usize;
+        (bit / 8, (bit % 8) as u32, (0xFFu16 >> (8 - T::BITS)) as u8)
+    }
+
+    #[inline]
+    fn next(p : usize) -> usize {
+        if p + 1 >= Self::SLOTS { 0 } else { p + 1 }
+    }
+}
+
+impl<const BYTES : usize> PackedFifo<Nibble, BYTES> {
+
+    // queue a 4 bit sample, Malformed (nothing queued) if it does not fit in 4 bits.
+    pub fn put_nibble(&mut self, v : u8) -> Result<(), StaticFifoError> {
+        self.put(Nibble::new(v).ok_or(StaticFifoError::Malformed)?)
+    }
+
+    // next sample, in the low 4 bits.
+    pub fn get_nibble(&mut self) -> Result<u8, StaticFifoError> {
+        self.get().map(Nibble::value)
+    }
+}
+
+impl<T : Packable, const BYTES : usize> FifoProducer<T> for PackedFifo<T, BYTES> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        PackedFifo::put(self, data)
+    }
+
+    fn free_space(&self) -> usize {
+        PackedFifo::free_space(self)
+    }
+}
+
+impl<T : Packable, const BYTES : usize> FifoConsumer<T> for PackedFifo<T, BYTES> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        PackedFifo::get(self)
+    }
+
+    fn len(&self) -> usize {
+        PackedFifo::len(self)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Led {
+        Off,
+        On,
+        Blink,
+    }
+
+    impl Packable for Led {
+        const BITS : u32 = 2;
+
+        fn pack(self) -> u8 {
+            self as u8
+        }
+
+        fn unpack(bits : u8) -> Self {
+            match bits {
+                0 => Led::Off,
+                1 => Led::On,
+                _ => Led::Blink,
+            }
+        }
+    }
+
+    #[test]
+    fn packed_elements() {
+        println!("##################### PACKED FIFO TEST ######################################");
+
+        // 16 bool slots in 2 bytes, one of them the full marker
+        let mut bits : PackedFifo<bool, 2> = PackedFifo::new();
+        assert!(bits.capacity() == 15, "capacity wrong");
+        for i in 0..15 {
+            bits.put(i % 3 == 0).unwrap();
+        }
+        assert!(bits.put(true) == Err(StaticFifoError::Full), "full fifo took data");
+        assert!((0..15).all(|i| bits.get() == Ok(i % 3 == 0)), "bools wrong");
+
+        // 2 bit enum across the wrap
+        let mut leds : PackedFifo<Led, 1> = PackedFifo::new();
+        for _ in 0..2 {
+            leds.put(Led::Off).unwrap();
+            leds.get().unwrap();
+        }
+        for l in [Led::Blink, Led::On, Led::Off] {
+            leds.put(l).unwrap();
+        }
+        assert!(leds.is_full() && leds.peek_at(1) == Ok(Led::On), "enum peek wrong");
+        assert!(leds.get() == Ok(Led::Blink) && leds.get() == Ok(Led::On) && leds.get() == Ok(Led::Off), "enum order wrong");
+        assert!(leds.is_empty(), "fifo not empty");
+    }
+
+    #[test]
+    fn nibble_samples() {
+        println!("##################### NIBBLE FIFO TEST ######################################");
+
+        // 8 samples in 4 bytes, one slot the full marker
+        let mut adc : NibbleFifo<4> = NibbleFifo::new();
+        assert!(adc.capacity() == 7, "capacity wrong");
+        for v in [0x3, 0xF, 0x0, 0xA] {
+            adc.put_nibble(v).unwrap();
+        }
+        assert!(adc.put_nibble(0x10) == Err(StaticFifoError::Malformed) && adc.len() == 4, "wide sample taken");
+        assert!(adc.get_nibble() == Ok(0x3) && adc.get_nibble() == Ok(0xF), "samples wrong");
+
+        // around the wrap, both halves of a byte
+        for v in 1..=5 {
+            adc.put_nibble(v).unwrap();
+        }
+        assert!(adc.is_full() && adc.put(Nibble::from_low_bits(0x17)) == Err(StaticFifoError::Full), "full fifo took data");
+        let out : [u8; 7] = core::array::from_fn(|_| adc.get_nibble().unwrap());
+        assert!(out == [0x0, 0xA, 1, 2, 3, 4, 5], "wrapped samples wrong");
+    }
+
+    #[test]
+    fn bool_events() {
+        println!("##################### BOOL FIFO TEST ######################################");
+
+        // rising / falling edges, 23 flags in 3 bytes
+        let mut edges : BoolFifo<3> = BoolFifo::new();
+        assert!(edges.capacity() == 23 && edges.is_empty(), "new fifo wrong");
+        for i in 0..23 {
+            edges.put(i % 2 == 0).unwrap();
+        }
+        assert!(edges.len() == 23 && edges.put(true) == Err(StaticFifoError::Full), "full fifo took data");
+        assert!(edges.get() == Ok(true) && edges.get() == Ok(false) && edges.len() == 21, "flags wrong");
+        edges.put(false).unwrap();
+        assert!((0..21).all(|i| edges.get() == Ok(i % 2 == 0)) && edges.get() == Ok(false), "wrapped flags wrong");
+        assert!(edges.get() == Err(StaticFifoError::Empty), "empty fifo gave data");
+    }
+}
@@ -0,0 +1,59 @@
+////////////////////////////////////////////////////////////////
+// Block padded writes
+//
+// For drains that work in fixed blocks (flash pages, AES
+// blocks): put_padded() queues the data and fills up to the
+// next multiple of the block size with a pad byte, all or
+// nothing. As long as every write goes through it, the drain
+// side always finds whole blocks queued.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoError};
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // queue data plus pad bytes up to a multiple of block_size, returns how many pad bytes went in.
+    // Full (nothing queued) if it does not all fit, Malformed for a block_size of 0.
+    pub fn put_padded(&mut self, data : &[u8], block_size : usize, pad : u8) -> Result<usize, StaticFifoError> {
+        if block_size == 0 {
+            return Err(StaticFifoError::Malformed);
+        }
+        let fill = (block_size - data.len() % block_size) % block_size;
+        if data.len() + fill > self.free_space() {
+            return Err(StaticFifoError::Full);
+        }
+        self.copy_in(data);
+        for _ in 0..fill {
+            let _ = self.put(pad);
+        }
+        Ok(fill)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn padded_blocks() {
+        println!("##################### PADDED WRITE TEST ######################################");
+
+        // 16 byte blocks, like AES
+        let mut log : StaticFifoU8<40> = StaticFifoU8::new();
+        assert!(log.put_padded(b"boot ok", 16, 0xFF) == Ok(9) && log.len() == 16, "short record not padded");
+        assert!(log.put_padded(&[0x55; 16], 16, 0xFF) == Ok(0) && log.len() == 32, "whole block padded");
+        assert!(log.put_padded(b"x", 16, 0xFF) == Err(StaticFifoError::Full) && log.len() == 32, "partial block queued");
+        assert!(log.put_padded(b"x", 0, 0xFF) == Err(StaticFifoError::Malformed), "zero block size taken");
+        assert!(log.put_padded(&[], 16, 0xFF) == Ok(0) && log.len() == 32, "empty write padded");
+
+        let mut block = [0u8; 16];
+        for b in block.iter_mut() {
+            *b = log.get().unwrap();
+        }
+        assert!(&block[..7] == b"boot ok" && block[7..].iter().all(|b| *b == 0xFF), "pad bytes wrong");
+    }
+}
@@ -0,0 +1,140 @@
+////////////////////////////////////////////////////////////////
+// PingPongBuffer
+//
+// Double buffer made of two fixed halves. The producer (DMA,
+// ISR) fills one half while the consumer works on the other.
+// swap() hands the filled half over, release() gives the
+// consumer's half back. If the consumer has not released its
+// half yet swap() refuses, so an overrun is reported instead
+// of half a block being overwritten under the consumer.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+
+pub struct PingPongBuffer<T, const N : usize> {
+    halves: [[T; N]; 2],
+    // half the producer is filling
+    fill: usize,
+    // elements put into the fill half so far
+    filled: usize,
+    // length of the half handed to the consumer, None while the consumer has nothing
+    ready: Option<usize>,
+}
+
+impl<T : Copy + Default, const N : usize> Default for PingPongBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T : Copy + Default, const N : usize> PingPongBuffer<T, N> {
+
+    pub fn new() -> Self {
+        PingPongBuffer { halves: [[T::default(); N]; 2], fill: 0, filled: 0, ready: None }
+    }
+
+    // producer side
+
+    // append one element to the half being filled. Full once that half holds N.
+    #[inline]
+    pub fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        if self.filled >= N {
+            return Err(StaticFifoError::Full);
+        }
+        self.halves[self.fill][self.filled] = data;
+        self.filled += 1;
+        Ok(())
+    }
+
+    // elements in the half being filled.
+    pub fn filled(&self) -> usize {
+        self.filled
+    }
+
+    pub fn is_fill_full(&self) -> bool {
+        self.filled >= N
+    }
+
+    // the whole half being filled, for a DMA to write straight into.
+    // report how much was written with set_filled().
+    pub fn fill_half_mut(&mut self) -> &mut [T; N] {
+        &mut self.halves[self.fill]
+    }
+
+    pub fn set_filled(&mut self, n : usize) {
+        self.filled = core::cmp::min(n, N);
+    }
+
+    // hand the fill half (as filled so far) to the consumer and start filling the other one.
+    // Full if the consumer still holds the other half, nothing changes in that case.
+    pub fn swap(&mut self) -> Result<(), StaticFifoError> {
+        if self.ready.is_some() {
+            return Err(StaticFifoError::Full);
+        }
+        self.ready = Some(self.filled);
+        self.fill ^= 1;
+        self.filled = 0;
+        Ok(())
+    }
+
+    // consumer side
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.is_some()
+    }
+
+    // the half handed over by the last swap(), None if there is nothing to process.
+    pub fn ready(&self) -> Option<&[T]> {
+        self.ready.map(|n| &self.halves[self.fill ^ 1][..n])
+    }
+
+    pub fn ready_mut(&mut self) -> Option<&mut [T]> {
+        let half = self.fill ^ 1;
+        self.ready.map(move |n| &mut self.halves[half][..n])
+    }
+
+    // done with the ready half, the producer may swap into it again.
+    pub fn release(&mut self) {
+        self.ready = None;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn ping_pong() {
+        println!("##################### PING PONG TEST ######################################");
+
+        let mut pp : PingPongBuffer<u16, 3> = PingPongBuffer::new();
+
+        assert!(pp.ready().is_none(), "new buffer has a ready half");
+        for v in [1, 2, 3] {
+            pp.put(v).unwrap();
+        }
+        assert!(pp.is_fill_full() && pp.put(4) == Err(StaticFifoError::Full), "put into full half");
+        pp.swap().unwrap();
+        assert!(pp.ready() == Some(&[1, 2, 3][..]), "ready half wrong");
+
+        // producer keeps going while the consumer holds the ready half
+        pp.put(4).unwrap();
+        assert!(pp.swap() == Err(StaticFifoError::Full), "swap over an unreleased half");
+        assert!(pp.filled() == 1 && pp.ready() == Some(&[1, 2, 3][..]), "failed swap changed state");
+        pp.release();
+
+        // dma style fill of a partial block
+        pp.fill_half_mut()[1] = 5;
+        pp.set_filled(2);
+        pp.swap().unwrap();
+        assert!(pp.ready() == Some(&[4, 5][..]), "partial half wrong");
+        pp.ready_mut().unwrap()[0] = 9;
+        assert!(pp.ready() == Some(&[9, 5][..]), "ready_mut did not write through");
+        pp.release();
+        assert!(!pp.is_ready(), "released half still ready");
+    }
+}
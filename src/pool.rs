@@ -0,0 +1,122 @@
+////////////////////////////////////////////////////////////////
+// StaticPool
+//
+// Pool of pre-allocated slots. The indices of the free slots
+// sit in a StaticFifoU32, alloc() takes one off the front and
+// free() puts it back at the end, so slots are reused round
+// robin. Slots are reached through a PoolHandle, which is not
+// Copy / Clone, so a slot can only be freed once.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU32, StaticFifoError};
+
+// owned ticket for one slot of the pool it came from.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PoolHandle(u32);
+
+impl PoolHandle {
+
+    // slot index, handy for tables that run parallel to the pool.
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+// note N is raw storage, same as the fifos one slot is lost to the
+// full marker of the free list, so N-1 slots can be handed out.
+pub struct StaticPool<T, const N : usize> {
+    slots: [T; N],
+    free: StaticFifoU32<N>,
+}
+
+impl<T : Copy + Default, const N : usize> Default for StaticPool<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T : Copy + Default, const N : usize> StaticPool<T, N> {
+
+    pub fn new() -> Self {
+        let mut free = StaticFifoU32::new();
+        for i in 0..(N.saturating_sub(1)) {
+            let _ = free.put(i as u32);
+        }
+        StaticPool { slots: [T::default(); N], free }
+    }
+}
+
+impl<T, const N : usize> StaticPool<T, N> {
+
+    // number of slots that can still be handed out.
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    // number of slots handed out.
+    pub fn in_use(&self) -> usize {
+        N.saturating_sub(1) - self.free.len()
+    }
+
+    // take a free slot and move value into it. Full if every slot is in use.
+    pub fn alloc(&mut self, value : T) -> Result<PoolHandle, StaticFifoError> {
+        let i = self.free.get().map_err(|_| StaticFifoError::Full)?;
+        self.slots[i as usize] = value;
+        Ok(PoolHandle(i))
+    }
+
+    pub fn get(&self, h : &PoolHandle) -> &T {
+        &self.slots[h.index()]
+    }
+
+    pub fn get_mut(&mut self, h : &PoolHandle) -> &mut T {
+        &mut self.slots[h.index()]
+    }
+}
+
+impl<T : Copy, const N : usize> StaticPool<T, N> {
+
+    // give the slot back, returns the value that was in it.
+    pub fn free(&mut self, h : PoolHandle) -> T {
+        let rv = self.slots[h.index()];
+        // the free list has room for every slot, this put can not fail.
+        let _ = self.free.put(h.0);
+        rv
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn pool_alloc_free() {
+        println!("##################### POOL TEST ######################################");
+
+        let mut pool : StaticPool<[u8; 4], 4> = StaticPool::new();
+
+        assert!(pool.available() == 3 && pool.in_use() == 0, "new pool counts wrong");
+        let a = pool.alloc([1; 4]).unwrap();
+        let b = pool.alloc([2; 4]).unwrap();
+        let c = pool.alloc([3; 4]).unwrap();
+        assert!(pool.alloc([4; 4]) == Err(StaticFifoError::Full), "alloc from empty pool");
+        assert!(a.index() != b.index() && b.index() != c.index(), "slot handed out twice");
+
+        pool.get_mut(&b)[0] = 9;
+        assert!(*pool.get(&b) == [9, 2, 2, 2], "slot contents wrong");
+        assert!(pool.free(b) == [9, 2, 2, 2], "free returned wrong value");
+        assert!(pool.in_use() == 2, "in use count wrong");
+
+        // freed slots are reused round robin
+        let d = pool.alloc([5; 4]).unwrap();
+        assert!(*pool.get(&d) == [5; 4] && *pool.get(&a) == [1; 4], "reused slot clobbered another");
+        pool.free(a);
+        pool.free(c);
+        pool.free(d);
+        assert!(pool.available() == 3, "pool not full after freeing everything");
+    }
+}
@@ -0,0 +1,139 @@
+////////////////////////////////////////////////////////////////
+// PriorityFifo
+//
+// Two StaticFifoU32 lanes behind one pop(). The high lane is
+// always drained first, so control words jump ahead of bulk
+// data. Optionally the low lane is guaranteed one element
+// every max_burst high pops, so a busy high lane can not
+// starve it completely.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU32, StaticFifoError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lane {
+    High,
+    Low,
+}
+
+// note H and L are the raw storage of each lane, same as StaticFifoU32.
+pub struct PriorityFifo<const H : usize, const L : usize> {
+    high: StaticFifoU32<H>,
+    low: StaticFifoU32<L>,
+    max_burst: Option<usize>,
+    burst: usize,
+}
+
+impl<const H : usize, const L : usize> Default for PriorityFifo<H, L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const H : usize, const L : usize> PriorityFifo<H, L> {
+
+    // strict priority, low only moves when high is empty.
+    pub const fn new() -> Self {
+        PriorityFifo { high: StaticFifoU32::new(), low: StaticFifoU32::new(), max_burst: None, burst: 0 }
+    }
+
+    // after max_burst high pops in a row with low waiting, the next pop comes from low.
+    pub const fn with_max_burst(max_burst : usize) -> Self {
+        PriorityFifo { high: StaticFifoU32::new(), low: StaticFifoU32::new(), max_burst: Some(max_burst), burst: 0 }
+    }
+
+    pub fn set_max_burst(&mut self, max_burst : Option<usize>) {
+        self.max_burst = max_burst;
+    }
+
+    pub fn len(&self) -> usize {
+        self.high.len() + self.low.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.high.is_empty() && self.low.is_empty()
+    }
+
+    // number of elements queued in one lane.
+    pub fn lane_len(&self, lane : Lane) -> usize {
+        match lane {
+            Lane::High => self.high.len(),
+            Lane::Low => self.low.len(),
+        }
+    }
+
+    // Full only reflects the lane that was put to.
+    pub fn put(&mut self, lane : Lane, data : u32) -> Result<(), StaticFifoError> {
+        match lane {
+            Lane::High => self.high.put(data),
+            Lane::Low => self.low.put(data),
+        }
+    }
+
+    // lane the next pop() will take from, None if both are empty.
+    pub fn next_lane(&self) -> Option<Lane> {
+        let starved = self.max_burst.is_some_and(|m| self.burst >= m);
+        match (self.high.is_empty(), self.low.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some(Lane::High),
+            (true, false) => Some(Lane::Low),
+            (false, false) => Some(if starved { Lane::Low } else { Lane::High }),
+        }
+    }
+
+    // remove the next element, high first (see with_max_burst), returns it with its lane.
+    pub fn pop(&mut self) -> Result<(u32, Lane), StaticFifoError> {
+        match self.next_lane() {
+            Some(Lane::High) => {
+                // only a burst while low is actually waiting counts toward starvation.
+                if !self.low.is_empty() {
+                    self.burst += 1;
+                }
+                Ok((self.high.get()?, Lane::High))
+            },
+            Some(Lane::Low) => {
+                self.burst = 0;
+                Ok((self.low.get()?, Lane::Low))
+            },
+            None => Err(StaticFifoError::Empty),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn priority_fifo_order() {
+        println!("##################### PRIORITY FIFO TEST ######################################");
+
+        let mut q : PriorityFifo<4, 8> = PriorityFifo::new();
+
+        q.put(Lane::Low, 10).unwrap();
+        q.put(Lane::Low, 11).unwrap();
+        q.put(Lane::High, 1).unwrap();
+        q.put(Lane::High, 2).unwrap();
+        assert!(q.len() == 4 && q.lane_len(Lane::High) == 2, "lengths wrong");
+        assert!(q.pop() == Ok((1, Lane::High)) && q.pop() == Ok((2, Lane::High)), "high not drained first");
+        assert!(q.pop() == Ok((10, Lane::Low)), "low not popped after high");
+        q.put(Lane::High, 3).unwrap();
+        assert!(q.pop() == Ok((3, Lane::High)), "new high did not jump ahead");
+        assert!(q.pop() == Ok((11, Lane::Low)), "last low wrong");
+        assert!(q.pop() == Err(StaticFifoError::Empty), "pop on empty queue");
+
+        // starvation avoidance, one low per two high
+        let mut q : PriorityFifo<8, 8> = PriorityFifo::with_max_burst(2);
+        for v in 1..6 {
+            q.put(Lane::High, v).unwrap();
+        }
+        q.put(Lane::Low, 10).unwrap();
+        q.put(Lane::Low, 11).unwrap();
+        let order : [u32; 7] = core::array::from_fn(|_| q.pop().unwrap().0);
+        assert!(order == [1, 2, 10, 3, 4, 11, 5], "burst limit not applied");
+    }
+}
@@ -0,0 +1,129 @@
+////////////////////////////////////////////////////////////////
+// Kani proof harnesses
+//
+// Bounded model checking of the core fifo index handling. Run
+// with `RUSTFLAGS="--cfg verification" cargo kani`; the module
+// only exists under cfg(kani) and cfg(verification), so normal
+// builds never see it. For any sequence of operations
+// (up to OPS long) the pointers stay inside the buffer, len()
+// never exceeds the usable capacity and len + free_space adds
+// up. Kani checks every slice / array index on the way, so a
+// passing run also rules out out of bounds accesses.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32};
+
+// operations per harness. small, the state space of a 4 element fifo is covered long before this.
+const OPS : usize = 8;
+
+fn check_u8<const N : usize>(fifo : &StaticFifoU8<N>) {
+    assert!(fifo.read_ptr < N && fifo.write_ptr < N);
+    assert!(fifo.capacity == N);
+    assert!(fifo.len() < N);
+    assert!(fifo.len() + fifo.free_space() == N - 1);
+    assert!(fifo.is_empty() == (fifo.len() == 0));
+    assert!(fifo.is_full() == (fifo.len() == N - 1));
+}
+
+fn check_u32<const N : usize>(fifo : &StaticFifoU32<N>) {
+    assert!(fifo.read_ptr < N && fifo.write_ptr < N);
+    assert!(fifo.capacity == N);
+    assert!(fifo.len() < N);
+    assert!(fifo.len() + fifo.free_space() == N - 1);
+    assert!(fifo.is_empty() == (fifo.len() == 0));
+    assert!(fifo.is_full() == (fifo.len() == N - 1));
+}
+
+#[kani::proof]
+#[kani::unwind(10)]
+fn u8_index_invariants() {
+    let mut fifo : StaticFifoU8<4> = StaticFifoU8::new();
+    for _ in 0..OPS {
+        let before = fifo.len();
+        match kani::any::<u8>() % 6 {
+            0 => {
+                let ok = fifo.put(kani::any()).is_ok();
+                assert!(ok == (before < 3));
+                assert!(fifo.len() == if ok { before + 1 } else { before });
+            },
+            1 => {
+                let ok = fifo.get().is_ok();
+                assert!(ok == (before > 0));
+                assert!(fifo.len() == if ok { before - 1 } else { before });
+            },
+            2 => {
+                let i : usize = kani::any();
+                assert!(fifo.peek_at(i).is_ok() == (i < before));
+            },
+            3 => {
+                let n : usize = kani::any();
+                let skipped = fifo.skip(n);
+                assert!(skipped <= before && fifo.len() == before - skipped);
+            },
+            4 => {
+                let (a, b) = fifo.as_slices();
+                assert!(a.len() + b.len() == before);
+            },
+            _ => {
+                assert!(fifo.make_contiguous().len() == before);
+            },
+        }
+        check_u8(&fifo);
+    }
+}
+
+#[kani::proof]
+#[kani::unwind(10)]
+fn u32_index_invariants() {
+    let mut fifo : StaticFifoU32<4> = StaticFifoU32::new();
+    for _ in 0..OPS {
+        let before = fifo.len();
+        match kani::any::<u8>() % 6 {
+            0 => {
+                let ok = fifo.put(kani::any()).is_ok();
+                assert!(ok == (before < 3));
+                assert!(fifo.len() == if ok { before + 1 } else { before });
+            },
+            1 => {
+                let ok = fifo.get().is_ok();
+                assert!(ok == (before > 0));
+                assert!(fifo.len() == if ok { before - 1 } else { before });
+            },
+            2 => {
+                let i : usize = kani::any();
+                assert!(fifo.peek_at(i).is_ok() == (i < before));
+            },
+            3 => {
+                let n : usize = kani::any();
+                let skipped = fifo.skip(n);
+                assert!(skipped <= before && fifo.len() == before - skipped);
+            },
+            4 => {
+                let (a, b) = fifo.as_slices();
+                assert!(a.len() + b.len() == before);
+            },
+            _ => {
+                assert!(fifo.make_contiguous().len() == before);
+            },
+        }
+        check_u32(&fifo);
+    }
+}
+
+// data put comes back out in order, whatever the pointers started at.
+#[kani::proof]
+#[kani::unwind(6)]
+fn u8_order_from_any_start() {
+    let mut fifo : StaticFifoU8<4> = StaticFifoU8::new();
+    let start : usize = kani::any();
+    kani::assume(start < 4);
+    fifo.read_ptr = start;
+    fifo.write_ptr = start;
+    let a : u8 = kani::any();
+    let b : u8 = kani::any();
+    fifo.put(a).unwrap();
+    fifo.put(b).unwrap();
+    assert!(fifo.get() == Ok(a) && fifo.get() == Ok(b));
+    check_u8(&fifo);
+}
@@ -0,0 +1,190 @@
+////////////////////////////////////////////////////////////////
+// Differential property tests
+//
+// Runs random operation sequences against the fifos and a
+// VecDeque model limited to the same capacity, and checks that
+// everything observable (results, len, free space, contents)
+// stays identical. Catches wrap / len corner cases that the
+// hand written tests do not happen to hit.
+//
+///////////////////////////////////////////////////////////////
+
+use std::collections::VecDeque;
+use std::vec::Vec;
+use proptest::prelude::*;
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError, ByteFifo};
+
+// small storage so sequences wrap and fill often. holds CAP - 1 elements.
+const CAP : usize = 5;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Put(u32),
+    Get,
+    PutSlice(Vec<u32>),
+    GetSlice(usize),
+    Skip(usize),
+    PeekAt(usize),
+    Find(u32),
+    MakeContiguous,
+    Init,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        4 => any::<u32>().prop_map(Op::Put),
+        3 => Just(Op::Get),
+        1 => proptest::collection::vec(any::<u32>(), 0..CAP + 2).prop_map(Op::PutSlice),
+        1 => (0..CAP + 2).prop_map(Op::GetSlice),
+        1 => (0..CAP + 2).prop_map(Op::Skip),
+        1 => (0..CAP + 2).prop_map(Op::PeekAt),
+        // few distinct values so find() hits sometimes
+        1 => (0u32..4).prop_map(Op::Find),
+        1 => Just(Op::MakeContiguous),
+        1 => Just(Op::Init),
+    ]
+}
+
+// the model: a VecDeque refusing to grow past CAP - 1.
+struct Model {
+    q: VecDeque<u32>,
+}
+
+impl Model {
+
+    fn put(&mut self, v : u32) -> Result<(), StaticFifoError> {
+        if self.q.len() == CAP - 1 {
+            return Err(StaticFifoError::Full);
+        }
+        self.q.push_back(v);
+        Ok(())
+    }
+
+    fn get(&mut self) -> Result<u32, StaticFifoError> {
+        self.q.pop_front().ok_or(StaticFifoError::Empty)
+    }
+
+    // as many as fit, returns how many went in.
+    fn put_slice(&mut self, data : &[u32]) -> usize {
+        let n = core::cmp::min(data.len(), CAP - 1 - self.q.len());
+        self.q.extend(&data[..n]);
+        n
+    }
+
+    // all or nothing.
+    fn put_all(&mut self, data : &[u32]) -> Result<(), StaticFifoError> {
+        if data.len() > CAP - 1 - self.q.len() {
+            return Err(StaticFifoError::Full);
+        }
+        self.q.extend(data);
+        Ok(())
+    }
+
+    // up to n from the front.
+    fn get_slice(&mut self, n : usize) -> Vec<u32> {
+        let n = core::cmp::min(n, self.q.len());
+        self.q.drain(..n).collect()
+    }
+
+    fn skip(&mut self, n : usize) -> usize {
+        let n = core::cmp::min(n, self.q.len());
+        self.q.drain(..n);
+        n
+    }
+
+    fn peek_at(&self, i : usize) -> Result<u32, StaticFifoError> {
+        self.q.get(i).copied().ok_or(StaticFifoError::Empty)
+    }
+
+    fn find(&self, v : u32) -> Option<usize> {
+        self.q.iter().position(|&x| x == v)
+    }
+}
+
+proptest! {
+
+    #[test]
+    fn u32_fifo_matches_vecdeque(ops in proptest::collection::vec(op(), 0..200)) {
+        let mut fifo : StaticFifoU32<CAP> = StaticFifoU32::new();
+        let mut model = Model { q: VecDeque::new() };
+        for op in ops {
+            match op {
+                Op::Put(v) => prop_assert_eq!(fifo.put(v), model.put(v)),
+                Op::Get => prop_assert_eq!(fifo.get(), model.get()),
+                // the word fifo has no partial slice put, the vectored one is all or nothing
+                Op::PutSlice(v) => prop_assert_eq!(fifo.put_vectored(&[&v]), model.put_all(&v)),
+                Op::GetSlice(n) => {
+                    let mut out = [0u32; CAP + 2];
+                    let got = fifo.copy_to_slice(&mut out[..n]);
+                    prop_assert_eq!(fifo.skip(got), got);
+                    prop_assert_eq!(&out[..got], &model.get_slice(n)[..]);
+                },
+                Op::Skip(n) => prop_assert_eq!(fifo.skip(n), model.skip(n)),
+                Op::PeekAt(i) => prop_assert_eq!(fifo.peek_at(i), model.peek_at(i)),
+                Op::Find(v) => prop_assert_eq!(fifo.find(v), model.find(v)),
+                Op::MakeContiguous => {
+                    let model_contents : Vec<u32> = model.q.iter().copied().collect();
+                    prop_assert_eq!(fifo.make_contiguous(), &model_contents[..]);
+                },
+                Op::Init => {
+                    fifo.init();
+                    model.q.clear();
+                },
+            }
+            prop_assert_eq!(fifo.len(), model.q.len());
+            prop_assert_eq!(fifo.free_space(), CAP - 1 - model.q.len());
+            prop_assert_eq!(fifo.is_empty(), model.q.is_empty());
+            prop_assert_eq!(fifo.is_full(), model.q.len() == CAP - 1);
+            let (a, b) = fifo.as_slices();
+            let (ma, mb) = model.q.as_slices();
+            prop_assert_eq!([a, b].concat(), [ma, mb].concat());
+            let mut out = [0u32; CAP];
+            let n = fifo.copy_to_slice(&mut out);
+            prop_assert_eq!(&out[..n], &[ma, mb].concat()[..]);
+        }
+    }
+
+    #[test]
+    fn u8_fifo_matches_vecdeque(ops in proptest::collection::vec(op(), 0..200)) {
+        let mut fifo : StaticFifoU8<CAP> = StaticFifoU8::new();
+        let mut model = Model { q: VecDeque::new() };
+        for op in ops {
+            // the model stores u32, keep values in byte range.
+            match op {
+                Op::Put(v) => prop_assert_eq!(fifo.put(v as u8), model.put(v & 0xFF)),
+                Op::Get => prop_assert_eq!(fifo.get().map(u32::from), model.get()),
+                Op::PutSlice(v) => {
+                    let bytes : Vec<u8> = v.iter().map(|&x| x as u8).collect();
+                    let masked : Vec<u32> = v.iter().map(|&x| x & 0xFF).collect();
+                    prop_assert_eq!(fifo.put_slice(&bytes), model.put_slice(&masked));
+                },
+                Op::GetSlice(n) => {
+                    let mut out = [0u8; CAP + 2];
+                    let got = fifo.get_slice(&mut out[..n]);
+                    let contents : Vec<u32> = out[..got].iter().map(|&x| u32::from(x)).collect();
+                    prop_assert_eq!(contents, model.get_slice(n));
+                },
+                Op::Skip(n) => prop_assert_eq!(fifo.skip(n), model.skip(n)),
+                Op::PeekAt(i) => prop_assert_eq!(fifo.peek_at(i).map(u32::from), model.peek_at(i)),
+                Op::Find(v) => prop_assert_eq!(fifo.find(v as u8), model.find(v)),
+                Op::MakeContiguous => {
+                    let model_contents : Vec<u8> = model.q.iter().map(|&x| x as u8).collect();
+                    prop_assert_eq!(fifo.make_contiguous(), &model_contents[..]);
+                },
+                Op::Init => {
+                    fifo.init();
+                    model.q.clear();
+                },
+            }
+            prop_assert_eq!(fifo.len(), model.q.len());
+            prop_assert_eq!(fifo.free_space(), CAP - 1 - model.q.len());
+            prop_assert_eq!(fifo.is_empty(), model.q.is_empty());
+            prop_assert_eq!(fifo.is_full(), model.q.len() == CAP - 1);
+            let (a, b) = fifo.as_slices();
+            let contents : Vec<u32> = a.iter().chain(b).map(|&x| u32::from(x)).collect();
+            let model_contents : Vec<u32> = model.q.iter().copied().collect();
+            prop_assert_eq!(contents, model_contents);
+        }
+    }
+}
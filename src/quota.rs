@@ -0,0 +1,213 @@
+////////////////////////////////////////////////////////////////
+// Per-producer quotas
+//
+// For the multi producer setup (a LockedFifo shared by several
+// tasks / cores): each producer gets an id and a cap on how
+// many of its elements may sit in the fifo at once, so one
+// chatty task fills its share and then gets Full while the
+// others still find room.
+//
+// The owner of each queued element is kept in a side ring of
+// ids that moves in lockstep with the fifo, so get() knows
+// whose count to give back. Queued / rejected counts per
+// producer are there for debugging starvation.
+//
+// P producers, ids 0..P. N must match the raw size of the
+// fifo wrapped, the id ring holds N-1 like the fifo does.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoError};
+use crate::lock::{Lock, LockedFifo};
+use crate::traits::{FifoProducer, FifoConsumer};
+
+// accounting for one producer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProducerStats {
+    // elements of this producer currently in the fifo.
+    pub queued: usize,
+    // most it may have in the fifo at once.
+    pub quota: usize,
+    // puts refused because the quota (or the fifo) was full.
+    pub rejected: u32,
+}
+
+pub struct QuotaFifo<F, const P : usize, const N : usize> {
+    fifo: F,
+    owners: StaticFifoU8<N>,
+    stats: [ProducerStats; P],
+}
+
+impl<F, const P : usize, const N : usize> QuotaFifo<F, P, N> {
+
+    const PRODUCERS_OK : () = assert!(P <= 256, "QuotaFifo ids are u8, at most 256 producers");
+
+    // every producer starts with the whole fifo as its quota.
+    pub fn new(fifo : F) -> Self {
+        let () = Self::PRODUCERS_OK;
+        let quota = N.saturating_sub(1);
+        QuotaFifo { fifo, owners: StaticFifoU8::new(), stats: [ProducerStats { queued: 0, quota, rejected: 0 }; P] }
+    }
+
+    // cap producer id at quota queued elements. lowering it below what is queued
+    // only blocks new puts, nothing is dropped.
+    pub fn set_quota(&mut self, id : usize, quota : usize) -> Result<(), StaticFifoError> {
+        self.stats.get_mut(id).ok_or(StaticFifoError::TooLarge)?.quota = quota;
+        Ok(())
+    }
+
+    pub fn stats(&self, id : usize) -> Option<ProducerStats> {
+        self.stats.get(id).copied()
+    }
+
+    pub fn all_stats(&self) -> &[ProducerStats; P] {
+        &self.stats
+    }
+
+    pub fn reset_rejected(&mut self) {
+        for s in self.stats.iter_mut() {
+            s.rejected = 0;
+        }
+    }
+
+    // put on behalf of producer id. Full if its quota or the fifo is used up,
+    // TooLarge for an unknown id.
+    pub fn put_from<T>(&mut self, id : usize, data : T) -> Result<(), StaticFifoError> where F : FifoProducer<T> {
+        let s = self.stats.get_mut(id).ok_or(StaticFifoError::TooLarge)?;
+        if s.queued >= s.quota || self.owners.is_full() {
+            s.rejected = s.rejected.wrapping_add(1);
+            return Err(StaticFifoError::Full);
+        }
+        if let Err(e) = self.fifo.put(data) {
+            s.rejected = s.rejected.wrapping_add(1);
+            return Err(e);
+        }
+        // id < P <= 256, and the ring had room.
+        let _ = self.owners.put(id as u8);
+        s.queued += 1;
+        Ok(())
+    }
+
+    // elements queued, counted on the id ring.
+    pub fn len(&self) -> usize {
+        self.owners.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.owners.is_empty()
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.fifo
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo
+    }
+}
+
+impl<T, F : FifoConsumer<T>, const P : usize, const N : usize> FifoConsumer<T> for QuotaFifo<F, P, N> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        let rv = self.fifo.get()?;
+        let id = self.owners.get().map_err(|_| StaticFifoError::Corrupt)?;
+        if let Some(s) = self.stats.get_mut(id as usize) {
+            s.queued = s.queued.saturating_sub(1);
+        }
+        Ok(rv)
+    }
+
+    fn len(&self) -> usize {
+        self.owners.len()
+    }
+}
+
+impl<F, L : Lock, const P : usize, const N : usize> LockedFifo<QuotaFifo<F, P, N>, L> {
+
+    // handle for producer id, puts through the lock under that id's quota.
+    pub fn producer(&self, id : usize) -> QuotaProducer<'_, F, L, P, N> {
+        QuotaProducer { shared: self, id }
+    }
+
+    pub fn stats(&self, id : usize) -> Option<ProducerStats> {
+        self.with(|q| q.stats(id))
+    }
+}
+
+pub struct QuotaProducer<'a, F, L, const P : usize, const N : usize> {
+    shared: &'a LockedFifo<QuotaFifo<F, P, N>, L>,
+    id: usize,
+}
+
+impl<'a, F, L : Lock, const P : usize, const N : usize> QuotaProducer<'a, F, L, P, N> {
+
+    pub fn put<T>(&self, data : T) -> Result<(), StaticFifoError> where F : FifoProducer<T> {
+        self.shared.with(|q| q.put_from(self.id, data))
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn stats(&self) -> Option<ProducerStats> {
+        self.shared.stats(self.id)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use std::thread;
+    use crate::atomic::{AtomicBool, Ordering};
+    use super::*;
+    use crate::StaticFifoU32;
+
+    struct SpinLock(AtomicBool);
+
+    unsafe impl Lock for SpinLock {
+        type Token = ();
+
+        fn acquire(&self) {
+            while self.0.swap(true, Ordering::Acquire) {
+                thread::yield_now();
+            }
+        }
+
+        fn release(&self, _token : ()) {
+            self.0.store(false, Ordering::Release);
+        }
+    }
+
+    #[test]
+    fn chatty_producer_capped() {
+        println!("##################### QUOTA FIFO TEST ######################################");
+
+        // 7 slots, 3 producers; the logger may only hold 3 of them
+        let mut q : QuotaFifo<StaticFifoU32<8>, 3, 8> = QuotaFifo::new(StaticFifoU32::new());
+        q.set_quota(0, 3).unwrap();
+        assert!(q.set_quota(3, 1) == Err(StaticFifoError::TooLarge), "unknown id accepted");
+        let shared = LockedFifo::new(q, SpinLock(AtomicBool::new(false)));
+
+        let (logger, sensor, control) = (shared.producer(0), shared.producer(1), shared.producer(2));
+        let mut sent = 0;
+        while logger.put(sent).is_ok() {
+            sent += 1;
+        }
+        assert!(sent == 3, "quota not enforced");
+        assert!(logger.stats() == Some(ProducerStats { queued: 3, quota: 3, rejected: 1 }), "logger stats wrong");
+
+        // the others still get in
+        sensor.put(100u32).unwrap();
+        control.put(200u32).unwrap();
+        assert!(sensor.stats().map(|s| s.queued) == Some(1), "sensor stats wrong");
+
+        // draining gives the logger its share back
+        assert!(shared.get() == Ok(0u32), "order wrong");
+        assert!(logger.put(3).is_ok() && logger.put(4).is_err(), "quota not given back");
+        let rest : std::vec::Vec<u32> = core::iter::from_fn(|| shared.get().ok()).collect();
+        assert!(rest == [1, 2, 100, 200, 3], "order wrong");
+        assert!(shared.with(|q| q.all_stats().iter().all(|s| s.queued == 0)), "counts not back to zero");
+    }
+}
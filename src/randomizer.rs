@@ -0,0 +1,160 @@
+////////////////////////////////////////////////////////////////
+// CCSDS pseudo-randomizer
+//
+// XORs the byte stream with the CCSDS (131.0-B) pseudo-random
+// sequence, h(x) = x^8 + x^7 + x^5 + x^3 + 1 seeded with all
+// ones (FF 48 0E C0 9A ..., repeating every 255 bytes), on the
+// way into the fifo or on the way out, so frames need no second
+// pass over a scratch buffer. XOR with the same sequence undoes
+// itself: randomizing on put at the sender and on get at the
+// receiver gets the data back.
+//
+// The sequence restarts at each frame, right after the attached
+// sync marker, which is never randomized; call frame_start()
+// there. A put or get that fails does not use up a sequence
+// byte.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+// register value at the start of each frame.
+pub const CCSDS_PN_SEED : u8 = 0xFF;
+
+// next sequence byte (msb first on the wire) and the register after it.
+pub fn ccsds_pn_byte(state : u8) -> (u8, u8) {
+    let mut s = state;
+    let mut rv = 0u8;
+    for _ in 0..8 {
+        rv = (rv << 1) | (s & 1);
+        let fb = (s ^ (s >> 3) ^ (s >> 5) ^ (s >> 7)) & 1;
+        s = (s >> 1) | (fb << 7);
+    }
+    (rv, s)
+}
+
+// which way through the fifo the bytes get randomized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RandomizeOn {
+    Put,
+    Get,
+}
+
+pub struct Randomizer<F> {
+    fifo: F,
+    on: RandomizeOn,
+    state: u8,
+}
+
+impl<F> Randomizer<F> {
+
+    pub fn new(fifo : F, on : RandomizeOn) -> Self {
+        Randomizer { fifo, on, state: CCSDS_PN_SEED }
+    }
+
+    // restart the sequence, at the start of every frame.
+    pub fn frame_start(&mut self) {
+        self.state = CCSDS_PN_SEED;
+    }
+
+    pub fn randomizes_on(&self) -> RandomizeOn {
+        self.on
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.fifo
+    }
+
+    // bytes put straight into the fifo skip the sequence.
+    pub fn inner_mut(&mut self) -> &mut F {
+        &mut self.fifo
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo
+    }
+}
+
+impl<F : FifoProducer<u8>> FifoProducer<u8> for Randomizer<F> {
+
+    fn put(&mut self, data : u8) -> Result<(), StaticFifoError> {
+        if self.on != RandomizeOn::Put {
+            return self.fifo.put(data);
+        }
+        let (pn, next) = ccsds_pn_byte(self.state);
+        self.fifo.put(data ^ pn)?;
+        self.state = next;
+        Ok(())
+    }
+
+    fn free_space(&self) -> usize {
+        self.fifo.free_space()
+    }
+}
+
+impl<F : FifoConsumer<u8>> FifoConsumer<u8> for Randomizer<F> {
+
+    fn get(&mut self) -> Result<u8, StaticFifoError> {
+        let rv = self.fifo.get()?;
+        if self.on != RandomizeOn::Get {
+            return Ok(rv);
+        }
+        let (pn, next) = ccsds_pn_byte(self.state);
+        self.state = next;
+        Ok(rv ^ pn)
+    }
+
+    fn len(&self) -> usize {
+        self.fifo.len()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+    use crate::StaticFifoU8;
+
+    #[test]
+    fn ccsds_sequence() {
+        println!("##################### CCSDS RANDOMIZER TEST ######################################");
+
+        // zeros in, the bare sequence out
+        let mut tx = Randomizer::new(StaticFifoU8::<300>::new(), RandomizeOn::Put);
+        for _ in 0..256 {
+            tx.put(0).unwrap();
+        }
+        let seq = tx.inner().as_slices().0;
+        assert!(seq[..8] == [0xFF, 0x48, 0x0E, 0xC0, 0x9A, 0x0D, 0x70, 0xBC], "sequence wrong");
+        assert!(seq[255] == seq[0] && seq[254] != seq[0], "period not 255");
+
+        // randomize on put, derandomize on get
+        let mut tx = Randomizer::new(StaticFifoU8::<8>::new(), RandomizeOn::Put);
+        let mut rx = Randomizer::new(StaticFifoU8::<8>::new(), RandomizeOn::Get);
+        for b in b"frame1" {
+            tx.put(*b).unwrap();
+        }
+        assert!(tx.put(0).is_ok() && tx.put(0) == Err(StaticFifoError::Full), "fifo size wrong");
+        while let Ok(b) = tx.inner_mut().get() {
+            rx.inner_mut().put(b).unwrap();
+        }
+        let mut out = [0u8; 7];
+        for o in out.iter_mut() {
+            *o = rx.get().unwrap();
+        }
+        assert!(&out == b"frame1\0", "derandomized data wrong");
+
+        // the failed put used no sequence byte, so both ends are still in step
+        tx.put(b'x').unwrap();
+        rx.inner_mut().put(tx.inner_mut().get().unwrap()).unwrap();
+        assert!(rx.get() == Ok(b'x'), "failed put used a sequence byte");
+
+        // next frame starts over at FF
+        tx.frame_start();
+        tx.put(0).unwrap();
+        assert!(tx.inner_mut().get() == Ok(0xFF), "frame restart wrong");
+    }
+}
@@ -0,0 +1,120 @@
+////////////////////////////////////////////////////////////////
+// RateLimiter
+//
+// Token bucket on the consumer side of a fifo. Every element
+// taken costs one token, tick(elapsed) adds rate tokens per
+// elapsed time unit up to the burst size. With no tokens left
+// get() reports Throttled and the data stays queued, which
+// turns the fifo into a traffic shaper (e.g. for a radio with
+// a duty cycle limit).
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::traits::FifoConsumer;
+
+pub struct RateLimiter<F> {
+    fifo: F,
+    rate: u32,
+    burst: u32,
+    tokens: u32,
+}
+
+impl<F> RateLimiter<F> {
+
+    // rate tokens per time unit passed to tick(), at most burst banked.
+    // starts with a full bucket.
+    pub fn new(fifo : F, rate : u32, burst : u32) -> Self {
+        RateLimiter { fifo, rate, burst, tokens: burst }
+    }
+
+    // credit elapsed time units of tokens.
+    pub fn tick(&mut self, elapsed : u32) {
+        self.tokens = core::cmp::min(self.tokens.saturating_add(self.rate.saturating_mul(elapsed)), self.burst);
+    }
+
+    // elements that may be taken right now.
+    pub fn tokens(&self) -> u32 {
+        self.tokens
+    }
+
+    pub fn set_rate(&mut self, rate : u32, burst : u32) {
+        self.rate = rate;
+        self.burst = burst;
+        self.tokens = core::cmp::min(self.tokens, burst);
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.fifo
+    }
+
+    pub fn inner_mut(&mut self) -> &mut F {
+        &mut self.fifo
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo
+    }
+}
+
+// a throttled limiter still reports its queued data through len().
+impl<T, F : FifoConsumer<T>> FifoConsumer<T> for RateLimiter<F> {
+
+    // Empty if nothing is queued, Throttled if data is queued but the bucket is empty.
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        if self.fifo.is_empty() {
+            return Err(StaticFifoError::Empty);
+        }
+        if self.tokens == 0 {
+            return Err(StaticFifoError::Throttled);
+        }
+        let rv = self.fifo.get()?;
+        self.tokens -= 1;
+        Ok(rv)
+    }
+
+    fn len(&self) -> usize {
+        self.fifo.len()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+    use crate::StaticFifoU8;
+
+    #[test]
+    fn token_bucket() {
+        println!("##################### RATE LIMIT TEST ######################################");
+
+        let mut tx : StaticFifoU8<16> = StaticFifoU8::new();
+        for b in 0..10 {
+            tx.put(b).unwrap();
+        }
+        // 1 byte per ms, bursts of 3
+        let mut shaper = RateLimiter::new(&mut tx, 1, 3);
+
+        assert!((0..3).all(|i| shaper.get() == Ok(i)), "burst not allowed");
+        assert!(shaper.get() == Err(StaticFifoError::Throttled), "bucket not empty");
+        assert!(shaper.len() == 7, "throttled get took data");
+        shaper.tick(1);
+        assert!(shaper.get() == Ok(3) && shaper.get() == Err(StaticFifoError::Throttled), "one tick allowed the wrong amount");
+
+        // a long idle time only banks the burst size
+        shaper.tick(100);
+        assert!(shaper.tokens() == 3, "bucket overfilled");
+        let mut n : usize = 0;
+        while shaper.get().is_ok() {
+            n += 1;
+        }
+        assert!(n == 3, "burst after idle wrong");
+        shaper.tick(100);
+        for _ in 0..3 {
+            shaper.get().unwrap();
+        }
+        assert!(shaper.get() == Err(StaticFifoError::Empty), "empty fifo reported as throttled");
+    }
+}
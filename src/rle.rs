@@ -0,0 +1,152 @@
+////////////////////////////////////////////////////////////////
+// Run length encoded fifo
+//
+// For streams that repeat a lot (sampled GPIO states, status
+// words): a put equal to the last value just bumps the count
+// of the last run, so a pin that sits high for 10000 samples
+// takes one (value, count) slot. The element API is the plain
+// put / get one; runs are never visible outside.
+//
+// A run counts up to 65535, the next equal put starts a new
+// one. N is the raw run storage, so N-1 runs fit; how many
+// elements that is depends on the data.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::deque::StaticDeque;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+pub struct RleFifo<T, const N : usize> {
+    runs: StaticDeque<(T, u16), N>,
+    // elements over all runs
+    count: usize,
+}
+
+impl<T : Copy + Default + PartialEq, const N : usize> Default for RleFifo<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T : Copy + Default + PartialEq, const N : usize> RleFifo<T, N> {
+
+    pub fn new() -> Self {
+        RleFifo { runs: StaticDeque::new(), count: 0 }
+    }
+
+    pub fn clear(&mut self) {
+        self.runs.clear();
+        self.count = 0;
+    }
+
+    // elements queued.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    // runs in use.
+    pub fn runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    // Full if data starts a new run and the run storage is used up.
+    pub fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        if let Ok((v, n)) = self.runs.back() {
+            if v == data && n < u16::MAX {
+                let _ = self.runs.pop_back();
+                let _ = self.runs.push_back((v, n + 1));
+                self.count += 1;
+                return Ok(());
+            }
+        }
+        self.runs.push_back((data, 1))?;
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn get(&mut self) -> Result<T, StaticFifoError> {
+        let (v, n) = self.runs.pop_front()?;
+        if n > 1 {
+            let _ = self.runs.push_front((v, n - 1));
+        }
+        self.count -= 1;
+        Ok(v)
+    }
+
+    // next element without removing it.
+    pub fn peek(&self) -> Result<T, StaticFifoError> {
+        self.runs.front().map(|(v, _)| v)
+    }
+
+    // the whole leading run at once, (value, how many).
+    pub fn get_run(&mut self) -> Result<(T, u16), StaticFifoError> {
+        let rv = self.runs.pop_front()?;
+        self.count -= rv.1 as usize;
+        Ok(rv)
+    }
+}
+
+impl<T : Copy + Default + PartialEq, const N : usize> FifoProducer<T> for RleFifo<T, N> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        RleFifo::put(self, data)
+    }
+
+    // puts that surely fit, each starting a new run. repeats of the last value may fit beyond that.
+    fn free_space(&self) -> usize {
+        self.runs.free_space()
+    }
+}
+
+impl<T : Copy + Default + PartialEq, const N : usize> FifoConsumer<T> for RleFifo<T, N> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        RleFifo::get(self)
+    }
+
+    fn len(&self) -> usize {
+        RleFifo::len(self)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn rle_gpio_samples() {
+        println!("##################### RLE FIFO TEST ######################################");
+
+        // 3 runs of room
+        let mut pin : RleFifo<bool, 4> = RleFifo::new();
+        for _ in 0..1000 {
+            pin.put(true).unwrap();
+        }
+        pin.put(false).unwrap();
+        pin.put(false).unwrap();
+        pin.put(true).unwrap();
+        assert!(pin.len() == 1003 && pin.runs() == 3, "runs not merged");
+        assert!(pin.put(false) == Err(StaticFifoError::Full) && pin.put(true).is_ok(), "run storage limit wrong");
+
+        assert!((0..1000).all(|_| pin.get() == Ok(true)), "first run wrong");
+        assert!(pin.peek() == Ok(false) && pin.runs() == 2, "used up run kept");
+        assert!(pin.get_run() == Ok((false, 2)) && pin.get() == Ok(true), "second run wrong");
+        assert!(pin.len() == 1 && pin.get() == Ok(true) && pin.is_empty(), "fifo not drained");
+
+        // a run past the counter limit continues in a second one
+        let mut word : RleFifo<u32, 4> = RleFifo::new();
+        for _ in 0..70000 {
+            word.put(7).unwrap();
+        }
+        assert!(word.runs() == 2 && word.get_run() == Ok((7, u16::MAX)), "long run wrong");
+        assert!(word.len() == 70000 - 65535, "count after get_run wrong");
+    }
+}
@@ -0,0 +1,55 @@
+////////////////////////////////////////////////////////////////
+// Zeroize support
+//
+// For fifos that carry key material or other secrets. zeroize()
+// overwrites the whole backing buffer, queued or not, with
+// writes the optimizer is not allowed to drop, then empties the
+// fifo. Put the fifo in a zeroize::Zeroizing to have that
+// happen on drop.
+//
+///////////////////////////////////////////////////////////////
+
+use zeroize::Zeroize;
+
+use crate::{StaticFifoU8, StaticFifoU32};
+
+impl<const N : usize> Zeroize for StaticFifoU8<N> {
+    fn zeroize(&mut self) {
+        self.clear();
+    }
+}
+
+impl<const N : usize> Zeroize for StaticFifoU32<N> {
+    fn zeroize(&mut self) {
+        self.clear();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use zeroize::Zeroizing;
+    use super::*;
+
+    #[test]
+    fn scrub_storage() {
+        println!("##################### ZEROIZE TEST ######################################");
+
+        let mut key : StaticFifoU8<8> = StaticFifoU8::new();
+        for b in [0xA5u8; 7] {
+            key.put(b).unwrap();
+        }
+        // consumed bytes are still in the storage until scrubbed
+        key.skip(5);
+        key.clear();
+        assert!(key.is_empty() && key.buf.iter().all(|b| *b == 0), "storage not scrubbed");
+
+        let mut words = Zeroizing::new(StaticFifoU32::<4>::new());
+        words.put(0xDEAD_BEEF).unwrap();
+        words.get().unwrap();
+        words.zeroize();
+        assert!(words.is_empty() && words.buf == [0; 4], "zeroize left data");
+    }
+}
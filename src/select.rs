@@ -0,0 +1,211 @@
+////////////////////////////////////////////////////////////////
+// Select / poll over several fifos
+//
+// Event loops that service a handful of queues can hand them
+// all to poll_any() / ready_set() instead of checking each one
+// by hand. Readiness is its own small trait so queues of
+// different element types can be polled together.
+//
+// RoundRobin owns K consumers of one element type and merges
+// them fairly: each get() takes from the source after the one
+// served last, skipping those with nothing to give, so a busy
+// producer's queue can not starve the others.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError, StaticDeque, RateLimiter, Watchdog};
+use crate::spsc::Consumer;
+use crate::traits::FifoConsumer;
+use crate::timed::Clock;
+
+// something that can say whether a get() would return data right now.
+pub trait Ready {
+    fn ready(&self) -> bool;
+}
+
+impl<R : Ready + ?Sized> Ready for &R {
+    fn ready(&self) -> bool {
+        (**self).ready()
+    }
+}
+
+impl<R : Ready + ?Sized> Ready for &mut R {
+    fn ready(&self) -> bool {
+        (**self).ready()
+    }
+}
+
+impl<const N : usize> Ready for StaticFifoU8<N> {
+    fn ready(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl<const N : usize> Ready for StaticFifoU32<N> {
+    fn ready(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl<T : Copy + Default, const N : usize> Ready for StaticDeque<T, N> {
+    fn ready(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl<'a, T : Copy, const N : usize> Ready for Consumer<'a, T, N> {
+    fn ready(&self) -> bool {
+        Consumer::ready(self)
+    }
+}
+
+// a throttled limiter is not ready even with data queued.
+impl<F : Ready> Ready for RateLimiter<F> {
+    fn ready(&self) -> bool {
+        self.tokens() > 0 && self.inner().ready()
+    }
+}
+
+impl<F : Ready, C : Clock> Ready for Watchdog<F, C> {
+    fn ready(&self) -> bool {
+        self.inner().ready()
+    }
+}
+
+// index of the first ready source, None if none are.
+pub fn poll_any(sources : &[&dyn Ready]) -> Option<usize> {
+    sources.iter().position(|s| s.ready())
+}
+
+// like poll_any() but the search starts at start and wraps around. passing the
+// last serviced index + 1 gives round robin, so a busy queue can not starve the rest.
+pub fn poll_any_from(sources : &[&dyn Ready], start : usize) -> Option<usize> {
+    let n = sources.len();
+    (0..n).map(|i| (start + i) % n).find(|&i| sources[i].ready())
+}
+
+// bit i set if sources[i] is ready. only the first 32 sources are looked at.
+pub fn ready_set(sources : &[&dyn Ready]) -> u32 {
+    sources.iter().take(32).enumerate().fold(0, |set, (i, s)| if s.ready() { set | (1 << i) } else { set })
+}
+
+// get from the first ready consumer of the same element type, with its index.
+pub fn get_any<T>(sources : &mut [&mut dyn FifoConsumer<T>]) -> Option<(usize, T)> {
+    sources.iter_mut().enumerate().find_map(|(i, s)| s.get().ok().map(|v| (i, v)))
+}
+
+// fair merge of K consumers.
+pub struct RoundRobin<C, const K : usize> {
+    sources: [C; K],
+    // source to try first on the next get
+    next: usize,
+}
+
+impl<C, const K : usize> RoundRobin<C, K> {
+
+    pub fn new(sources : [C; K]) -> Self {
+        RoundRobin { sources, next: 0 }
+    }
+
+    // next element with the index of the source it came from. sources that
+    // fail (empty, throttled ..) are skipped, Empty if none had anything.
+    pub fn get_from<T>(&mut self) -> Result<(usize, T), StaticFifoError> where C : FifoConsumer<T> {
+        for i in (0..K).map(|i| (self.next + i) % K) {
+            if let Ok(v) = self.sources[i].get() {
+                self.next = (i + 1) % K;
+                return Ok((i, v));
+            }
+        }
+        Err(StaticFifoError::Empty)
+    }
+
+    pub fn source(&self, i : usize) -> Option<&C> {
+        self.sources.get(i)
+    }
+
+    pub fn source_mut(&mut self, i : usize) -> Option<&mut C> {
+        self.sources.get_mut(i)
+    }
+
+    pub fn into_inner(self) -> [C; K] {
+        self.sources
+    }
+}
+
+impl<T, C : FifoConsumer<T>, const K : usize> FifoConsumer<T> for RoundRobin<C, K> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        self.get_from().map(|(_, v)| v)
+    }
+
+    // elements queued over all sources.
+    fn len(&self) -> usize {
+        self.sources.iter().map(|s| s.len()).sum()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn select_ready_queue() {
+        println!("##################### SELECT TEST ######################################");
+
+        let mut uart : StaticFifoU8<8> = StaticFifoU8::new();
+        let mut adc : StaticFifoU32<8> = StaticFifoU32::new();
+        let mut cmd : StaticDeque<u16, 8> = StaticDeque::new();
+
+        assert!(poll_any(&[&uart, &adc, &cmd]).is_none() && ready_set(&[&uart, &adc, &cmd]) == 0, "empty queues ready");
+
+        adc.put(7).unwrap();
+        cmd.push_back(1).unwrap();
+        assert!(poll_any(&[&uart, &adc, &cmd]) == Some(1), "first ready wrong");
+        assert!(ready_set(&[&uart, &adc, &cmd]) == 0b110, "ready set wrong");
+        assert!(poll_any_from(&[&uart, &adc, &cmd], 2) == Some(2), "round robin start ignored");
+        adc.get().unwrap();
+        assert!(poll_any_from(&[&uart, &adc, &cmd], 0) == Some(2), "round robin wrong");
+        cmd.pop_front().unwrap();
+        assert!(poll_any_from(&[&uart, &adc, &cmd], 2).is_none(), "wrap around wrong");
+
+        // a throttled queue is skipped
+        uart.put(b'a').unwrap();
+        uart.put(b'b').unwrap();
+        let mut shaped = RateLimiter::new(&mut uart, 1, 1);
+        shaped.get().unwrap();
+        assert!(poll_any(&[&shaped]).is_none(), "throttled queue ready");
+
+        let mut a : StaticFifoU32<4> = StaticFifoU32::new();
+        let mut b : StaticFifoU32<4> = StaticFifoU32::new();
+        b.put(42).unwrap();
+        assert!(get_any(&mut [&mut a, &mut b]) == Some((1, 42)), "get_any wrong");
+        assert!(get_any(&mut [&mut a, &mut b]).is_none(), "get_any on empty queues");
+    }
+
+    #[test]
+    fn round_robin_merge() {
+        println!("##################### ROUND ROBIN TEST ######################################");
+
+        let mut busy : StaticFifoU32<8> = StaticFifoU32::new();
+        let mut quiet : StaticFifoU32<8> = StaticFifoU32::new();
+        let mut idle : StaticFifoU32<8> = StaticFifoU32::new();
+        for v in 10..15 {
+            busy.put(v).unwrap();
+        }
+        quiet.put(20).unwrap();
+        quiet.put(21).unwrap();
+
+        let mut rr = RoundRobin::new([&mut busy, &mut quiet, &mut idle]);
+        assert!(rr.len() == 7, "len wrong");
+        assert!(rr.get_from() == Ok((0, 10)) && rr.get_from() == Ok((1, 20)), "not taking turns");
+        // idle skipped, back to the start
+        assert!(rr.get_from() == Ok((0, 11)) && rr.get_from() == Ok((1, 21)), "empty source not skipped");
+        rr.source_mut(2).unwrap().put(30).unwrap();
+        assert!(rr.get() == Ok(30) && rr.get() == Ok(12), "late source not served in turn");
+        assert!(rr.get() == Ok(13) && rr.get() == Ok(14), "single busy source wrong");
+        assert!(rr.get() == Err(StaticFifoError::Empty) && rr.is_empty(), "drained merge gave data");
+    }
+}
@@ -0,0 +1,131 @@
+////////////////////////////////////////////////////////////////
+// Send / Sync guarantees
+//
+// Compile time checks of the marker traits on the shared
+// fifos and their split handles, so a change that quietly
+// makes a handle Clone, or lets a single context type cross
+// into a thread / ISR, breaks the build instead of the
+// program. The rules:
+//
+//  - Mailbox / spsc::Queue / BufferQueue / LockedFifo: Sync
+//    (once the element / fifo is Send), meant to sit in a
+//    static.
+//  - their producer / consumer handles: Send, never Clone,
+//    so each side has exactly one owner wherever it moves.
+//  - CellFifo halves: not Send, not Clone. One execution
+//    context only.
+//  - channel() ends (std): Send and Clone, the Mutex makes
+//    any number of senders / receivers fine.
+//
+// A failing positive check is a plain trait bound error. A
+// failing negative check shows up as "type annotations
+// needed": both impls of the probe trait apply, so the
+// compiler cannot pick one.
+//
+///////////////////////////////////////////////////////////////
+
+#![allow(dead_code)]
+
+use core::cell::Cell;
+
+use crate::{StaticFifoU32, StaticFifoError};
+use crate::lock::{Lock, LockedFifo};
+use crate::mailbox::{Fence, Mailbox, MailboxProducer, MailboxConsumer};
+use crate::bufqueue::{BufferQueue, BufProducer, BufConsumer, Packet};
+use crate::cellfifo::{CellFifo, CellProducer, CellConsumer};
+use crate::spsc::{Queue, Producer, Consumer};
+use crate::quota::{QuotaFifo, QuotaProducer};
+
+// ty implements every listed trait.
+macro_rules! assert_impl {
+    ($t:ty : $($tr:path),+) => {
+        const _ : fn() = || {
+            fn check<T : ?Sized $(+ $tr)+>() {}
+            check::<$t>();
+        };
+    };
+}
+
+// ty does not implement the trait.
+macro_rules! assert_not_impl {
+    ($t:ty : $tr:path) => {
+        const _ : fn() = || {
+            trait Probe<A> {
+                fn probe() {}
+            }
+            impl<T : ?Sized> Probe<()> for T {}
+            impl<T : ?Sized + $tr> Probe<u8> for T {}
+            let _ = <$t as Probe<_>>::probe;
+        };
+    };
+}
+
+// lock for the LockedFifo checks, never run.
+struct NoLock;
+
+unsafe impl Lock for NoLock {
+    type Token = ();
+
+    fn acquire(&self) {}
+
+    fn release(&self, _ : ()) {}
+}
+
+type Mb = Mailbox<u32, 8, Fence>;
+type Locked = LockedFifo<StaticFifoU32<8>, NoLock>;
+type Quota = QuotaFifo<StaticFifoU32<8>, 2, 8>;
+
+// mailbox: shared, one owner per side
+assert_impl!(Mb : Send, Sync);
+assert_impl!(MailboxProducer<'static, u32, 8, Fence> : Send);
+assert_impl!(MailboxConsumer<'static, u32, 8, Fence> : Send);
+assert_not_impl!(MailboxProducer<'static, u32, 8, Fence> : Clone);
+assert_not_impl!(MailboxConsumer<'static, u32, 8, Fence> : Clone);
+// elements that cannot move between threads keep it local
+assert_not_impl!(Mailbox<*const u8, 8, Fence> : Sync);
+assert_not_impl!(MailboxProducer<'static, *const u8, 8, Fence> : Send);
+
+// buffer queue: same, and the descriptors move but do not copy
+assert_impl!(BufferQueue<4> : Send, Sync);
+assert_impl!(BufProducer<'static, 4> : Send);
+assert_impl!(BufConsumer<'static, 4> : Send);
+assert_not_impl!(BufProducer<'static, 4> : Clone);
+assert_not_impl!(BufConsumer<'static, 4> : Clone);
+assert_impl!(Packet : Send);
+assert_not_impl!(Packet : Clone);
+
+// locked fifo: shared once the fifo can move
+assert_impl!(Locked : Send, Sync);
+assert_not_impl!(LockedFifo<StaticFifoU32<8>, Cell<bool>> : Sync);
+assert_impl!(QuotaProducer<'static, StaticFifoU32<8>, NoLock, 2, 8> : Send);
+assert_not_impl!(QuotaProducer<'static, StaticFifoU32<8>, NoLock, 2, 8> : Clone);
+assert_impl!(LockedFifo<Quota, NoLock> : Sync);
+
+// spsc shim: the same handoff as the mailbox
+assert_impl!(Queue<u32, 8> : Send, Sync);
+assert_impl!(Producer<'static, u32, 8> : Send);
+assert_impl!(Consumer<'static, u32, 8> : Send);
+assert_not_impl!(Producer<'static, u32, 8> : Clone);
+assert_not_impl!(Consumer<'static, u32, 8> : Clone);
+assert_not_impl!(Queue<*const u8, 8> : Sync);
+assert_not_impl!(Producer<'static, *const u8, 8> : Send);
+
+assert_impl!(CellFifo<u32, 8> : Send);
+assert_not_impl!(CellFifo<u32, 8> : Sync);
+assert_not_impl!(CellProducer<'static, u32, 8> : Send);
+assert_not_impl!(CellConsumer<'static, u32, 8> : Send);
+assert_not_impl!(CellProducer<'static, u32, 8> : Clone);
+assert_not_impl!(CellConsumer<'static, u32, 8> : Clone);
+
+// plain fifos and the error move freely
+assert_impl!(StaticFifoU32<8> : Send, Sync);
+assert_impl!(StaticFifoError : Send, Sync, Copy);
+
+#[cfg(feature = "std")]
+mod channel {
+    use crate::StaticFifoU32;
+    use crate::channel::{Sender, Receiver};
+
+    assert_impl!(Sender<u32, StaticFifoU32<8>> : Send, Sync, Clone);
+    assert_impl!(Receiver<u32, StaticFifoU32<8>> : Send, Sync, Clone);
+}
@@ -0,0 +1,150 @@
+////////////////////////////////////////////////////////////////
+// Sequence numbers
+//
+// Sequenced wraps a fifo of (u32, T) and stamps every element
+// put with the next number of a wrapping u32 counter; get()
+// hands the number back with the data. A SeqTracker on the
+// consumer side compares each number with the one it expected,
+// so data lost or reordered on the way (a lossy overwrite fifo,
+// a hop between cores) shows up as a Gap / Stale instead of
+// going unnoticed.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+pub struct Sequenced<F> {
+    fifo: F,
+    next: u32,
+}
+
+impl<F> Sequenced<F> {
+
+    pub fn new(fifo : F) -> Self {
+        Sequenced { fifo, next: 0 }
+    }
+
+    // the number the next put will get.
+    pub fn next_seq(&self) -> u32 {
+        self.next
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.fifo
+    }
+
+    pub fn inner_mut(&mut self) -> &mut F {
+        &mut self.fifo
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo
+    }
+}
+
+// a number is only used up when the put goes through.
+impl<T, F : FifoProducer<(u32, T)>> FifoProducer<T> for Sequenced<F> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        self.fifo.put((self.next, data))?;
+        self.next = self.next.wrapping_add(1);
+        Ok(())
+    }
+
+    fn free_space(&self) -> usize {
+        self.fifo.free_space()
+    }
+}
+
+impl<T, F : FifoConsumer<(u32, T)>> FifoConsumer<(u32, T)> for Sequenced<F> {
+
+    fn get(&mut self) -> Result<(u32, T), StaticFifoError> {
+        self.fifo.get()
+    }
+
+    fn len(&self) -> usize {
+        self.fifo.len()
+    }
+}
+
+// what a sequence number says about the path it came over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeqCheck {
+    // the one expected
+    InOrder,
+    // this many numbers were skipped, i.e. elements lost
+    Gap(u32),
+    // older than expected by this many, duplicated or reordered
+    Stale(u32),
+}
+
+// consumer side bookkeeping for the numbers of one Sequenced producer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SeqTracker {
+    expected: Option<u32>,
+    lost: u32,
+}
+
+impl SeqTracker {
+
+    pub const fn new() -> Self {
+        SeqTracker { expected: None, lost: 0 }
+    }
+
+    // check seq against the last one seen. the first number seen is always in order.
+    // wrapping math, distances of up to 2^31 either way are told apart.
+    pub fn observe(&mut self, seq : u32) -> SeqCheck {
+        let expected = self.expected.unwrap_or(seq);
+        let ahead = seq.wrapping_sub(expected);
+        if ahead >= 0x8000_0000 {
+            return SeqCheck::Stale(expected.wrapping_sub(seq));
+        }
+        self.expected = Some(seq.wrapping_add(1));
+        if ahead == 0 {
+            SeqCheck::InOrder
+        } else {
+            self.lost = self.lost.wrapping_add(ahead);
+            SeqCheck::Gap(ahead)
+        }
+    }
+
+    // elements lost over all gaps seen.
+    pub fn lost(&self) -> u32 {
+        self.lost
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+    use crate::StaticDeque;
+
+    #[test]
+    fn gaps_and_reordering() {
+        println!("##################### SEQUENCE TEST ######################################");
+
+        let mut fifo : Sequenced<StaticDeque<(u32, u16), 8>> = Sequenced::new(StaticDeque::new());
+        for v in [10, 11, 12, 13] {
+            fifo.put(v).unwrap();
+        }
+        assert!(fifo.next_seq() == 4, "numbers not handed out");
+
+        // lose one on the way
+        let mut rx = SeqTracker::new();
+        assert!(fifo.get() == Ok((0, 10)) && rx.observe(0) == SeqCheck::InOrder, "first element wrong");
+        fifo.get().unwrap();
+        let (seq, v) = fifo.get().unwrap();
+        assert!(v == 12 && rx.observe(seq) == SeqCheck::Gap(1), "gap not seen");
+        assert!(rx.observe(1) == SeqCheck::Stale(2), "late element not seen");
+        assert!(rx.observe(3) == SeqCheck::InOrder && rx.lost() == 1, "order after late element wrong");
+
+        // numbers wrap
+        let mut rx = SeqTracker::new();
+        assert!(rx.observe(u32::MAX) == SeqCheck::InOrder && rx.observe(0) == SeqCheck::InOrder, "wrap not in order");
+        assert!(rx.observe(3) == SeqCheck::Gap(2), "gap after wrap wrong");
+    }
+}
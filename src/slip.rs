@@ -0,0 +1,189 @@
+////////////////////////////////////////////////////////////////
+// SLIP framing
+//
+// Serial Line IP (RFC 1055) framing on top of StaticFifoU8.
+// put_frame() escapes a frame into a tx fifo between END
+// bytes. get_frame() pulls one complete frame back out of an
+// rx fifo and undoes the escaping.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoError};
+
+pub const END : u8 = 0xC0;
+pub const ESC : u8 = 0xDB;
+pub const ESC_END : u8 = 0xDC;
+pub const ESC_ESC : u8 = 0xDD;
+
+// worst case encoded size of a len byte frame, including both END bytes.
+pub const fn max_encoded_len(len : usize) -> usize {
+    (len * 2) + 2
+}
+
+// encode data as one frame into the fifo.
+// a leading END flushes any line noise at the receiver, as RFC 1055 suggests.
+// all or nothing, returns Full without touching the fifo if the frame does not fit.
+pub fn put_frame<const N : usize>(fifo : &mut StaticFifoU8<N>, data : &[u8]) -> Result<(), StaticFifoError> {
+    let escapes = data.iter().filter(|&&b| b == END || b == ESC).count();
+    if data.len() + escapes + 2 > fifo.free_space() {
+        return Err(StaticFifoError::Full);
+    }
+    // space was checked above, puts can not fail.
+    let _ = fifo.put(END);
+    for b in data {
+        match *b {
+            END => { let _ = fifo.put(ESC); let _ = fifo.put(ESC_END); },
+            ESC => { let _ = fifo.put(ESC); let _ = fifo.put(ESC_ESC); },
+            v => { let _ = fifo.put(v); },
+        }
+    }
+    let _ = fifo.put(END);
+    Ok(())
+}
+
+// true if a complete frame is queued and get_frame() will return it (or drop it as bad).
+pub fn frame_ready<const N : usize>(fifo : &StaticFifoU8<N>) -> bool {
+    let mut start : usize = 0;
+    while fifo.peek_at(start) == Ok(END) {
+        start += 1;
+    }
+    (start..fifo.len()).any(|i| fifo.peek_at(i) == Ok(END))
+}
+
+// undo the escaping of the bytes in front of the END at offset end.
+fn decode<const N : usize>(fifo : &StaticFifoU8<N>, end : usize, out : &mut [u8]) -> Result<usize, StaticFifoError> {
+    let mut i : usize = 0;
+    let mut n : usize = 0;
+    while i < end {
+        let mut b = fifo.peek_at(i)?;
+        i += 1;
+        if b == ESC {
+            if i >= end {
+                return Err(StaticFifoError::Malformed);
+            }
+            b = match fifo.peek_at(i)? {
+                ESC_END => END,
+                ESC_ESC => ESC,
+                _ => return Err(StaticFifoError::Malformed),
+            };
+            i += 1;
+        }
+        if n >= out.len() {
+            return Err(StaticFifoError::TooLarge);
+        }
+        out[n] = b;
+        n += 1;
+    }
+    Ok(n)
+}
+
+// pull one complete frame out of the fifo and decode it into out, returns decoded length.
+// Empty if no complete frame is queued yet (partial frames are left alone).
+// a frame that is Malformed or TooLarge for out is dropped so the link stays in sync.
+// a full fifo with no END in it can never complete a frame, it is dropped as Malformed.
+pub fn get_frame<const N : usize>(fifo : &mut StaticFifoU8<N>, out : &mut [u8]) -> Result<usize, StaticFifoError> {
+    // back to back END bytes carry nothing, drop them.
+    while fifo.peek_at(0) == Ok(END) {
+        fifo.skip(1);
+    }
+    let end = match fifo.find(END) {
+        Some(e) => e,
+        None if fifo.is_full() => {
+            fifo.skip(fifo.len());
+            return Err(StaticFifoError::Malformed);
+        },
+        None => return Err(StaticFifoError::Empty),
+    };
+    let rv = decode(fifo, end, out);
+    fifo.skip(end + 1);
+    rv
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    fn new_fifo() -> StaticFifoU8<64> {
+        StaticFifoU8::<64> { buf: [0;64], read_ptr: 0, write_ptr: 0, capacity: 64, soft_cap: usize::MAX }
+    }
+
+    #[test]
+    fn slip_round_trip() {
+        println!("##################### SLIP ROUND TRIP TEST ######################################");
+
+        let mut fifo = new_fifo();
+        let mut out = [0u8; 16];
+
+        let data = [0x01, END, 0x02, ESC, 0x03];
+        put_frame(&mut fifo, &data).unwrap();
+        let expect = [END, 0x01, ESC, ESC_END, 0x02, ESC, ESC_ESC, 0x03, END];
+        assert!(fifo.len() == expect.len(), "encoded length wrong");
+        for (i, b) in expect.iter().enumerate() {
+            assert!(fifo.peek_at(i) == Ok(*b), "encoded byte {} wrong", i);
+        }
+        assert!(frame_ready(&fifo), "complete frame not detected");
+        assert!(get_frame(&mut fifo, &mut out) == Ok(data.len()), "decoded length wrong");
+        assert!(out[..data.len()] == data, "decoded data wrong");
+        assert!(fifo.is_empty(), "fifo should be empty after reading the only frame");
+        assert!(!frame_ready(&fifo), "frame reported on empty fifo");
+
+        // frame that does not fit leaves the fifo untouched
+        let mut small = StaticFifoU8::<8> { buf: [0;8], read_ptr: 0, write_ptr: 0, capacity: 8, soft_cap: usize::MAX };
+        assert!(put_frame(&mut small, &[END, ESC, 1, 2]) == Err(StaticFifoError::Full), "oversized put accepted");
+        assert!(small.is_empty(), "failed put modified the fifo");
+        assert!(put_frame(&mut small, &[END, ESC, 1]) == Ok(()), "frame that fits exactly was rejected");
+        assert!(small.is_full(), "fifo should be exactly full");
+    }
+
+    #[test]
+    fn slip_rx_side() {
+        println!("##################### SLIP RX TEST ######################################");
+
+        let mut fifo = new_fifo();
+        let mut out = [0u8; 4];
+
+        // partial frame stays queued until its END arrives
+        for b in [END, END, 0x11, ESC] {
+            fifo.put(b).unwrap();
+        }
+        assert!(!frame_ready(&fifo), "partial frame reported ready");
+        assert!(get_frame(&mut fifo, &mut out) == Err(StaticFifoError::Empty), "partial frame returned");
+        assert!(fifo.len() == 2, "partial frame should be left queued");
+        fifo.put(ESC_END).unwrap();
+        fifo.put(END).unwrap();
+        assert!(get_frame(&mut fifo, &mut out) == Ok(2) && out[..2] == [0x11, END], "completed frame not returned");
+
+        // bad escape is malformed and dropped
+        for b in [0x11, ESC, 0x00, END, 0x44, END] {
+            fifo.put(b).unwrap();
+        }
+        assert!(get_frame(&mut fifo, &mut out) == Err(StaticFifoError::Malformed), "bad escape accepted");
+        assert!(get_frame(&mut fifo, &mut out) == Ok(1) && out[0] == 0x44, "frame after malformed one lost");
+
+        // frame bigger than the output buffer is dropped
+        put_frame(&mut fifo, &[1, 2, 3, 4, 5]).unwrap();
+        put_frame(&mut fifo, &[6]).unwrap();
+        assert!(get_frame(&mut fifo, &mut out) == Err(StaticFifoError::TooLarge), "oversized frame accepted");
+        assert!(get_frame(&mut fifo, &mut out) == Ok(1) && out[0] == 6, "frame after oversized one lost");
+        assert!(fifo.is_empty(), "fifo should be empty");
+    }
+
+    #[test]
+    fn slip_full_without_end() {
+        println!("##################### SLIP FULL RX TEST ######################################");
+
+        // line noise fills the rx fifo without an END
+        let mut fifo = new_fifo();
+        let mut out = [0u8; 4];
+        while fifo.put(0x55).is_ok() {}
+        assert!(get_frame(&mut fifo, &mut out) == Err(StaticFifoError::Malformed), "wedged fifo not reported");
+        assert!(fifo.is_empty(), "wedged fifo not dropped");
+
+        // decoder picks up again with the next frame
+        put_frame(&mut fifo, &[0x11, END]).unwrap();
+        assert!(get_frame(&mut fifo, &mut out) == Ok(2) && out[..2] == [0x11, END], "frame after wedged fifo lost");
+    }
+}
@@ -0,0 +1,384 @@
+////////////////////////////////////////////////////////////////
+// heapless::spsc compatible shim
+//
+// Queue with the method names and semantics of
+// heapless::spsc::Queue (enqueue / dequeue / peek / split,
+// Producer / Consumer halves with ready()), so code written
+// against heapless can switch over by changing the import.
+//
+// Like heapless, a Queue<T, N> holds N-1 elements. The indices
+// are handed over the same way as in Mailbox: each one is
+// owned by one side and only loaded / stored, the producer
+// publishes write_ptr with release after filling the slot, the
+// consumer publishes read_ptr with release after reading it.
+// So the split halves are Send (once T is) and can sit in two
+// threads or a thread and an interrupt. Neither is Clone, each
+// side keeps exactly one owner (see sendsync.rs for the checks).
+//
+// Built with --cfg loom, the indices and slots come from loom
+// instead, and the loom tests at the bottom run the producer /
+// consumer handoff through every interleaving and weak memory
+// ordering loom models (host only):
+//
+//   RUSTFLAGS="--cfg loom" cargo test --release --lib spsc::loom
+//
+///////////////////////////////////////////////////////////////
+
+use core::mem::MaybeUninit;
+#[cfg(not(loom))]
+use core::cell::UnsafeCell;
+#[cfg(not(loom))]
+use crate::atomic::{AtomicUsize, Ordering};
+#[cfg(loom)]
+use loom::cell::UnsafeCell;
+#[cfg(loom)]
+use loom::sync::atomic::{AtomicUsize, Ordering};
+
+// one element slot. loom's UnsafeCell only hands out pointers inside a closure (so it
+// can see the accesses), this keeps the queue code the same for both.
+struct Slot<T>(UnsafeCell<MaybeUninit<T>>);
+
+impl<T : Copy> Slot<T> {
+
+    #[cfg(not(loom))]
+    const fn new() -> Self {
+        Slot(UnsafeCell::new(MaybeUninit::uninit()))
+    }
+
+    #[cfg(loom)]
+    fn new() -> Self {
+        Slot(UnsafeCell::new(MaybeUninit::uninit()))
+    }
+
+    // only by the side that owns the slot right now.
+    #[cfg(not(loom))]
+    unsafe fn write(&self, item : T) {
+        *self.0.get() = MaybeUninit::new(item);
+    }
+
+    #[cfg(loom)]
+    unsafe fn write(&self, item : T) {
+        self.0.with_mut(|p| *p = MaybeUninit::new(item));
+    }
+
+    // only after a write, by the side that owns the slot right now.
+    #[cfg(not(loom))]
+    unsafe fn get(&self) -> &T {
+        (*self.0.get()).assume_init_ref()
+    }
+
+    #[cfg(loom)]
+    unsafe fn get(&self) -> &T {
+        self.0.with(|p| (*p).assume_init_ref())
+    }
+}
+
+pub struct Queue<T, const N : usize> {
+    // owned by the consumer
+    read_ptr: AtomicUsize,
+    // owned by the producer
+    write_ptr: AtomicUsize,
+    buf: [Slot<T>; N],
+}
+
+// only the one producer writes a slot, and only while it is outside read_ptr..write_ptr;
+// the one consumer only reads slots inside it. Both need &mut (of the queue or their half).
+unsafe impl<T : Send, const N : usize> Sync for Queue<T, N> {}
+
+impl<T : Copy, const N : usize> Default for Queue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T : Copy, const N : usize> Queue<T, N> {
+
+    // one slot is the full marker, so below 2 the queue could never hold anything.
+    const CAPACITY_OK : () = assert!(N >= 2, "spsc::Queue needs N >= 2, one slot is lost to the full marker");
+
+    #[cfg(not(loom))]
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY_OK;
+        Queue {
+            read_ptr: AtomicUsize::new(0),
+            write_ptr: AtomicUsize::new(0),
+            buf: [const { Slot::new() }; N],
+        }
+    }
+
+    // loom's cells and atomics can not be built in a const fn.
+    #[cfg(loom)]
+    pub fn new() -> Self {
+        let () = Self::CAPACITY_OK;
+        Queue {
+            read_ptr: AtomicUsize::new(0),
+            write_ptr: AtomicUsize::new(0),
+            buf: core::array::from_fn(|_| Slot::new()),
+        }
+    }
+
+    // elements the queue can hold, one less than N.
+    pub const fn capacity(&self) -> usize {
+        N - 1
+    }
+
+    pub fn len(&self) -> usize {
+        let w = self.write_ptr.load(Ordering::Acquire);
+        let r = self.read_ptr.load(Ordering::Acquire);
+        (w + N - r) % N
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == N - 1
+    }
+
+    // queue item, gives it back if the queue is full.
+    pub fn enqueue(&mut self, item : T) -> Result<(), T> {
+        self.push(item)
+    }
+
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.front()
+    }
+
+    // split into a producer and a consumer half.
+    pub fn split(&mut self) -> (Producer<'_, T, N>, Consumer<'_, T, N>) {
+        (Producer { q: self }, Consumer { q: self })
+    }
+
+    // producer side, called through &mut self or the one Producer only.
+    fn push(&self, item : T) -> Result<(), T> {
+        let w = self.write_ptr.load(Ordering::Relaxed);
+        let next = (w + 1) % N;
+        if next == self.read_ptr.load(Ordering::Acquire) {
+            return Err(item);
+        }
+        // the consumer is done with this slot, it moved read_ptr past it.
+        unsafe { self.buf[w].write(item) };
+        self.write_ptr.store(next, Ordering::Release);
+        Ok(())
+    }
+
+    // consumer side, called through &mut self or the one Consumer only.
+    fn pop(&self) -> Option<T> {
+        let r = self.read_ptr.load(Ordering::Relaxed);
+        if r == self.write_ptr.load(Ordering::Acquire) {
+            return None;
+        }
+        // written by the producer before it published write_ptr past it.
+        let rv = unsafe { *self.buf[r].get() };
+        self.read_ptr.store((r + 1) % N, Ordering::Release);
+        Some(rv)
+    }
+
+    // the slot stays put until read_ptr moves, which takes &mut of the consumer side.
+    fn front(&self) -> Option<&T> {
+        let r = self.read_ptr.load(Ordering::Relaxed);
+        if r == self.write_ptr.load(Ordering::Acquire) {
+            return None;
+        }
+        Some(unsafe { self.buf[r].get() })
+    }
+}
+
+// write half of a split Queue.
+pub struct Producer<'a, T, const N : usize> {
+    q: &'a Queue<T, N>,
+}
+
+impl<'a, T : Copy, const N : usize> Producer<'a, T, N> {
+
+    pub fn enqueue(&mut self, item : T) -> Result<(), T> {
+        self.q.push(item)
+    }
+
+    // true if there is room for an item.
+    pub fn ready(&self) -> bool {
+        !self.q.is_full()
+    }
+
+    pub fn len(&self) -> usize {
+        self.q.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        N - 1
+    }
+}
+
+// read half of a split Queue.
+pub struct Consumer<'a, T, const N : usize> {
+    q: &'a Queue<T, N>,
+}
+
+impl<'a, T : Copy, const N : usize> Consumer<'a, T, N> {
+
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.q.pop()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.q.front()
+    }
+
+    // true if there is an item to dequeue.
+    pub fn ready(&self) -> bool {
+        !self.q.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.q.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        N - 1
+    }
+}
+
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn heapless_style_queue() {
+        println!("##################### SPSC SHIM TEST ######################################");
+
+        let mut q : Queue<u16, 4> = Queue::new();
+
+        assert!(q.capacity() == 3 && q.dequeue().is_none(), "new queue wrong");
+        q.enqueue(1).unwrap();
+        q.enqueue(2).unwrap();
+        q.enqueue(3).unwrap();
+        assert!(q.enqueue(4) == Err(4), "full queue did not give the item back");
+        assert!(q.peek() == Some(&1) && q.dequeue() == Some(1), "dequeue wrong");
+
+        {
+            let (mut p, mut c) = q.split();
+            assert!(p.ready() && c.ready(), "ready flags wrong");
+            p.enqueue(4).unwrap();
+            assert!(!p.ready() && p.len() == 3, "producer view wrong");
+            assert!(c.peek() == Some(&2), "consumer peek wrong");
+            assert!(c.dequeue() == Some(2) && c.dequeue() == Some(3) && c.dequeue() == Some(4), "consumer order wrong");
+            assert!(!c.ready() && c.dequeue().is_none(), "consumer not empty");
+        }
+        assert!(q.is_empty(), "queue not empty after split use");
+    }
+    #[test]
+    fn split_across_threads() {
+        println!("##################### SPSC THREADS TEST ######################################");
+
+        let mut q : Queue<u32, 8> = Queue::new();
+        let (mut p, mut c) = q.split();
+        std::thread::scope(|s| {
+            s.spawn(move || {
+                for i in 0..10_000u32 {
+                    while p.enqueue(i).is_err() {
+                        std::thread::yield_now();
+                    }
+                }
+            });
+            let mut expect = 0u32;
+            while expect < 10_000 {
+                match c.dequeue() {
+                    Some(v) => { assert!(v == expect, "order lost across threads"); expect += 1; },
+                    None => std::thread::yield_now(),
+                }
+            }
+        });
+        assert!(q.is_empty(), "queue not drained");
+    }
+}
+
+
+// every interleaving of one producer and one consumer thread. the queue is leaked, loom
+// has no scoped threads and the producer half borrows it for the spawned thread.
+#[cfg(all(test, loom))]
+mod loom_tests {
+
+    use std::boxed::Box;
+    use loom::thread;
+    use super::*;
+
+    #[test]
+    fn loom_handoff_in_order() {
+        loom::model(|| {
+            let q : &'static mut Queue<u32, 3> = Box::leak(Box::new(Queue::new()));
+            let (mut p, mut c) = q.split();
+            let producer = thread::spawn(move || {
+                p.enqueue(1).unwrap();
+                p.enqueue(2).unwrap();
+            });
+            // a slot read before its write is visible shows up as a loom data race
+            let mut expect = 1;
+            while expect <= 2 {
+                match c.dequeue() {
+                    Some(v) => { assert!(v == expect, "order lost"); expect += 1; },
+                    None => thread::yield_now(),
+                }
+            }
+            producer.join().unwrap();
+            assert!(c.dequeue().is_none(), "queue not drained");
+        });
+    }
+
+    #[test]
+    fn loom_slot_reuse_when_full() {
+        loom::model(|| {
+            // one element, every enqueue reuses the slot the consumer just left
+            let q : &'static mut Queue<u32, 2> = Box::leak(Box::new(Queue::new()));
+            let (mut p, mut c) = q.split();
+            let producer = thread::spawn(move || {
+                for i in 0..3 {
+                    while p.enqueue(i).is_err() {
+                        thread::yield_now();
+                    }
+                }
+            });
+            let mut expect = 0;
+            while expect < 3 {
+                match c.dequeue() {
+                    Some(v) => { assert!(v == expect, "slot reused too early"); expect += 1; },
+                    None => thread::yield_now(),
+                }
+            }
+            producer.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn loom_peek_during_enqueue() {
+        loom::model(|| {
+            let q : &'static mut Queue<u32, 3> = Box::leak(Box::new(Queue::new()));
+            let (mut p, c) = q.split();
+            let producer = thread::spawn(move || {
+                p.enqueue(7).unwrap();
+                p.enqueue(8).unwrap();
+            });
+            // the front element never changes under a peek, whatever the producer does
+            if let Some(v) = c.peek() {
+                assert!(*v == 7, "peek saw a later element");
+            }
+            producer.join().unwrap();
+            assert!(c.peek() == Some(&7) && c.len() == 2, "queue wrong after the producer");
+        });
+    }
+}
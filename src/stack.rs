@@ -0,0 +1,133 @@
+////////////////////////////////////////////////////////////////
+// StaticStack
+//
+// Fixed capacity LIFO stack, the same no dynamic memory deal
+// as the fifos. Unlike the fifos no slot is lost to a full
+// marker, a StaticStack<T, N> holds N elements.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+
+pub struct StaticStack<T, const N : usize> {
+    buf: [T; N],
+    top: usize,
+}
+
+impl<T : Copy + Default, const N : usize> Default for StaticStack<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T : Copy + Default, const N : usize> StaticStack<T, N> {
+
+    pub fn new() -> Self {
+        Self::filled(T::default())
+    }
+}
+
+impl<T : Copy, const N : usize> StaticStack<T, N> {
+
+    // empty stack with the unused storage set to fill, usable in a const / static.
+    pub const fn filled(fill : T) -> Self {
+        StaticStack { buf: [fill; N], top: 0 }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.top = 0;
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.top == 0
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.top == N
+    }
+
+    pub fn len(&self) -> usize {
+        self.top
+    }
+
+    pub fn max_len(&self) -> usize {
+        N
+    }
+
+    // number of elements that can still be pushed before the stack is full.
+    pub fn free_space(&self) -> usize {
+        N - self.top
+    }
+
+    #[inline]
+    pub fn push(&mut self, data : T) -> Result<(), StaticFifoError> {
+        if self.is_full() {
+            return Err(StaticFifoError::Full);
+        }
+        self.buf[self.top] = data;
+        self.top += 1;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn pop(&mut self) -> Result<T, StaticFifoError> {
+        let rv = self.peek()?;
+        self.top -= 1;
+        Ok(rv)
+    }
+
+    // the top element without removing it.
+    #[inline]
+    pub fn peek(&self) -> Result<T, StaticFifoError> {
+        self.peek_at(0)
+    }
+
+    // look at the element i places down from the top without removing it.
+    #[inline]
+    pub fn peek_at(&self, i : usize) -> Result<T, StaticFifoError> {
+        if i >= self.top {
+            return Err(StaticFifoError::Empty);
+        }
+        Ok(self.buf[self.top - 1 - i])
+    }
+
+    // elements bottom to top.
+    pub fn as_slice(&self) -> &[T] {
+        &self.buf[..self.top]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    const EMPTY_STACK : StaticStack<u32, 3> = StaticStack::filled(0);
+
+    #[test]
+    fn stack_lifo() {
+        println!("##################### STACK TEST ######################################");
+
+        let mut st : StaticStack<u32, 3> = StaticStack::new();
+
+        assert!(st.pop() == Err(StaticFifoError::Empty), "pop on empty stack");
+        for v in [1, 2, 3] {
+            st.push(v).unwrap();
+        }
+        assert!(st.is_full() && st.push(4) == Err(StaticFifoError::Full), "push on full stack");
+        assert!(st.peek() == Ok(3) && st.peek_at(2) == Ok(1) && st.peek_at(3).is_err(), "peek wrong");
+        assert!(st.as_slice() == [1, 2, 3], "slice order wrong");
+        assert!(st.pop() == Ok(3) && st.pop() == Ok(2), "pop order wrong");
+        assert!(st.len() == 1 && st.free_space() == 2, "length wrong");
+
+        // const construction
+        let mut cs = EMPTY_STACK;
+        cs.push(7).unwrap();
+        assert!(cs.pop() == Ok(7) && cs.is_empty(), "static stack wrong");
+    }
+}
@@ -0,0 +1,93 @@
+////////////////////////////////////////////////////////////////
+// Statistics over the queued data
+//
+// min() / max() / mean() over what is queued right now, for
+// control loops that want a look at the buffered sensor data
+// without draining it. Each is one pass over as_slices(), so
+// the cost grows with len() and nothing extra is kept per put.
+// None on an empty fifo. The sum is kept in a u64, so it
+// cannot overflow at any fifo size; the mean rounds down.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32};
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    pub fn min(&self) -> Option<u8> {
+        let (a, b) = self.as_slices();
+        a.iter().chain(b).copied().min()
+    }
+
+    pub fn max(&self) -> Option<u8> {
+        let (a, b) = self.as_slices();
+        a.iter().chain(b).copied().max()
+    }
+
+    // sum of the queued bytes.
+    pub fn sum(&self) -> u64 {
+        let (a, b) = self.as_slices();
+        a.iter().chain(b).map(|v| *v as u64).sum()
+    }
+
+    pub fn mean(&self) -> Option<u8> {
+        self.sum().checked_div(self.len() as u64).map(|m| m as u8)
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    pub fn min(&self) -> Option<u32> {
+        let (a, b) = self.as_slices();
+        a.iter().chain(b).copied().min()
+    }
+
+    pub fn max(&self) -> Option<u32> {
+        let (a, b) = self.as_slices();
+        a.iter().chain(b).copied().max()
+    }
+
+    // sum of the queued words.
+    pub fn sum(&self) -> u64 {
+        let (a, b) = self.as_slices();
+        a.iter().chain(b).map(|v| *v as u64).sum()
+    }
+
+    pub fn mean(&self) -> Option<u32> {
+        self.sum().checked_div(self.len() as u64).map(|m| m as u32)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn queued_statistics() {
+        println!("##################### QUEUED STATISTICS TEST ######################################");
+
+        let mut temps : StaticFifoU32<5> = StaticFifoU32::new();
+        assert!(temps.min().is_none() && temps.mean().is_none(), "empty fifo has statistics");
+
+        // wrap the data
+        for v in [9, 9, 210, 190] {
+            temps.put(v).unwrap();
+        }
+        temps.get().unwrap();
+        temps.get().unwrap();
+        temps.put(205).unwrap();
+        assert!(temps.min() == Some(190) && temps.max() == Some(210), "min / max wrong");
+        assert!(temps.mean() == Some(201) && temps.len() == 3, "mean wrong or data consumed");
+        temps.put(u32::MAX).unwrap();
+        assert!(temps.max() == Some(u32::MAX) && temps.sum() == 605 + u32::MAX as u64, "sum overflowed");
+
+        let mut adc : StaticFifoU8<8> = StaticFifoU8::new();
+        for v in [255, 255, 254] {
+            adc.put(v).unwrap();
+        }
+        assert!(adc.mean() == Some(254) && adc.min() == Some(254), "byte statistics wrong");
+    }
+}
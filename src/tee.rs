@@ -0,0 +1,110 @@
+////////////////////////////////////////////////////////////////
+// Tee
+//
+// Producer side adapter that copies every put into D fifos,
+// e.g. one feeding the radio and one feeding an SD card log.
+// Each destination succeeds or overflows on its own, a full
+// logger never stops the radio from getting data, and the
+// elements each destination dropped are counted.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError};
+
+pub struct TeeU8<'a, const N : usize, const D : usize> {
+    sinks: [&'a mut StaticFifoU8<N>; D],
+    dropped: [usize; D],
+}
+
+impl<'a, const N : usize, const D : usize> TeeU8<'a, N, D> {
+
+    pub fn new(sinks : [&'a mut StaticFifoU8<N>; D]) -> Self {
+        TeeU8 { sinks, dropped: [0; D] }
+    }
+
+    // put data into every destination, returns how each one went.
+    pub fn put(&mut self, data : u8) -> [Result<(), StaticFifoError>; D] {
+        core::array::from_fn(|i| {
+            let rv = self.sinks[i].put(data);
+            if rv.is_err() {
+                self.dropped[i] += 1;
+            }
+            rv
+        })
+    }
+
+    // elements destination i has dropped for being full.
+    pub fn dropped(&self, i : usize) -> usize {
+        self.dropped[i]
+    }
+
+    // give the destinations back.
+    pub fn into_inner(self) -> [&'a mut StaticFifoU8<N>; D] {
+        self.sinks
+    }
+}
+
+pub struct TeeU32<'a, const N : usize, const D : usize> {
+    sinks: [&'a mut StaticFifoU32<N>; D],
+    dropped: [usize; D],
+}
+
+impl<'a, const N : usize, const D : usize> TeeU32<'a, N, D> {
+
+    pub fn new(sinks : [&'a mut StaticFifoU32<N>; D]) -> Self {
+        TeeU32 { sinks, dropped: [0; D] }
+    }
+
+    // put data into every destination, returns how each one went.
+    pub fn put(&mut self, data : u32) -> [Result<(), StaticFifoError>; D] {
+        core::array::from_fn(|i| {
+            let rv = self.sinks[i].put(data);
+            if rv.is_err() {
+                self.dropped[i] += 1;
+            }
+            rv
+        })
+    }
+
+    // elements destination i has dropped for being full.
+    pub fn dropped(&self, i : usize) -> usize {
+        self.dropped[i]
+    }
+
+    // give the destinations back.
+    pub fn into_inner(self) -> [&'a mut StaticFifoU32<N>; D] {
+        self.sinks
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn tee_fan_out() {
+        println!("##################### TEE TEST ######################################");
+
+        let mut radio : StaticFifoU8<4> = StaticFifoU8::new();
+        let mut log : StaticFifoU8<4> = StaticFifoU8::new();
+        log.put(0).unwrap();
+        log.put(0).unwrap();
+
+        let mut tee = TeeU8::new([&mut radio, &mut log]);
+        assert!(tee.put(1) == [Ok(()), Ok(())], "first put failed");
+        assert!(tee.put(2) == [Ok(()), Err(StaticFifoError::Full)], "overflow not reported per destination");
+        assert!(tee.put(3) == [Ok(()), Err(StaticFifoError::Full)], "overflow not reported per destination");
+        assert!(tee.dropped(0) == 0 && tee.dropped(1) == 2, "drop counts wrong");
+        let [radio, log] = tee.into_inner();
+        assert!(radio.len() == 3 && log.len() == 3, "destination lengths wrong");
+
+        let mut a : StaticFifoU32<4> = StaticFifoU32::new();
+        let mut b : StaticFifoU32<4> = StaticFifoU32::new();
+        let mut tee = TeeU32::new([&mut a, &mut b]);
+        assert!(tee.put(9) == [Ok(()), Ok(())], "word put failed");
+        assert!(a.get() == Ok(9) && b.get() == Ok(9), "word not copied to both");
+    }
+}
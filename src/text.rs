@@ -0,0 +1,88 @@
+////////////////////////////////////////////////////////////////
+// UTF-8 text
+//
+// push_str() queues text, read_str_into() hands text back but
+// only ever as complete, valid UTF-8. A character whose bytes
+// have not all arrived yet stays queued until they have, and
+// bytes that can never be valid UTF-8 are dropped and reported
+// as Malformed, so CLI / display code needs no checks of its own.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoError};
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // queue s, all or nothing. Full if it does not fit.
+    pub fn push_str(&mut self, s : &str) -> Result<(), StaticFifoError> {
+        self.put_all(s.as_bytes())
+    }
+
+    // move as much complete, valid text as fits into out and return it as a str.
+    // a partial character at the end is left queued (out should be at least 4 bytes
+    // so any character fits). Empty if nothing is queued, Malformed if the next bytes
+    // are not valid UTF-8, those bytes are dropped.
+    pub fn read_str_into<'a>(&mut self, out : &'a mut [u8]) -> Result<&'a str, StaticFifoError> {
+        if self.is_empty() {
+            return Err(StaticFifoError::Empty);
+        }
+        let n = self.copy_to_slice(out);
+        let valid = match core::str::from_utf8(&out[..n]) {
+            Ok(_) => n,
+            Err(e) => match (e.valid_up_to(), e.error_len()) {
+                (0, Some(bad)) => {
+                    self.skip(bad);
+                    return Err(StaticFifoError::Malformed);
+                },
+                (v, _) => v,
+            },
+        };
+        self.skip(valid);
+        // checked just above.
+        Ok(core::str::from_utf8(&out[..valid]).unwrap_or(""))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn utf8_text() {
+        println!("##################### TEXT TEST ######################################");
+
+        let mut fifo : StaticFifoU8<16> = StaticFifoU8::new();
+        let mut out = [0u8; 16];
+
+        fifo.push_str("héllo").unwrap();
+        assert!(fifo.push_str("0123456789") == Err(StaticFifoError::Full) && fifo.len() == 6, "partial text pushed");
+        assert!(fifo.read_str_into(&mut out) == Ok("héllo"), "text wrong");
+        assert!(fifo.read_str_into(&mut out) == Err(StaticFifoError::Empty), "read on empty fifo");
+
+        // "€" arriving one byte at a time
+        let euro = "€".as_bytes();
+        fifo.put(b'a').unwrap();
+        fifo.put(euro[0]).unwrap();
+        assert!(fifo.read_str_into(&mut out) == Ok("a") && fifo.len() == 1, "partial character handed out");
+        fifo.put(euro[1]).unwrap();
+        assert!(fifo.read_str_into(&mut out) == Ok(""), "partial character handed out");
+        fifo.put(euro[2]).unwrap();
+        assert!(fifo.read_str_into(&mut out) == Ok("€"), "completed character wrong");
+
+        // out too short splits on a character boundary
+        fifo.push_str("aé").unwrap();
+        assert!(fifo.read_str_into(&mut out[..2]) == Ok("a"), "character split by short buffer");
+        assert!(fifo.read_str_into(&mut out[..2]) == Ok("é"), "rest of text wrong");
+
+        // invalid bytes are dropped
+        fifo.put(b'x').unwrap();
+        fifo.put(0xFF).unwrap();
+        fifo.put(b'y').unwrap();
+        assert!(fifo.read_str_into(&mut out) == Ok("x"), "text before bad byte wrong");
+        assert!(fifo.read_str_into(&mut out) == Err(StaticFifoError::Malformed), "bad byte not reported");
+        assert!(fifo.read_str_into(&mut out) == Ok("y"), "text after bad byte wrong");
+    }
+}
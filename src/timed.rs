@@ -0,0 +1,239 @@
+////////////////////////////////////////////////////////////////
+// TimedFifo
+//
+// Fifo that stamps every element with the time it was put,
+// taken from a user supplied Clock. The stamp comes back out
+// with the element, and the age of the oldest element can be
+// checked without removing it, so consumers can enforce
+// latency budgets or throw away stale data.
+//
+// With a TTL set (with_ttl()) elements older than the TTL are
+// skipped by peek() and dropped (and counted) by get() /
+// expire(), so a slow consumer only ever sees data that is
+// still fresh.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+
+// source of timestamps. ticks are in whatever unit the clock counts (ms, timer ticks ..)
+// and are allowed to wrap, ages are worked out with wrapping math.
+pub trait Clock {
+    fn now(&self) -> u32;
+}
+
+// any closure / fn returning the current tick count is a clock.
+impl<F : Fn() -> u32> Clock for F {
+    fn now(&self) -> u32 {
+        self()
+    }
+}
+
+// note length specified is raw storage container.
+// fifo full pointers take up 1 element. so you will need N+1
+pub struct TimedFifo<T, C, const N : usize> {
+    buf: [(T, u32); N],
+    read_ptr: usize,
+    write_ptr: usize,
+    clock: C,
+    ttl: Option<u32>,
+    expired: usize,
+}
+
+impl<T : Copy + Default, C : Clock, const N : usize> TimedFifo<T, C, N> {
+
+    pub fn new(clock : C) -> Self {
+        TimedFifo { buf: [(T::default(), 0); N], read_ptr: 0, write_ptr: 0, clock, ttl: None, expired: 0 }
+    }
+
+    // fifo whose elements expire once they are more than ttl ticks old.
+    pub fn with_ttl(clock : C, ttl : u32) -> Self {
+        let mut rv = Self::new(clock);
+        rv.ttl = Some(ttl);
+        rv
+    }
+
+    pub fn set_ttl(&mut self, ttl : Option<u32>) {
+        self.ttl = ttl;
+    }
+
+    pub fn ttl(&self) -> Option<u32> {
+        self.ttl
+    }
+
+    // number of elements dropped for being older than the TTL.
+    pub fn expired(&self) -> usize {
+        self.expired
+    }
+
+    pub fn reset_expired(&mut self) {
+        self.expired = 0;
+    }
+
+    // drop every element at the front that is older than the TTL, returns how many went.
+    // get() does this on its own, call it to free space ahead of time.
+    pub fn expire(&mut self) -> usize {
+        let n = self.stale();
+        self.read_ptr = (self.read_ptr + n) % N;
+        self.expired += n;
+        n
+    }
+
+    // number of elements at the front that are older than the TTL.
+    fn stale(&self) -> usize {
+        let ttl = match self.ttl {
+            Some(t) => t,
+            None => return 0,
+        };
+        let now = self.clock.now();
+        let mut n : usize = 0;
+        while n < self.len() && now.wrapping_sub(self.buf[(self.read_ptr + n) % N].1) > ttl {
+            n += 1;
+        }
+        n
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.read_ptr == self.write_ptr
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        let rp1 : usize = (self.write_ptr+1) % N;
+        rp1 == self.read_ptr
+    }
+
+    pub fn len(&self) -> usize {
+        if self.read_ptr > self.write_ptr {
+            (N - self.read_ptr) + self.write_ptr
+        } else {
+            self.write_ptr - self.read_ptr
+        }
+    }
+
+    // number of elements that can still be put before the fifo is full.
+    pub fn free_space(&self) -> usize {
+        (N - 1) - self.len()
+    }
+
+    // queue data stamped with the current clock time.
+    #[inline]
+    pub fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        if self.is_full() {
+            return Err(StaticFifoError::Full);
+        }
+        self.buf[self.write_ptr] = (data, self.clock.now());
+        self.write_ptr = (self.write_ptr + 1) % N;
+        Ok(())
+    }
+
+    // remove the oldest element, returns it with the time it was put.
+    // expired elements are dropped first, Empty if nothing fresh is left.
+    #[inline]
+    pub fn get(&mut self) -> Result<(T, u32), StaticFifoError> {
+        self.expire();
+        let rv = self.front()?;
+        self.read_ptr = (self.read_ptr + 1) % N;
+        Ok(rv)
+    }
+
+    // the oldest fresh element and its timestamp, without removing anything.
+    // expired elements are skipped but left in place for get() / expire() to drop.
+    #[inline]
+    pub fn peek(&self) -> Result<(T, u32), StaticFifoError> {
+        let n = self.stale();
+        if n == self.len() {
+            return Err(StaticFifoError::Empty);
+        }
+        Ok(self.buf[(self.read_ptr + n) % N])
+    }
+
+    #[inline]
+    fn front(&self) -> Result<(T, u32), StaticFifoError> {
+        if self.is_empty() {
+            return Err(StaticFifoError::Empty);
+        }
+        Ok(self.buf[self.read_ptr])
+    }
+
+    // ticks since the oldest element was put, None if the fifo is empty.
+    // does not expire anything, the age can be past the TTL.
+    pub fn age_of_front(&self) -> Option<u32> {
+        self.front().ok().map(|(_, t)| self.clock.now().wrapping_sub(t))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use core::cell::Cell;
+    use super::*;
+
+    #[test]
+    fn timed_fifo_stamps() {
+        println!("##################### TIMED FIFO TEST ######################################");
+
+        let ticks = Cell::new(u32::MAX - 1);
+        let mut fifo : TimedFifo<u16, _, 4> = TimedFifo::new(|| ticks.get());
+
+        assert!(fifo.age_of_front().is_none(), "empty fifo has an age");
+        fifo.put(100).unwrap();
+        ticks.set(ticks.get().wrapping_add(3));
+        fifo.put(200).unwrap();
+        fifo.put(300).unwrap();
+        assert!(fifo.put(400) == Err(StaticFifoError::Full), "put on full fifo");
+
+        // age works across the tick counter wrapping
+        ticks.set(ticks.get().wrapping_add(5));
+        assert!(fifo.age_of_front() == Some(8), "front age wrong");
+        assert!(fifo.get() == Ok((100, u32::MAX - 1)), "first element or stamp wrong");
+        assert!(fifo.age_of_front() == Some(5), "age after get wrong");
+        assert!(fifo.peek() == Ok((200, 1)), "peek wrong");
+        assert!(fifo.len() == 2, "peek removed an element");
+    }
+
+    #[test]
+    fn timed_fifo_ttl() {
+        println!("##################### TIMED FIFO TTL TEST ######################################");
+
+        let ticks = Cell::new(0);
+        let mut fifo : TimedFifo<u8, _, 8> = TimedFifo::with_ttl(|| ticks.get(), 10);
+
+        fifo.put(1).unwrap();
+        fifo.put(2).unwrap();
+        ticks.set(6);
+        fifo.put(3).unwrap();
+
+        // exactly ttl old is still fresh
+        ticks.set(10);
+        assert!(fifo.peek() == Ok((1, 0)), "element at the ttl expired");
+        ticks.set(11);
+        assert!(fifo.peek() == Ok((3, 6)), "peek returned a stale element");
+        assert!(fifo.len() == 3 && fifo.expired() == 0, "peek dropped stale elements");
+        assert!(fifo.age_of_front() == Some(11), "age_of_front expired elements");
+        assert!(fifo.get() == Ok((3, 6)), "stale elements not skipped");
+        assert!(fifo.expired() == 2, "expired count wrong");
+
+        fifo.put(4).unwrap();
+        ticks.set(30);
+        assert!(fifo.peek() == Err(StaticFifoError::Empty), "peek returned a stale element");
+        assert!(fifo.expire() == 1 && fifo.is_empty(), "expire left data behind");
+        fifo.put(4).unwrap();
+        ticks.set(50);
+        assert!(fifo.get() == Err(StaticFifoError::Empty), "stale element returned");
+        assert!(fifo.expired() == 4 && fifo.is_empty(), "get left stale data behind");
+
+        // ttl off keeps everything
+        fifo.set_ttl(None);
+        fifo.put(5).unwrap();
+        ticks.set(1000);
+        assert!(fifo.get() == Ok((5, 50)), "element expired with ttl off");
+    }
+}
@@ -0,0 +1,190 @@
+////////////////////////////////////////////////////////////////
+// TMR fifo
+//
+// Triple modular redundancy word fifo. Every element and both
+// pointers are kept in three copies and read back through a
+// bitwise majority vote, so any upset that only touches one
+// copy of a bit is voted out. Meant for short critical queues
+// (e.g. spacecraft commands), it costs 3x the memory and a
+// vote per access.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+
+// bitwise 2 out of 3 vote.
+#[inline]
+pub fn vote_u32(v : [u32; 3]) -> u32 {
+    (v[0] & v[1]) | (v[0] & v[2]) | (v[1] & v[2])
+}
+
+#[inline]
+fn vote_usize(v : [usize; 3]) -> usize {
+    (v[0] & v[1]) | (v[0] & v[2]) | (v[1] & v[2])
+}
+
+// note length specified is raw storage container.
+// fifo full pointers take up 1 element. so you will need N+1
+// the three copies live in separate arrays so one burst can not reach all of them.
+pub struct TmrFifoU32<const N : usize> {
+    buf: [[u32; N]; 3],
+    read_ptr: [usize; 3],
+    write_ptr: [usize; 3],
+}
+
+impl<const N : usize> Default for TmrFifoU32<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N : usize> TmrFifoU32<N> {
+
+    // create new empty TmrFifoU32
+    pub const fn new() -> Self {
+        TmrFifoU32 { buf: [[0; N]; 3], read_ptr: [0; 3], write_ptr: [0; 3] }
+    }
+
+    #[inline]
+    pub fn init(&mut self) {
+        self.read_ptr = [0; 3];
+        self.write_ptr = [0; 3];
+    }
+
+    // voted pointers, a pointer that votes out of range is wrapped back into the buffer.
+    #[inline]
+    fn read_ptr(&self) -> usize {
+        vote_usize(self.read_ptr) % N
+    }
+
+    #[inline]
+    fn write_ptr(&self) -> usize {
+        vote_usize(self.write_ptr) % N
+    }
+
+    #[inline]
+    fn element(&self, i : usize) -> u32 {
+        vote_u32([self.buf[0][i], self.buf[1][i], self.buf[2][i]])
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.read_ptr() == self.write_ptr()
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        let rp1 : usize = (self.write_ptr()+1) % N;
+        rp1 == self.read_ptr()
+    }
+
+    pub fn len(&self) -> usize {
+        let (rp, wp) = (self.read_ptr(), self.write_ptr());
+        if rp > wp {
+            (N - rp) + wp
+        } else {
+            wp - rp
+        }
+    }
+
+    pub fn max_len(&self) -> usize {
+        N
+    }
+
+    // number of elements that can still be put before the fifo is full.
+    pub fn free_space(&self) -> usize {
+        (N - 1) - self.len()
+    }
+
+    #[inline]
+    pub fn get(&mut self) -> Result<u32, StaticFifoError> {
+        if self.is_empty() {
+            return Err(StaticFifoError::Empty);
+        }
+        let rp = self.read_ptr();
+        let rv = self.element(rp);
+        self.read_ptr = [(rp + 1) % N; 3];
+        Ok(rv)
+    }
+
+    #[inline]
+    pub fn put(&mut self, data : u32) -> Result<(), StaticFifoError> {
+        if self.is_full() {
+            return Err(StaticFifoError::Full);
+        }
+        let wp = self.write_ptr();
+        for copy in self.buf.iter_mut() {
+            copy[wp] = data;
+        }
+        self.write_ptr = [(wp + 1) % N; 3];
+        Ok(())
+    }
+
+    // look at the element i places from the read side without removing it.
+    pub fn peek_at(&self, i : usize) -> Result<u32, StaticFifoError> {
+        if i >= self.len() {
+            return Err(StaticFifoError::Empty);
+        }
+        Ok(self.element((self.read_ptr() + i) % N))
+    }
+
+    // rewrite every copy that disagrees with the vote (pointers and queued elements),
+    // returns how many words were repaired. call it periodically so upsets do not pile up
+    // in a second copy of the same word.
+    pub fn scrub(&mut self) -> usize {
+        let (rp, wp) = (self.read_ptr(), self.write_ptr());
+        let mut repaired = self.read_ptr.iter().filter(|&&p| p != rp).count()
+            + self.write_ptr.iter().filter(|&&p| p != wp).count();
+        self.read_ptr = [rp; 3];
+        self.write_ptr = [wp; 3];
+        for i in 0..self.len() {
+            let idx = (self.read_ptr() + i) % N;
+            let v = self.element(idx);
+            for copy in self.buf.iter_mut() {
+                if copy[idx] != v {
+                    copy[idx] = v;
+                    repaired += 1;
+                }
+            }
+        }
+        repaired
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn tmr_fifo_vote_and_scrub() {
+        println!("##################### TMR FIFO TEST ######################################");
+
+        let mut fifo : TmrFifoU32<8> = TmrFifoU32::new();
+
+        for v in [0x1ACF_FC1D, 2, 3] {
+            fifo.put(v).unwrap();
+        }
+
+        // different bits flipped in every copy of the first element are all voted out
+        fifo.buf[0][0] ^= 0x0000_0001;
+        fifo.buf[1][0] ^= 0x0001_0000;
+        fifo.buf[2][0] ^= 0x8000_0000;
+        // one copy of each pointer upset
+        fifo.read_ptr[1] = 5;
+        fifo.write_ptr[2] = 7;
+        assert!(fifo.len() == 3, "voted length wrong");
+        assert!(fifo.peek_at(0) == Ok(0x1ACF_FC1D), "element not voted");
+
+        assert!(fifo.scrub() == 5, "scrub repaired count wrong");
+        assert!(fifo.scrub() == 0, "second scrub found damage");
+        assert!(fifo.buf.iter().all(|c| c[0] == 0x1ACF_FC1D), "copies not rewritten");
+
+        assert!(fifo.get() == Ok(0x1ACF_FC1D), "first element wrong");
+        assert!(fifo.get() == Ok(2), "second element wrong");
+        assert!(fifo.get() == Ok(3), "third element wrong");
+        assert!(fifo.get() == Err(StaticFifoError::Empty), "get on empty fifo");
+    }
+}
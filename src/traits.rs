@@ -0,0 +1,229 @@
+////////////////////////////////////////////////////////////////
+// Producer / consumer traits
+//
+// The two sides of a fifo as traits, so application code can
+// be written against "something I put T into" / "something I
+// get T out of" and the queue behind it (plain, ECC, TMR,
+// deque ..) can be swapped without touching that code.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticDeque, StaticFifoError};
+
+pub trait FifoProducer<T> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError>;
+
+    // number of elements that can still be put.
+    fn free_space(&self) -> usize;
+
+    fn is_full(&self) -> bool {
+        self.free_space() == 0
+    }
+}
+
+pub trait FifoConsumer<T> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError>;
+
+    // number of elements queued.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // next element, None for any error (mostly Empty).
+    fn get_opt(&mut self) -> Option<T> {
+        self.get().ok()
+    }
+
+    // next element, or default if there is none.
+    fn get_or(&mut self, default : T) -> T {
+        self.get().unwrap_or(default)
+    }
+}
+
+impl<T, P : FifoProducer<T> + ?Sized> FifoProducer<T> for &mut P {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        (**self).put(data)
+    }
+
+    fn free_space(&self) -> usize {
+        (**self).free_space()
+    }
+}
+
+impl<T, C : FifoConsumer<T> + ?Sized> FifoConsumer<T> for &mut C {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        (**self).get()
+    }
+
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+}
+
+impl<const N : usize> FifoProducer<u8> for StaticFifoU8<N> {
+
+    fn put(&mut self, data : u8) -> Result<(), StaticFifoError> {
+        StaticFifoU8::put(self, data)
+    }
+
+    fn free_space(&self) -> usize {
+        StaticFifoU8::free_space(self)
+    }
+}
+
+impl<const N : usize> FifoConsumer<u8> for StaticFifoU8<N> {
+
+    fn get(&mut self) -> Result<u8, StaticFifoError> {
+        StaticFifoU8::get(self)
+    }
+
+    fn len(&self) -> usize {
+        StaticFifoU8::len(self)
+    }
+}
+
+impl<const N : usize> FifoProducer<u32> for StaticFifoU32<N> {
+
+    fn put(&mut self, data : u32) -> Result<(), StaticFifoError> {
+        StaticFifoU32::put(self, data)
+    }
+
+    fn free_space(&self) -> usize {
+        StaticFifoU32::free_space(self)
+    }
+}
+
+impl<const N : usize> FifoConsumer<u32> for StaticFifoU32<N> {
+
+    fn get(&mut self) -> Result<u32, StaticFifoError> {
+        StaticFifoU32::get(self)
+    }
+
+    fn len(&self) -> usize {
+        StaticFifoU32::len(self)
+    }
+}
+
+// used as a fifo, in at the back and out at the front.
+impl<T : Copy + Default, const N : usize> FifoProducer<T> for StaticDeque<T, N> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        self.push_back(data)
+    }
+
+    fn free_space(&self) -> usize {
+        StaticDeque::free_space(self)
+    }
+}
+
+impl<T : Copy + Default, const N : usize> FifoConsumer<T> for StaticDeque<T, N> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        self.pop_front()
+    }
+
+    fn len(&self) -> usize {
+        StaticDeque::len(self)
+    }
+}
+
+#[cfg(feature = "ecc")]
+impl<const N : usize> FifoProducer<u8> for crate::EccFifoU8<N> {
+
+    fn put(&mut self, data : u8) -> Result<(), StaticFifoError> {
+        crate::EccFifoU8::put(self, data)
+    }
+
+    fn free_space(&self) -> usize {
+        crate::EccFifoU8::free_space(self)
+    }
+}
+
+#[cfg(feature = "ecc")]
+impl<const N : usize> FifoConsumer<u8> for crate::EccFifoU8<N> {
+
+    fn get(&mut self) -> Result<u8, StaticFifoError> {
+        crate::EccFifoU8::get(self)
+    }
+
+    fn len(&self) -> usize {
+        crate::EccFifoU8::len(self)
+    }
+}
+
+#[cfg(feature = "tmr")]
+impl<const N : usize> FifoProducer<u32> for crate::TmrFifoU32<N> {
+
+    fn put(&mut self, data : u32) -> Result<(), StaticFifoError> {
+        crate::TmrFifoU32::put(self, data)
+    }
+
+    fn free_space(&self) -> usize {
+        crate::TmrFifoU32::free_space(self)
+    }
+}
+
+#[cfg(feature = "tmr")]
+impl<const N : usize> FifoConsumer<u32> for crate::TmrFifoU32<N> {
+
+    fn get(&mut self) -> Result<u32, StaticFifoError> {
+        crate::TmrFifoU32::get(self)
+    }
+
+    fn len(&self) -> usize {
+        crate::TmrFifoU32::len(self)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    // application code that only knows the traits
+    fn forward<T, C : FifoConsumer<T>, P : FifoProducer<T>>(mut from : C, mut to : P) -> usize {
+        let mut n : usize = 0;
+        while !from.is_empty() && !to.is_full() {
+            let _ = from.get().and_then(|v| to.put(v));
+            n += 1;
+        }
+        n
+    }
+
+    #[test]
+    fn producer_consumer_traits() {
+        println!("##################### PRODUCER CONSUMER TRAIT TEST ######################################");
+
+        let mut rx : StaticFifoU32<8> = StaticFifoU32::new();
+        let mut dq : StaticDeque<u32, 4> = StaticDeque::new();
+        for v in 1..=5 {
+            rx.put(v).unwrap();
+        }
+        assert!(forward(&mut rx, &mut dq) == 3, "moved count wrong");
+        assert!(dq.pop_front() == Ok(1) && rx.len() == 2, "wrong data forwarded");
+
+        let mut a : StaticFifoU8<4> = StaticFifoU8::new();
+        let mut b : StaticFifoU8<4> = StaticFifoU8::new();
+        a.put(7).unwrap();
+        assert!(forward(&mut a, &mut b) == 1 && b.get() == Ok(7), "byte forward wrong");
+    }
+
+    #[test]
+    fn option_accessors() {
+        println!("##################### GET OPT TEST ######################################");
+
+        let mut fifo : StaticFifoU8<4> = StaticFifoU8::new();
+        fifo.put(5).unwrap();
+        fifo.put(6).unwrap();
+        assert!(fifo.get_opt() == Some(5) && fifo.get_or(0) == 6, "queued data not returned");
+        assert!(fifo.get_opt().is_none() && fifo.get_or(0xFF) == 0xFF, "empty fifo gave data");
+    }
+}
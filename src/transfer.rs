@@ -0,0 +1,106 @@
+////////////////////////////////////////////////////////////////
+// Fifo to fifo transfer
+//
+// Moves queued elements from one fifo straight into another
+// (e.g. parsed bytes from an rx fifo into a per session fifo)
+// with at most four block copies, one per side of the wrap in
+// each fifo, instead of a get / put per element.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError};
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // copy data into the free space, caller has checked it fits.
+    pub(crate) fn copy_in(&mut self, data : &[u8]) {
+        let first = core::cmp::min(data.len(), self.capacity - self.write_ptr);
+        self.buf[self.write_ptr..(self.write_ptr + first)].copy_from_slice(&data[..first]);
+        self.buf[..(data.len() - first)].copy_from_slice(&data[first..]);
+        self.write_ptr = (self.write_ptr + data.len()) % self.capacity;
+    }
+
+    // put data all or nothing, Full without touching the fifo if it does not fit.
+    pub(crate) fn put_all(&mut self, data : &[u8]) -> Result<(), StaticFifoError> {
+        if data.len() > self.free_space() {
+            return Err(StaticFifoError::Full);
+        }
+        self.copy_in(data);
+        Ok(())
+    }
+
+    // move up to n elements from the read side of self to the write side of other.
+    // stops early when self runs dry or other fills up, returns how many were moved.
+    pub fn transfer_to<const M : usize>(&mut self, other : &mut StaticFifoU8<M>, n : usize) -> usize {
+        let n = core::cmp::min(n, core::cmp::min(self.len(), other.free_space()));
+        let (a, b) = self.as_slices();
+        let first = core::cmp::min(n, a.len());
+        other.copy_in(&a[..first]);
+        other.copy_in(&b[..(n - first)]);
+        self.skip(n)
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // copy data into the free space, caller has checked it fits.
+    pub(crate) fn copy_in(&mut self, data : &[u32]) {
+        let first = core::cmp::min(data.len(), self.capacity - self.write_ptr);
+        self.buf[self.write_ptr..(self.write_ptr + first)].copy_from_slice(&data[..first]);
+        self.buf[..(data.len() - first)].copy_from_slice(&data[first..]);
+        self.write_ptr = (self.write_ptr + data.len()) % self.capacity;
+    }
+
+    // move up to n elements from the read side of self to the write side of other.
+    // stops early when self runs dry or other fills up, returns how many were moved.
+    pub fn transfer_to<const M : usize>(&mut self, other : &mut StaticFifoU32<M>, n : usize) -> usize {
+        let n = core::cmp::min(n, core::cmp::min(self.len(), other.free_space()));
+        let (a, b) = self.as_slices();
+        let first = core::cmp::min(n, a.len());
+        other.copy_in(&a[..first]);
+        other.copy_in(&b[..(n - first)]);
+        self.skip(n)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn fifo_transfer() {
+        println!("##################### TRANSFER TEST ######################################");
+
+        let mut rx : StaticFifoU8<8> = StaticFifoU8::new();
+        let mut session : StaticFifoU8<6> = StaticFifoU8::new();
+
+        // wrap both fifos so every copy is split
+        for _ in 0..5 {
+            rx.put(0).unwrap();
+        }
+        rx.skip(5);
+        for b in 1..=7 {
+            rx.put(b).unwrap();
+        }
+        session.put(0).unwrap();
+        session.put(0).unwrap();
+        session.put(0).unwrap();
+        session.skip(3);
+
+        assert!(rx.transfer_to(&mut session, 4) == 4, "moved count wrong");
+        assert!(rx.len() == 3 && session.len() == 4, "lengths wrong after transfer");
+        assert!((0..4).all(|i| session.peek_at(i) == Ok(i as u8 + 1)), "transferred data wrong");
+
+        // stops when the destination is full
+        assert!(rx.transfer_to(&mut session, 10) == 1, "overfilled destination");
+        assert!(rx.peek_at(0) == Ok(6), "source read side wrong");
+
+        let mut a : StaticFifoU32<4> = StaticFifoU32::new();
+        let mut b : StaticFifoU32<8> = StaticFifoU32::new();
+        a.put(7).unwrap();
+        assert!(a.transfer_to(&mut b, 5) == 1 && b.get() == Ok(7), "word transfer wrong");
+    }
+}
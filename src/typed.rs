@@ -0,0 +1,151 @@
+////////////////////////////////////////////////////////////////
+// TypedFifo
+//
+// Queue of serde messages (e.g. an event enum) kept postcard
+// encoded in a FrameFifo. Messages only take up their encoded
+// size, so variants of very different sizes can share one
+// statically allocated queue.
+//
+///////////////////////////////////////////////////////////////
+
+use core::marker::PhantomData;
+
+use serde::{Serialize, de::DeserializeOwned};
+use postcard::ser_flavors::Flavor;
+
+use crate::{FrameFifo, StaticFifoError};
+use crate::framefifo::{MessageWriter, RecordCrc};
+
+// postcard output flavor that encodes straight into a FrameFifo message.
+struct WriterFlavor<'a, const N : usize> {
+    writer: MessageWriter<'a, N>,
+}
+
+impl<'a, const N : usize> Flavor for WriterFlavor<'a, N> {
+    type Output = MessageWriter<'a, N>;
+
+    fn try_extend(&mut self, data : &[u8]) -> postcard::Result<()> {
+        self.writer.write(data).map_err(|_| postcard::Error::SerializeBufferFull)
+    }
+
+    fn try_push(&mut self, data : u8) -> postcard::Result<()> {
+        self.try_extend(&[data])
+    }
+
+    fn finalize(self) -> postcard::Result<Self::Output> {
+        Ok(self.writer)
+    }
+}
+
+// note N is raw byte storage, see FrameFifo for the per message overhead.
+pub struct TypedFifo<M, const N : usize> {
+    frames: FrameFifo<N>,
+    _msg: PhantomData<M>,
+}
+
+impl<M, const N : usize> Default for TypedFifo<M, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M, const N : usize> TypedFifo<M, N> {
+
+    pub const fn new() -> Self {
+        Self::with_crc(RecordCrc::None)
+    }
+
+    // queue whose messages carry a crc, a damaged message pops as Corrupt.
+    pub const fn with_crc(crc : RecordCrc) -> Self {
+        TypedFifo { frames: FrameFifo::with_crc(crc), _msg: PhantomData }
+    }
+
+    // number of messages queued.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    // bytes of storage in use, including the per message overhead.
+    pub fn bytes_used(&self) -> usize {
+        self.frames.bytes_used()
+    }
+
+    // drop the next message without decoding it.
+    pub fn discard(&mut self) -> Result<(), StaticFifoError> {
+        self.frames.discard().map(|_| ())
+    }
+}
+
+impl<M : Serialize, const N : usize> TypedFifo<M, N> {
+
+    // encode msg into the queue, all or nothing. Full if the encoded message does not fit,
+    // Malformed if serde could not encode it at all.
+    pub fn push(&mut self, msg : &M) -> Result<(), StaticFifoError> {
+        let writer = self.frames.writer()?;
+        match postcard::serialize_with_flavor(msg, WriterFlavor { writer }) {
+            Ok(w) => {
+                w.finish();
+                Ok(())
+            },
+            Err(postcard::Error::SerializeBufferFull) => Err(StaticFifoError::Full),
+            Err(_) => Err(StaticFifoError::Malformed),
+        }
+    }
+}
+
+impl<M : DeserializeOwned, const N : usize> TypedFifo<M, N> {
+
+    // remove and decode the next message. a message that does not decode as M
+    // is dropped and reported as Malformed, one that fails its crc as Corrupt.
+    pub fn pop(&mut self) -> Result<M, StaticFifoError> {
+        self.frames.pop_with(|b| postcard::from_bytes::<M>(b))?
+            .map_err(|_| StaticFifoError::Malformed)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use serde::Deserialize;
+    use super::*;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Event {
+        Tick,
+        Key(u8),
+        Reading { channel: u8, value: i32 },
+        Name([u8; 6]),
+    }
+
+    #[test]
+    fn typed_fifo_functional() {
+        println!("##################### TYPED FIFO TEST ######################################");
+
+        let mut q : TypedFifo<Event, 22> = TypedFifo::new();
+
+        assert!(q.pop() == Err(StaticFifoError::Empty), "pop on empty queue");
+        q.push(&Event::Tick).unwrap();
+        q.push(&Event::Key(7)).unwrap();
+        q.push(&Event::Reading { channel: 2, value: -100_000 }).unwrap();
+        assert!(q.len() == 3, "message count wrong");
+        println!("3 events use {} bytes", q.bytes_used());
+
+        // does not fit, queue is left alone
+        let used = q.bytes_used();
+        assert!(q.push(&Event::Name(*b"abcdef")) == Err(StaticFifoError::Full), "overfull push accepted");
+        assert!(q.len() == 3 && q.bytes_used() == used, "failed push modified the queue");
+
+        assert!(q.pop() == Ok(Event::Tick), "first event wrong");
+        assert!(q.pop() == Ok(Event::Key(7)), "second event wrong");
+        q.push(&Event::Name(*b"abcdef")).unwrap();
+        assert!(q.pop() == Ok(Event::Reading { channel: 2, value: -100_000 }), "third event wrong");
+        assert!(q.pop() == Ok(Event::Name(*b"abcdef")), "wrapped event wrong");
+        assert!(q.is_empty(), "queue should be empty");
+    }
+}
@@ -0,0 +1,44 @@
+////////////////////////////////////////////////////////////////
+// ufmt support
+//
+// uWrite for StaticFifoU8 so uwrite! / uwriteln! can format
+// straight into the fifo on targets that use ufmt to stay
+// clear of the core::fmt machinery.
+//
+///////////////////////////////////////////////////////////////
+
+use ufmt_write::uWrite;
+
+use crate::{StaticFifoU8, StaticFifoError};
+
+impl<const N : usize> uWrite for StaticFifoU8<N> {
+    type Error = StaticFifoError;
+
+    // each piece of text goes in whole or not at all, Full if it does not fit.
+    fn write_str(&mut self, s : &str) -> Result<(), StaticFifoError> {
+        self.put_all(s.as_bytes())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use ufmt::uwrite;
+    use super::*;
+
+    #[test]
+    fn ufmt_into_fifo() {
+        println!("##################### UFMT TEST ######################################");
+
+        let mut fifo : StaticFifoU8<16> = StaticFifoU8::new();
+        uwrite!(fifo, "t={} v={}", 42u32, -7i16).unwrap();
+        let mut out = [0u8; 16];
+        let n = fifo.copy_to_slice(&mut out);
+        assert!(&out[..n] == b"t=42 v=-7", "formatted text wrong");
+
+        assert!(uwrite!(fifo, "{}", "too long to fit") == Err(StaticFifoError::Full), "overfull write accepted");
+        assert!(fifo.len() == 9, "failed write left partial text");
+    }
+}
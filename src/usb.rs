@@ -0,0 +1,204 @@
+////////////////////////////////////////////////////////////////
+// USB serial buffering
+//
+// A tx and an rx byte fifo pumped through a CDC-ACM class from
+// the usb-device stack, so serial firmware only deals with the
+// fifos and calls poll() next to UsbDevice::poll().
+//
+// Packet aware: each poll sends at most one IN packet of up to
+// max_packet_size bytes, and a transfer that ends on a full
+// packet is closed with a zero length packet so the host does
+// not sit on the data. An OUT packet is only read when the rx
+// fifo has room for a whole one; until then the endpoint keeps
+// NAKing, which is the backpressure towards the host.
+//
+// The class side is the CdcPackets trait, the three calls of
+// usbd_serial::CdcAcmClass (impl it by forwarding). A pair of
+// raw usb-device bulk endpoints implements it already.
+// Full speed packets, at most 64 bytes.
+//
+///////////////////////////////////////////////////////////////
+
+use usb_device::UsbError;
+use usb_device::bus::UsbBus;
+use usb_device::endpoint::{EndpointIn, EndpointOut};
+
+use crate::StaticFifoU8;
+use crate::bytefifo::ByteFifo;
+
+// largest full speed bulk packet
+const MAX_PACKET : usize = 64;
+
+// packet level access to a CDC-ACM data interface.
+pub trait CdcPackets {
+    fn max_packet_size(&self) -> u16;
+    // send one packet (data no longer than max_packet_size), returns bytes taken.
+    fn write_packet(&mut self, data : &[u8]) -> usb_device::Result<usize>;
+    // take one received packet, returns its length.
+    fn read_packet(&mut self, data : &mut [u8]) -> usb_device::Result<usize>;
+}
+
+impl<'a, B : UsbBus> CdcPackets for (&EndpointIn<'a, B>, &EndpointOut<'a, B>) {
+
+    fn max_packet_size(&self) -> u16 {
+        self.0.max_packet_size()
+    }
+
+    fn write_packet(&mut self, data : &[u8]) -> usb_device::Result<usize> {
+        self.0.write(data)
+    }
+
+    fn read_packet(&mut self, data : &mut [u8]) -> usb_device::Result<usize> {
+        self.1.read(data)
+    }
+}
+
+// TX bytes towards the host, RX bytes from it. the fifos hold TX-1 / RX-1 bytes.
+pub struct UsbSerialBuffers<const TX : usize, const RX : usize> {
+    tx: StaticFifoU8<TX>,
+    rx: StaticFifoU8<RX>,
+    // last packet sent was full, the transfer still needs closing
+    zlp: bool,
+}
+
+impl<const TX : usize, const RX : usize> Default for UsbSerialBuffers<TX, RX> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const TX : usize, const RX : usize> UsbSerialBuffers<TX, RX> {
+
+    // OUT packets are only read with room for a whole one, a smaller rx fifo would never take any.
+    const RX_OK : () = assert!(RX > MAX_PACKET, "UsbSerialBuffers needs RX > 64, the rx fifo has to hold a full packet");
+
+    pub const fn new() -> Self {
+        let () = Self::RX_OK;
+        UsbSerialBuffers { tx: StaticFifoU8::new(), rx: StaticFifoU8::new(), zlp: false }
+    }
+
+    // queue bytes for the host, returns how many fit.
+    pub fn write(&mut self, data : &[u8]) -> usize {
+        self.tx.put_slice(data)
+    }
+
+    // take bytes the host sent, returns how many.
+    pub fn read(&mut self, out : &mut [u8]) -> usize {
+        self.rx.get_slice(out)
+    }
+
+    pub fn tx(&mut self) -> &mut StaticFifoU8<TX> {
+        &mut self.tx
+    }
+
+    pub fn rx(&mut self) -> &mut StaticFifoU8<RX> {
+        &mut self.rx
+    }
+
+    // move one packet each way. a busy endpoint (WouldBlock) is not an error,
+    // that packet just waits for the next poll.
+    pub fn poll<C : CdcPackets>(&mut self, class : &mut C) -> usb_device::Result<()> {
+        let mps = core::cmp::min(usize::from(class.max_packet_size()), MAX_PACKET);
+        let mut buf = [0u8; MAX_PACKET];
+
+        // in
+        if !self.tx.is_empty() || self.zlp {
+            let n = self.tx.copy_to_slice(&mut buf[..mps]);
+            match class.write_packet(&buf[..n]) {
+                Ok(sent) => {
+                    self.tx.skip(sent);
+                    self.zlp = sent == mps;
+                }
+                Err(UsbError::WouldBlock) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        // out, only with room for a whole packet
+        if self.rx.free_space() >= mps {
+            match class.read_packet(&mut buf[..mps]) {
+                Ok(n) => {
+                    self.rx.put_slice(&buf[..n]);
+                }
+                Err(UsbError::WouldBlock) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use std::vec::Vec;
+    use super::*;
+
+    // host side of the wire, 8 byte packets
+    struct FakeCdc {
+        sent: Vec<Vec<u8>>,
+        incoming: Vec<Vec<u8>>,
+        busy: bool,
+    }
+
+    impl CdcPackets for FakeCdc {
+        fn max_packet_size(&self) -> u16 {
+            8
+        }
+
+        fn write_packet(&mut self, data : &[u8]) -> usb_device::Result<usize> {
+            if self.busy {
+                return Err(UsbError::WouldBlock);
+            }
+            self.sent.push(data.to_vec());
+            Ok(data.len())
+        }
+
+        fn read_packet(&mut self, data : &mut [u8]) -> usb_device::Result<usize> {
+            if self.incoming.is_empty() {
+                return Err(UsbError::WouldBlock);
+            }
+            let p = self.incoming.remove(0);
+            data[..p.len()].copy_from_slice(&p);
+            Ok(p.len())
+        }
+    }
+
+    #[test]
+    fn packets_both_ways() {
+        println!("##################### USB SERIAL TEST ######################################");
+
+        let mut cdc = FakeCdc { sent: Vec::new(), incoming: Vec::new(), busy: false };
+        let mut port : UsbSerialBuffers<32, 72> = UsbSerialBuffers::new();
+
+        // 16 bytes: two full packets then a zero length one
+        assert!(port.write(b"0123456789abcdef") == 16, "tx write short");
+        for _ in 0..4 {
+            port.poll(&mut cdc).unwrap();
+        }
+        assert!(cdc.sent == [b"01234567".to_vec(), b"89abcdef".to_vec(), Vec::new()], "in packets wrong");
+
+        // a busy endpoint keeps the data
+        cdc.busy = true;
+        port.write(b"xyz");
+        port.poll(&mut cdc).unwrap();
+        cdc.busy = false;
+        port.poll(&mut cdc).unwrap();
+        assert!(cdc.sent.len() == 4 && cdc.sent[3] == b"xyz", "short packet wrong");
+
+        // rx holds 71: after eight full packets the ninth waits until there is room for it
+        let data : Vec<u8> = (0..72u8).collect();
+        cdc.incoming = data.chunks(8).map(|p| p.to_vec()).collect();
+        for _ in 0..10 {
+            port.poll(&mut cdc).unwrap();
+        }
+        assert!(cdc.incoming.len() == 1 && port.rx().len() == 64, "packet read without room");
+        let mut out = [0u8; 80];
+        let n = port.read(&mut out);
+        port.poll(&mut cdc).unwrap();
+        let m = port.read(&mut out[n..]);
+        assert!(out[..(n + m)] == data[..], "out packets wrong");
+    }
+}
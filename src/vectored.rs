@@ -0,0 +1,81 @@
+////////////////////////////////////////////////////////////////
+// Vectored writes
+//
+// put_vectored() queues several slices back to back (header,
+// payload, crc ..) as one all or nothing write, so a message
+// never has to be assembled in a scratch buffer first and can
+// never be left half queued. Each slice is a block copy, at
+// most two per slice.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32, StaticFifoError};
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // queue every slice in order, Full (nothing queued) if they do not all fit.
+    pub fn put_vectored(&mut self, bufs : &[&[u8]]) -> Result<(), StaticFifoError> {
+        let total : usize = bufs.iter().map(|b| b.len()).sum();
+        if total > self.free_space() {
+            return Err(StaticFifoError::Full);
+        }
+        for b in bufs {
+            self.copy_in(b);
+        }
+        Ok(())
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // queue every slice in order, Full (nothing queued) if they do not all fit.
+    pub fn put_vectored(&mut self, bufs : &[&[u32]]) -> Result<(), StaticFifoError> {
+        let total : usize = bufs.iter().map(|b| b.len()).sum();
+        if total > self.free_space() {
+            return Err(StaticFifoError::Full);
+        }
+        for b in bufs {
+            self.copy_in(b);
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+    use crate::crc::crc16;
+
+    #[test]
+    fn scatter_write() {
+        println!("##################### VECTORED WRITE TEST ######################################");
+
+        // wrap the storage so a slice gets split
+        let mut tx : StaticFifoU8<16> = StaticFifoU8::new();
+        for _ in 0..10 {
+            tx.put(0).unwrap();
+        }
+        tx.skip(10);
+
+        let payload = b"hello";
+        let crc = crc16(payload).to_le_bytes();
+        tx.put_vectored(&[&[0x7E, 5], payload, &crc]).unwrap();
+        assert!(tx.len() == 9, "length wrong");
+        let mut out = [0u8; 9];
+        for o in out.iter_mut() {
+            *o = tx.get().unwrap();
+        }
+        assert!(out[..7] == [0x7E, 5, b'h', b'e', b'l', b'l', b'o'] && out[7..] == crc, "message wrong");
+
+        // one byte short: nothing goes in
+        tx.put_vectored(&[&[1; 10]]).unwrap();
+        assert!(tx.put_vectored(&[&[2; 3], &[3; 3]]) == Err(StaticFifoError::Full) && tx.len() == 10, "partial write");
+
+        let mut words : StaticFifoU32<8> = StaticFifoU32::new();
+        words.put_vectored(&[&[1, 2], &[], &[3]]).unwrap();
+        assert!(words.get() == Ok(1) && words.get() == Ok(2) && words.get() == Ok(3), "word slices wrong");
+    }
+}
@@ -0,0 +1,122 @@
+////////////////////////////////////////////////////////////////
+// Watchdog
+//
+// Wrapper that remembers (from a Clock) when the last put and
+// the last get went through, so supervisory code can spot a
+// stalled producer or consumer and reset the link. Failed
+// calls (Full / Empty ..) do not count as activity.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::timed::Clock;
+use crate::traits::{FifoProducer, FifoConsumer};
+
+pub struct Watchdog<F, C> {
+    fifo: F,
+    clock: C,
+    last_put: u32,
+    last_get: u32,
+}
+
+impl<F, C : Clock> Watchdog<F, C> {
+
+    // both sides count as active at creation.
+    pub fn new(fifo : F, clock : C) -> Self {
+        let now = clock.now();
+        Watchdog { fifo, clock, last_put: now, last_get: now }
+    }
+
+    // ticks since the last successful put.
+    pub fn put_idle_time(&self) -> u32 {
+        self.clock.now().wrapping_sub(self.last_put)
+    }
+
+    // ticks since the last successful get.
+    pub fn get_idle_time(&self) -> u32 {
+        self.clock.now().wrapping_sub(self.last_get)
+    }
+
+    // ticks since either side last did anything.
+    pub fn idle_time(&self) -> u32 {
+        core::cmp::min(self.put_idle_time(), self.get_idle_time())
+    }
+
+    // mark both sides active again, e.g. after the link was reset.
+    pub fn kick(&mut self) {
+        let now = self.clock.now();
+        self.last_put = now;
+        self.last_get = now;
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.fifo
+    }
+
+    pub fn inner_mut(&mut self) -> &mut F {
+        &mut self.fifo
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fifo
+    }
+}
+
+impl<T, F : FifoProducer<T>, C : Clock> FifoProducer<T> for Watchdog<F, C> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        self.fifo.put(data)?;
+        self.last_put = self.clock.now();
+        Ok(())
+    }
+
+    fn free_space(&self) -> usize {
+        self.fifo.free_space()
+    }
+}
+
+impl<T, F : FifoConsumer<T>, C : Clock> FifoConsumer<T> for Watchdog<F, C> {
+
+    fn get(&mut self) -> Result<T, StaticFifoError> {
+        let rv = self.fifo.get()?;
+        self.last_get = self.clock.now();
+        Ok(rv)
+    }
+
+    fn len(&self) -> usize {
+        self.fifo.len()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use core::cell::Cell;
+    use super::*;
+    use crate::StaticFifoU32;
+
+    #[test]
+    fn stall_detection() {
+        println!("##################### WATCHDOG TEST ######################################");
+
+        let ticks = Cell::new(100);
+        let mut link = Watchdog::new(StaticFifoU32::<4>::new(), || ticks.get());
+
+        ticks.set(110);
+        link.put(1).unwrap();
+        ticks.set(150);
+        assert!(link.put_idle_time() == 40 && link.get_idle_time() == 50, "idle times wrong");
+        assert!(link.idle_time() == 40, "combined idle time wrong");
+
+        // the consumer keeps failing on an empty fifo after this, that is not activity
+        link.get().unwrap();
+        ticks.set(200);
+        assert!(link.get() == Err(StaticFifoError::Empty), "get on empty fifo");
+        assert!(link.get_idle_time() == 50, "failed get counted as activity");
+
+        link.kick();
+        assert!(link.idle_time() == 0 && link.put_idle_time() == 0, "kick did not reset");
+    }
+}
@@ -0,0 +1,113 @@
+////////////////////////////////////////////////////////////////
+// Sliding windows
+//
+// Non consuming iterator over every run of n consecutive
+// queued elements, oldest first. A window can straddle the
+// wrap of the storage, so each one comes out as two slices
+// the same way as_slices() does, the second one only used
+// when the window wraps.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32};
+
+// see StaticFifoU8::windows() / StaticFifoU32::windows().
+pub struct Windows<'a, T> {
+    buf: &'a [T],
+    read_ptr: usize,
+    n: usize,
+    pos: usize,
+    count: usize,
+}
+
+impl<'a, T> Windows<'a, T> {
+
+    fn new(buf : &'a [T], read_ptr : usize, len : usize, n : usize) -> Self {
+        let count = if n == 0 || n > len { 0 } else { len - n + 1 };
+        Windows { buf, read_ptr, n, pos: 0, count }
+    }
+}
+
+impl<'a, T> Iterator for Windows<'a, T> {
+    type Item = (&'a [T], &'a [T]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.count {
+            return None;
+        }
+        let cap = self.buf.len();
+        let start = (self.read_ptr + self.pos) % cap;
+        self.pos += 1;
+        if start + self.n <= cap {
+            Some((&self.buf[start..(start + self.n)], &[]))
+        } else {
+            Some((&self.buf[start..], &self.buf[..(start + self.n - cap)]))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.count - self.pos;
+        (left, Some(left))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Windows<'a, T> {}
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // every window of n consecutive queued elements, oldest first. nothing if n is 0 or more than len().
+    pub fn windows(&self, n : usize) -> Windows<'_, u8> {
+        Windows::new(&self.buf[..self.capacity], self.read_ptr, self.len(), n)
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // every window of n consecutive queued elements, oldest first. nothing if n is 0 or more than len().
+    pub fn windows(&self, n : usize) -> Windows<'_, u32> {
+        Windows::new(&self.buf[..self.capacity], self.read_ptr, self.len(), n)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn sliding_windows() {
+        println!("##################### WINDOWS TEST ######################################");
+
+        let mut fifo : StaticFifoU8<6> = StaticFifoU8::new();
+
+        // start near the end so the data wraps
+        for _ in 0..4 {
+            fifo.put(0).unwrap();
+        }
+        fifo.skip(4);
+        for b in 1..=5 {
+            fifo.put(b).unwrap();
+        }
+
+        let w = fifo.windows(3);
+        assert!(w.len() == 3, "window count wrong");
+        let mut seen = [[0u8; 3]; 3];
+        for (k, (a, b)) in fifo.windows(3).enumerate() {
+            assert!(a.len() + b.len() == 3, "window length wrong");
+            for (i, v) in a.iter().chain(b.iter()).enumerate() {
+                seen[k][i] = *v;
+            }
+        }
+        assert!(seen == [[1, 2, 3], [2, 3, 4], [3, 4, 5]], "window contents wrong");
+        assert!(fifo.len() == 5, "windows consumed data");
+
+        assert!(fifo.windows(0).next().is_none() && fifo.windows(6).next().is_none(), "bad window size yielded");
+
+        let mut words : StaticFifoU32<4> = StaticFifoU32::new();
+        words.put(10).unwrap();
+        words.put(20).unwrap();
+        assert!(words.windows(2).eq([(&[10u32, 20][..], &[][..])]), "word window wrong");
+    }
+}
@@ -0,0 +1,76 @@
+////////////////////////////////////////////////////////////////
+// Writer
+//
+// Write only view of a fifo, the counterpart of Inspector.
+// A module handed a Writer can put into the queue and ask how
+// much room is left, nothing more: no get, no len, no peeking
+// at what other producers queued.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::StaticFifoError;
+use crate::traits::FifoProducer;
+
+pub struct Writer<'a, F> {
+    fifo: &'a mut F,
+}
+
+impl<'a, F> Writer<'a, F> {
+
+    pub fn new(fifo : &'a mut F) -> Self {
+        Writer { fifo }
+    }
+
+    pub fn put<T>(&mut self, data : T) -> Result<(), StaticFifoError> where F : FifoProducer<T> {
+        self.fifo.put(data)
+    }
+
+    // number of elements that can still be put.
+    pub fn free_space<T>(&self) -> usize where F : FifoProducer<T> {
+        self.fifo.free_space()
+    }
+}
+
+impl<'a, T, F : FifoProducer<T>> FifoProducer<T> for Writer<'a, F> {
+
+    fn put(&mut self, data : T) -> Result<(), StaticFifoError> {
+        self.fifo.put(data)
+    }
+
+    fn free_space(&self) -> usize {
+        self.fifo.free_space()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+    use crate::StaticFifoU32;
+
+    // a module that may only feed the queue
+    fn sensor_task(out : &mut Writer<'_, StaticFifoU32<4>>, samples : &[u32]) -> usize {
+        let mut sent = 0;
+        for &s in samples {
+            if out.free_space() == 0 || out.put(s).is_err() {
+                break;
+            }
+            sent += 1;
+        }
+        sent
+    }
+
+    #[test]
+    fn write_only_view() {
+        println!("##################### WRITER TEST ######################################");
+
+        let mut fifo : StaticFifoU32<4> = StaticFifoU32::new();
+        fifo.put(100).unwrap();
+        let sent = sensor_task(&mut Writer::new(&mut fifo), &[1, 2, 3, 4]);
+        assert!(sent == 2, "writer did not stop at full");
+        assert!(fifo.get() == Ok(100) && fifo.get() == Ok(1) && fifo.get() == Ok(2), "order wrong");
+        assert!(Writer::new(&mut fifo).put(7u32) == Ok(()) && fifo.get() == Ok(7), "writer put wrong");
+    }
+}
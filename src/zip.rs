@@ -0,0 +1,114 @@
+////////////////////////////////////////////////////////////////
+// Zip
+//
+// Consumer side combinator over two fifos that only ever
+// removes elements in lockstep pairs (e.g. timestamps with
+// their samples). When one side is empty nothing is taken
+// from the other, so the two can not drift apart.
+//
+///////////////////////////////////////////////////////////////
+
+use crate::{StaticFifoU8, StaticFifoU32};
+
+// see StaticFifoU8::zip().
+pub struct ZipU8<'a, const N : usize, const M : usize> {
+    a: &'a mut StaticFifoU8<N>,
+    b: &'a mut StaticFifoU8<M>,
+}
+
+impl<'a, const N : usize, const M : usize> Iterator for ZipU8<'a, N, M> {
+    type Item = (u8, u8);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.a.is_empty() || self.b.is_empty() {
+            return None;
+        }
+        Some((self.a.get().ok()?, self.b.get().ok()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = core::cmp::min(self.a.len(), self.b.len());
+        (n, Some(n))
+    }
+}
+
+impl<'a, const N : usize, const M : usize> ExactSizeIterator for ZipU8<'a, N, M> {}
+
+// see StaticFifoU32::zip().
+pub struct ZipU32<'a, const N : usize, const M : usize> {
+    a: &'a mut StaticFifoU32<N>,
+    b: &'a mut StaticFifoU32<M>,
+}
+
+impl<'a, const N : usize, const M : usize> Iterator for ZipU32<'a, N, M> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.a.is_empty() || self.b.is_empty() {
+            return None;
+        }
+        Some((self.a.get().ok()?, self.b.get().ok()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = core::cmp::min(self.a.len(), self.b.len());
+        (n, Some(n))
+    }
+}
+
+impl<'a, const N : usize, const M : usize> ExactSizeIterator for ZipU32<'a, N, M> {}
+
+impl<const N : usize> StaticFifoU8<N> {
+
+    // pop pairs, one from self and one from other, while both have data.
+    pub fn zip<'a, const M : usize>(&'a mut self, other : &'a mut StaticFifoU8<M>) -> ZipU8<'a, N, M> {
+        ZipU8 { a: self, b: other }
+    }
+}
+
+impl<const N : usize> StaticFifoU32<N> {
+
+    // pop pairs, one from self and one from other, while both have data.
+    pub fn zip<'a, const M : usize>(&'a mut self, other : &'a mut StaticFifoU32<M>) -> ZipU32<'a, N, M> {
+        ZipU32 { a: self, b: other }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use std::println;
+    use super::*;
+
+    #[test]
+    fn zip_lockstep() {
+        println!("##################### ZIP TEST ######################################");
+
+        let mut stamps : StaticFifoU32<8> = StaticFifoU32::new();
+        let mut samples : StaticFifoU32<8> = StaticFifoU32::new();
+        for t in [100, 200, 300] {
+            stamps.put(t).unwrap();
+        }
+        samples.put(7).unwrap();
+        samples.put(8).unwrap();
+
+        let mut pairs = [(0u32, 0u32); 2];
+        let mut n : usize = 0;
+        for p in stamps.zip(&mut samples) {
+            pairs[n] = p;
+            n += 1;
+        }
+        assert!(n == 2 && pairs == [(100, 7), (200, 8)], "pairs wrong");
+        assert!(stamps.len() == 1 && samples.is_empty(), "unpaired element consumed");
+
+        // the late sample pairs up with the stamp that waited for it
+        samples.put(9).unwrap();
+        assert!(stamps.zip(&mut samples).next() == Some((300, 9)), "late pair wrong");
+
+        let mut a : StaticFifoU8<4> = StaticFifoU8::new();
+        let mut b : StaticFifoU8<4> = StaticFifoU8::new();
+        a.put(1).unwrap();
+        assert!(a.zip(&mut b).next().is_none() && a.len() == 1, "byte zip consumed a lone element");
+    }
+}